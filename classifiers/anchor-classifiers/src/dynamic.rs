@@ -0,0 +1,561 @@
+use std::{collections::HashMap, fmt::Write, path::Path, str::FromStr};
+
+use actions::{AnchorInstruction, AnchorInstructionAccount};
+use anchor_lang_idl::{
+    convert::convert_idl,
+    types::{
+        Idl, IdlArrayLen, IdlDefinedFields, IdlGenericArg, IdlInstruction,
+        IdlInstructionAccountItem, IdlSerialization, IdlType, IdlTypeDef, IdlTypeDefGeneric,
+        IdlTypeDefTy,
+    },
+};
+use classifier_core::{
+    json::{safe_integer, ToJsonValue},
+    ClassifiableInstruction, ClassifiableTransaction,
+};
+use classifier_trait::ClassifyInstructionResult;
+use solana_sdk::pubkey::Pubkey;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum DynamicAnchorError {
+    #[error("Failed to read IDL directory: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Unexpected end of instruction data")]
+    UnexpectedEof,
+
+    #[error("Invalid {0} value")]
+    InvalidValue(&'static str),
+
+    #[error("Invalid variant {index} for enum {name}")]
+    InvalidEnumVariant { name: String, index: u8 },
+
+    #[error("Unknown type: {0}")]
+    UnknownType(String),
+
+    #[error("Unknown generic: {0}")]
+    UnknownGeneric(String),
+
+    #[error("Unsupported serialization for type {0}")]
+    UnsupportedSerialization(String),
+
+    #[error("Missing account: {0}")]
+    MissingAccount(String),
+}
+
+type Result<T> = std::result::Result<T, DynamicAnchorError>;
+
+/// Classifies instructions of Anchor programs from IDLs loaded at runtime, so programs
+/// can be decoded without a compiled classifier. Instruction accounts and arguments are
+/// decoded into a generic `AnchorInstruction` action.
+#[derive(Debug, Default)]
+pub struct DynamicAnchorClassifier {
+    programs: HashMap<Pubkey, DynamicProgram>,
+}
+
+#[derive(Debug)]
+struct DynamicProgram {
+    name: String,
+    instructions: Vec<IdlInstruction>,
+    types: HashMap<String, IdlTypeDef>,
+}
+
+impl DynamicAnchorClassifier {
+    /// Loads every `*.json` IDL in `idl_dir`. Both the current and legacy IDL formats are
+    /// accepted. IDLs which can not be parsed or have no program address are skipped.
+    pub fn load(idl_dir: &Path) -> Result<Self> {
+        let mut classifier = Self::default();
+
+        for entry in std::fs::read_dir(idl_dir)? {
+            let path = entry?.path();
+
+            if path.extension().and_then(|extension| extension.to_str()) != Some("json") {
+                continue;
+            }
+
+            let idl = match std::fs::read(&path)
+                .map_err(anyhow::Error::from)
+                .and_then(|buf| convert_idl(&buf))
+            {
+                Ok(idl) => idl,
+                Err(err) => {
+                    tracing::warn!("Skipping IDL {}: {:?}", path.display(), err);
+                    continue;
+                }
+            };
+
+            let program_id = match Pubkey::from_str(&idl.address) {
+                Ok(program_id) => program_id,
+                Err(_) => {
+                    tracing::warn!("Skipping IDL {}: invalid program address", path.display());
+                    continue;
+                }
+            };
+
+            classifier.insert(program_id, idl);
+        }
+
+        tracing::info!("Loaded {} Anchor IDLs", classifier.programs.len());
+
+        Ok(classifier)
+    }
+
+    pub fn insert(&mut self, program_id: Pubkey, idl: Idl) {
+        let types = idl
+            .types
+            .into_iter()
+            .map(|ty| (ty.name.clone(), ty))
+            .collect();
+
+        self.programs.insert(
+            program_id,
+            DynamicProgram {
+                name: idl.metadata.name,
+                instructions: idl.instructions,
+                types,
+            },
+        );
+    }
+
+    pub fn contains(&self, program_id: &Pubkey) -> bool {
+        self.programs.contains_key(program_id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.programs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.programs.is_empty()
+    }
+
+    /// Returns `Ok(None)` if no IDL is loaded for the program, or no instruction of the
+    /// IDL matches the instruction's discriminator.
+    pub fn classify_instruction(
+        &self,
+        program_id: &Pubkey,
+        txn: &ClassifiableTransaction,
+        ix: &ClassifiableInstruction,
+    ) -> ClassifyInstructionResult {
+        let program = match self.programs.get(program_id) {
+            Some(program) => program,
+            None => return Ok(None),
+        };
+
+        // Discriminators are not always 8 bytes, prefer the most specific match
+        let idl_ix = match program
+            .instructions
+            .iter()
+            .filter(|idl_ix| {
+                !idl_ix.discriminator.is_empty() && ix.data.starts_with(&idl_ix.discriminator)
+            })
+            .max_by_key(|idl_ix| idl_ix.discriminator.len())
+        {
+            Some(idl_ix) => idl_ix,
+            None => return Ok(None),
+        };
+
+        let mut accounts = Vec::new();
+        decode_accounts(
+            program_id,
+            txn,
+            ix,
+            &idl_ix.accounts,
+            "",
+            &mut 0,
+            &mut accounts,
+        )?;
+
+        let mut data = &ix.data[idl_ix.discriminator.len()..];
+        let mut args = serde_json::Map::new();
+
+        for arg in &idl_ix.args {
+            let value = program.decode_type(&arg.ty, &mut data, &HashMap::new())?;
+            args.insert(arg.name.clone(), value);
+        }
+
+        Ok(Some(
+            AnchorInstruction {
+                program_id: *program_id,
+                program_name: program.name.clone(),
+                instruction: idl_ix.name.clone(),
+                accounts,
+                args: serde_json::Value::Object(args),
+            }
+            .into(),
+        ))
+    }
+}
+
+/// Names the instruction's accounts in IDL order, walking into composite account
+/// groups. Omitted optional accounts are passed as the program id, and are left out.
+fn decode_accounts(
+    program_id: &Pubkey,
+    txn: &ClassifiableTransaction,
+    ix: &ClassifiableInstruction,
+    items: &[IdlInstructionAccountItem],
+    prefix: &str,
+    position: &mut usize,
+    accounts: &mut Vec<AnchorInstructionAccount>,
+) -> Result<()> {
+    for item in items {
+        match item {
+            IdlInstructionAccountItem::Single(account) => {
+                let name = format!("{}{}", prefix, account.name);
+
+                let pubkey = match txn.get_instruction_account(ix, *position) {
+                    Some(meta) => meta.pubkey,
+                    None => return Err(DynamicAnchorError::MissingAccount(name)),
+                };
+                *position += 1;
+
+                if account.optional && pubkey == *program_id {
+                    continue;
+                }
+
+                accounts.push(AnchorInstructionAccount { name, pubkey });
+            }
+            IdlInstructionAccountItem::Composite(group) => {
+                let prefix = format!("{}{}.", prefix, group.name);
+                decode_accounts(
+                    program_id,
+                    txn,
+                    ix,
+                    &group.accounts,
+                    &prefix,
+                    position,
+                    accounts,
+                )?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Generic arguments in scope while decoding a defined type
+type Generics = HashMap<String, IdlGenericArg>;
+
+impl DynamicProgram {
+    /// Decodes a borsh serialized value into JSON. Integers beyond the safe JSON range
+    /// are written as strings, as in [`ToJsonValue`], and byte arrays as hex.
+    fn decode_type(
+        &self,
+        ty: &IdlType,
+        data: &mut &[u8],
+        generics: &Generics,
+    ) -> Result<serde_json::Value> {
+        let value = match ty {
+            IdlType::Bool => match read_bytes::<1>(data)?[0] {
+                0 => false.into(),
+                1 => true.into(),
+                _ => return Err(DynamicAnchorError::InvalidValue("bool")),
+            },
+            IdlType::U8 => u8::from_le_bytes(read_bytes(data)?).into(),
+            IdlType::I8 => i8::from_le_bytes(read_bytes(data)?).into(),
+            IdlType::U16 => u16::from_le_bytes(read_bytes(data)?).into(),
+            IdlType::I16 => i16::from_le_bytes(read_bytes(data)?).into(),
+            IdlType::U32 => u32::from_le_bytes(read_bytes(data)?).into(),
+            IdlType::I32 => i32::from_le_bytes(read_bytes(data)?).into(),
+            IdlType::U64 => safe_integer(u64::from_le_bytes(read_bytes(data)?) as i128),
+            IdlType::I64 => safe_integer(i64::from_le_bytes(read_bytes(data)?) as i128),
+            IdlType::U128 => u128::from_le_bytes(read_bytes(data)?).to_json_value(),
+            IdlType::I128 => safe_integer(i128::from_le_bytes(read_bytes(data)?)),
+            IdlType::F32 => f32::from_le_bytes(read_bytes(data)?).into(),
+            IdlType::F64 => f64::from_le_bytes(read_bytes(data)?).into(),
+            IdlType::U256 | IdlType::I256 => {
+                let mut bytes = read_bytes::<32>(data)?;
+                bytes.reverse();
+                format!("0x{}", to_hex(&bytes)).into()
+            }
+            IdlType::Bytes => {
+                let len = read_len(data)?;
+                to_hex(read_slice(data, len)?).into()
+            }
+            IdlType::String => {
+                let len = read_len(data)?;
+                std::str::from_utf8(read_slice(data, len)?)
+                    .map_err(|_| DynamicAnchorError::InvalidValue("string"))?
+                    .into()
+            }
+            IdlType::Pubkey => Pubkey::new_from_array(read_bytes(data)?).to_string().into(),
+            IdlType::Option(inner) => match read_bytes::<1>(data)?[0] {
+                0 => serde_json::Value::Null,
+                1 => self.decode_type(inner, data, generics)?,
+                _ => return Err(DynamicAnchorError::InvalidValue("option")),
+            },
+            IdlType::Vec(inner) => {
+                let len = read_len(data)?;
+                self.decode_sequence(inner, len, data, generics)?
+            }
+            IdlType::Array(inner, len) => {
+                let len = match len {
+                    IdlArrayLen::Value(len) => *len,
+                    IdlArrayLen::Generic(name) => match generics.get(name) {
+                        Some(IdlGenericArg::Const { value }) => value
+                            .parse()
+                            .map_err(|_| DynamicAnchorError::UnknownGeneric(name.clone()))?,
+                        _ => return Err(DynamicAnchorError::UnknownGeneric(name.clone())),
+                    },
+                };
+
+                self.decode_sequence(inner, len, data, generics)?
+            }
+            IdlType::Defined {
+                name,
+                generics: args,
+            } => self.decode_defined(name, args, data, generics)?,
+            IdlType::Generic(name) => match generics.get(name) {
+                Some(IdlGenericArg::Type { ty }) => self.decode_type(ty, data, generics)?,
+                _ => return Err(DynamicAnchorError::UnknownGeneric(name.clone())),
+            },
+            _ => return Err(DynamicAnchorError::UnknownType(format!("{:?}", ty))),
+        };
+
+        Ok(value)
+    }
+
+    fn decode_sequence(
+        &self,
+        ty: &IdlType,
+        len: usize,
+        data: &mut &[u8],
+        generics: &Generics,
+    ) -> Result<serde_json::Value> {
+        // Byte arrays are far more readable as hex than as a list of numbers
+        if matches!(ty, IdlType::U8) {
+            return Ok(to_hex(read_slice(data, len)?).into());
+        }
+
+        // Length is untrusted, so values are not preallocated. Each value must read some
+        // data, or a huge length would never reach the end of the instruction data.
+        let mut values = Vec::new();
+        for _ in 0..len {
+            let remaining = data.len();
+            values.push(self.decode_type(ty, data, generics)?);
+
+            if data.len() == remaining && len > remaining {
+                return Err(DynamicAnchorError::InvalidValue("sequence length"));
+            }
+        }
+
+        Ok(values.into())
+    }
+
+    fn decode_defined(
+        &self,
+        name: &str,
+        args: &[IdlGenericArg],
+        data: &mut &[u8],
+        generics: &Generics,
+    ) -> Result<serde_json::Value> {
+        let type_def = self
+            .types
+            .get(name)
+            .ok_or_else(|| DynamicAnchorError::UnknownType(name.to_string()))?;
+
+        if !matches!(type_def.serialization, IdlSerialization::Borsh) {
+            return Err(DynamicAnchorError::UnsupportedSerialization(
+                name.to_string(),
+            ));
+        }
+
+        // Bind the type's generic parameters to the given arguments. Arguments which are
+        // themselves generic are resolved against the enclosing type.
+        let mut scope = Generics::new();
+        for (param, arg) in type_def.generics.iter().zip(args) {
+            let param_name = match param {
+                IdlTypeDefGeneric::Type { name } | IdlTypeDefGeneric::Const { name, .. } => name,
+            };
+
+            let arg = match arg {
+                IdlGenericArg::Type {
+                    ty: IdlType::Generic(outer),
+                } => generics
+                    .get(outer)
+                    .cloned()
+                    .ok_or_else(|| DynamicAnchorError::UnknownGeneric(outer.clone()))?,
+                arg => arg.clone(),
+            };
+
+            scope.insert(param_name.clone(), arg);
+        }
+
+        match &type_def.ty {
+            IdlTypeDefTy::Struct { fields } => self.decode_fields(fields.as_ref(), data, &scope),
+            IdlTypeDefTy::Enum { variants } => {
+                let index = read_bytes::<1>(data)?[0];
+                let variant = variants.get(index as usize).ok_or_else(|| {
+                    DynamicAnchorError::InvalidEnumVariant {
+                        name: name.to_string(),
+                        index,
+                    }
+                })?;
+
+                match &variant.fields {
+                    None => Ok(variant.name.clone().into()),
+                    Some(fields) => {
+                        let mut value = serde_json::Map::new();
+                        value.insert(
+                            variant.name.clone(),
+                            self.decode_fields(Some(fields), data, &scope)?,
+                        );
+
+                        Ok(value.into())
+                    }
+                }
+            }
+            IdlTypeDefTy::Type { alias } => self.decode_type(alias, data, &scope),
+        }
+    }
+
+    fn decode_fields(
+        &self,
+        fields: Option<&IdlDefinedFields>,
+        data: &mut &[u8],
+        generics: &Generics,
+    ) -> Result<serde_json::Value> {
+        match fields {
+            None => Ok(serde_json::Value::Object(serde_json::Map::new())),
+            Some(IdlDefinedFields::Named(fields)) => {
+                let mut values = serde_json::Map::new();
+                for field in fields {
+                    let value = self.decode_type(&field.ty, data, generics)?;
+                    values.insert(field.name.clone(), value);
+                }
+
+                Ok(values.into())
+            }
+            Some(IdlDefinedFields::Tuple(types)) => {
+                let mut values = Vec::with_capacity(types.len());
+                for ty in types {
+                    values.push(self.decode_type(ty, data, generics)?);
+                }
+
+                Ok(values.into())
+            }
+        }
+    }
+}
+
+fn read_slice<'a>(data: &mut &'a [u8], len: usize) -> Result<&'a [u8]> {
+    if data.len() < len {
+        return Err(DynamicAnchorError::UnexpectedEof);
+    }
+
+    let (slice, rest) = data.split_at(len);
+    *data = rest;
+
+    Ok(slice)
+}
+
+fn read_bytes<const N: usize>(data: &mut &[u8]) -> Result<[u8; N]> {
+    let mut bytes = [0u8; N];
+    bytes.copy_from_slice(read_slice(data, N)?);
+
+    Ok(bytes)
+}
+
+fn read_len(data: &mut &[u8]) -> Result<usize> {
+    Ok(u32::from_le_bytes(read_bytes(data)?) as usize)
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().fold(String::new(), |mut hex, byte| {
+        let _ = write!(hex, "{:02x}", byte);
+        hex
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn program(types: serde_json::Value) -> DynamicProgram {
+        let types: Vec<IdlTypeDef> = serde_json::from_value(types).unwrap();
+
+        DynamicProgram {
+            name: "test".to_string(),
+            instructions: Vec::new(),
+            types: types.into_iter().map(|ty| (ty.name.clone(), ty)).collect(),
+        }
+    }
+
+    fn decode(program: &DynamicProgram, ty: IdlType, data: &[u8]) -> Result<serde_json::Value> {
+        program.decode_type(&ty, &mut &data[..], &Generics::new())
+    }
+
+    #[test]
+    fn decodes_vec() {
+        let mut data = 2u32.to_le_bytes().to_vec();
+        data.extend(7u16.to_le_bytes());
+        data.extend(9u16.to_le_bytes());
+
+        let value = decode(
+            &program(serde_json::json!([])),
+            IdlType::Vec(Box::new(IdlType::U16)),
+            &data,
+        );
+
+        assert_eq!(value.unwrap(), serde_json::json!([7, 9]));
+    }
+
+    #[test]
+    fn large_integers_are_strings() {
+        let program = program(serde_json::json!([]));
+
+        let small = decode(&program, IdlType::U64, &1000u64.to_le_bytes());
+        assert_eq!(small.unwrap(), serde_json::json!(1000));
+
+        let large = decode(&program, IdlType::U64, &u64::MAX.to_le_bytes());
+        assert_eq!(large.unwrap(), serde_json::json!("18446744073709551615"));
+
+        let negative = decode(&program, IdlType::I64, &i64::MIN.to_le_bytes());
+        assert_eq!(negative.unwrap(), serde_json::json!("-9223372036854775808"));
+
+        let small = decode(&program, IdlType::U128, &5u128.to_le_bytes());
+        assert_eq!(small.unwrap(), serde_json::json!(5));
+
+        let large = decode(&program, IdlType::I128, &i128::MIN.to_le_bytes());
+        assert_eq!(large.unwrap(), serde_json::json!(i128::MIN.to_string()));
+    }
+
+    #[test]
+    fn huge_vec_length_is_rejected() {
+        let mut data = u32::MAX.to_le_bytes().to_vec();
+        data.extend(1u64.to_le_bytes());
+
+        let value = decode(
+            &program(serde_json::json!([])),
+            IdlType::Vec(Box::new(IdlType::U64)),
+            &data,
+        );
+
+        assert!(matches!(value, Err(DynamicAnchorError::UnexpectedEof)));
+    }
+
+    #[test]
+    fn huge_byte_vec_length_is_rejected() {
+        let data = u32::MAX.to_le_bytes();
+
+        let value = decode(&program(serde_json::json!([])), IdlType::Bytes, &data);
+
+        assert!(matches!(value, Err(DynamicAnchorError::UnexpectedEof)));
+    }
+
+    #[test]
+    fn huge_vec_of_empty_values_is_rejected() {
+        let program = program(serde_json::json!([
+            { "name": "Empty", "type": { "kind": "struct" } },
+        ]));
+        let ty = IdlType::Vec(Box::new(IdlType::Defined {
+            name: "Empty".to_string(),
+            generics: Vec::new(),
+        }));
+
+        let value = decode(&program, ty, &u32::MAX.to_le_bytes());
+
+        assert!(matches!(value, Err(DynamicAnchorError::InvalidValue(_))));
+    }
+}
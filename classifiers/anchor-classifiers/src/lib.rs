@@ -1,6 +1,10 @@
 use anchor_lang::{prelude::*, Discriminator};
 use macros::declare_anchor_classifier;
 
+mod dynamic;
+
+pub use dynamic::*;
+
 declare_anchor_classifier!(whirlpools, Swap, SwapV2);
 declare_anchor_classifier!(jupiter_v6, Route, RouteWithTokenLedger, SharedAccountsRoute);
 declare_anchor_classifier!(meteora_dlmm, Swap, SwapExactOut);
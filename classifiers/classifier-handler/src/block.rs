@@ -1,9 +1,15 @@
-use actions::{Action, ActionTree, Block};
-use classifier_core::ClassifiableTransaction;
-use solana_transaction_status::UiConfirmedBlock;
+use std::collections::{HashMap, HashSet};
+
+use actions::{
+    Action, ActionNodeId, ActionTree, Block, LeaderSource, ProgramComputeUnits, RewardDiscrepancy,
+};
+use classifier_core::{AddressLookupTableSource, ClassifiableTransaction};
+use rayon::prelude::*;
+use solana_sdk::{pubkey::Pubkey, reward_type::RewardType, signature::Signature};
+use solana_transaction_status::{Rewards, UiConfirmedBlock};
 use thiserror::Error;
 
-use crate::classify_transaction;
+use crate::{classify_transaction, ClassifierRegistry};
 
 #[derive(Debug, Error)]
 pub enum ClassifyBlockError {
@@ -26,6 +32,9 @@ pub fn classify_block(
     slot: u64,
     block: UiConfirmedBlock,
     filter_tx: Option<String>,
+    lookup_tables: Option<&dyn AddressLookupTableSource>,
+    registry: &ClassifierRegistry,
+    parallel_transactions: bool,
 ) -> Result<ActionTree> {
     if block.transactions.is_none() {
         return Err(ClassifyBlockError::MissingTransactions);
@@ -38,16 +47,24 @@ pub fn classify_block(
         }
     };
 
-		let validator_pubkey: Option<String> = match block.rewards {
-			Some(rewards) => Some(rewards.first().unwrap().pubkey.clone()),
-			None => None
-		};
+    // The leader is credited with the block's fee reward
+    let validator_pubkey = block.rewards.as_ref().and_then(|rewards| {
+        rewards
+            .iter()
+            .find(|reward| reward.reward_type == Some(RewardType::Fee))
+            .map(|reward| reward.pubkey.clone())
+    });
 
-    let root_action = Block::new(slot, block.parent_slot, block_time, validator_pubkey);
+    let mut root_action = Block::new(slot, block.parent_slot, block_time, validator_pubkey);
+    if root_action.validator_pubkey.is_some() {
+        root_action.leader_source = Some(LeaderSource::FeeReward);
+    }
 
     let mut tree = ActionTree::new(root_action.into());
     let block_id = tree.root();
 
+    let mut transactions = Vec::new();
+
     for txn in block.transactions.unwrap() {
         let v_txn = txn.transaction.decode().unwrap();
 
@@ -63,74 +80,178 @@ pub fn classify_block(
             }
         }
 
-        let c_txn = ClassifiableTransaction::new(v_txn, txn.meta.unwrap());
-        let tx_id = tree.insert_child(block_id, c_txn.clone().into());
-
-        match classify_transaction(&c_txn, &mut tree, tx_id) {
-            Ok(_) => {}
-            Err(err) => {
-                // TODO: Handle these somehow
-                tracing::error!(
-                    "Failed to classify transaction: {:?}, signature: {}",
-                    err,
-                    signature,
-                );
+        transactions.push(ClassifiableTransaction::new_with_lookup_tables(
+            v_txn,
+            txn.meta.unwrap(),
+            lookup_tables,
+        ));
+    }
+
+    // Each transaction is classified into its own tree, so transactions can be classified
+    // in parallel and then appended to the block in order
+    let transaction_trees: Vec<(ActionTree, bool)> = if parallel_transactions {
+        transactions
+            .into_par_iter()
+            .map(|c_txn| classify_transaction_tree(c_txn, registry))
+            .collect()
+    } else {
+        transactions
+            .into_iter()
+            .map(|c_txn| classify_transaction_tree(c_txn, registry))
+            .collect()
+    };
+
+    let mut unclassified = HashSet::new();
+    for (transaction_tree, classified) in &transaction_trees {
+        tree.append_tree(block_id, transaction_tree);
+
+        if !classified {
+            let root = transaction_tree.get(transaction_tree.root()).unwrap();
+            if let Action::ClassifiableTransaction(txn) = root.get() {
+                unclassified.insert(txn.signature);
             }
         }
     }
 
-    calculate_rewards(&mut tree);
+    // Reconciling requires every transaction in the block
+    let rewards = block.rewards.as_ref().filter(|_| filter_tx.is_none());
+    calculate_rewards(&mut tree, rewards, &unclassified);
+    calculate_compute_units(&mut tree);
 
     Ok(tree)
 }
 
-pub const BASE_FEE: u64 = 5000;
+/// Classifies a transaction into a tree rooted at the transaction, and whether it was
+/// classified. Failures are logged and leave the transaction with whatever actions were
+/// classified before the failure.
+fn classify_transaction_tree(
+    c_txn: ClassifiableTransaction,
+    registry: &ClassifierRegistry,
+) -> (ActionTree, bool) {
+    let signature = c_txn.signature;
+
+    let mut tree = ActionTree::new(c_txn.clone().into());
+    let tx_id = tree.root();
+
+    let classified = match classify_transaction(&c_txn, &mut tree, tx_id, registry) {
+        Ok(_) => true,
+        Err(err) => {
+            // TODO: Handle these somehow
+            tracing::error!(
+                "Failed to classify transaction: {:?}, signature: {}",
+                err,
+                signature,
+            );
+            false
+        }
+    };
 
-fn calculate_rewards(tree: &mut ActionTree) {
+    (tree, classified)
+}
+
+/// Percentage of base fees burned by the runtime. The remainder goes to the leader.
+pub const BASE_FEE_BURN_PERCENT: u64 = 50;
+
+/// Percentage of all fees, including priority fees, burned before SIMD-0096 sent the
+/// full priority fee to the leader.
+pub const LEGACY_FEE_BURN_PERCENT: u64 = 50;
+
+/// Totals fees and tips paid in the block, splits fees into the burned portion and the
+/// leader's share, and reconciles the leader's share against the block rewards when given.
+/// Fees of `unclassified` transactions are totaled but not checked against their compute
+/// budget.
+fn calculate_rewards(
+    tree: &mut ActionTree,
+    rewards: Option<&Rewards>,
+    unclassified: &HashSet<Signature>,
+) {
     let block_id = tree.root();
 
-    let mut total_tips = 0;
+    let mut total_jito_tips = 0;
+    let mut total_bloxroute_tips = 0;
     let mut total_priority_fees = 0;
     let mut total_base_fees = 0;
+    let mut total_fees = 0;
+    let mut discrepancies = Vec::new();
 
     for node_id in tree.descendants(block_id) {
         let node = tree.get(node_id).unwrap();
 
         match node.get() {
             Action::ClassifiableTransaction(txn) => {
-                if txn.fee > BASE_FEE {
-                    total_base_fees += BASE_FEE;
-                    total_priority_fees += txn.fee - BASE_FEE;
-                } else {
-                    total_base_fees += txn.fee;
+                let base_fee = txn.base_fee();
+                let budget_priority_fee = txn.compute_budget_priority_fee();
+
+                if !unclassified.contains(&txn.signature)
+                    && base_fee + budget_priority_fee != txn.fee
+                {
+                    discrepancies.push(RewardDiscrepancy::TransactionFee {
+                        signature: txn.signature,
+                        expected: base_fee + budget_priority_fee,
+                        actual: txn.fee,
+                    });
                 }
+
+                // The fee charged, rather than the one requested through the compute budget
+                total_base_fees += base_fee;
+                total_priority_fees += txn.priority_fee();
+                total_fees += txn.fee;
             }
             Action::JitoTip(tip) => {
-                // Find parent transaction
-                let mut succeeded = false;
-                let mut parent_id = node.parent();
-                while let Some(some_parent_id) = parent_id {
-                    let parent = tree.get(some_parent_id).unwrap();
-
-                    match parent.get() {
-                        Action::ClassifiableTransaction(tx) => {
-                            succeeded = tx.status.is_ok();
-                            break;
-                        }
-                        _ => {
-                            parent_id = parent.parent();
-                        }
-                    }
+                if tip_succeeded(tree, node_id) {
+                    total_jito_tips += tip.tip_amount;
                 }
-
-                if succeeded {
-                    total_tips += tip.tip_amount;
+            }
+            Action::BloxrouteTip(tip) => {
+                if tip_succeeded(tree, node_id) {
+                    total_bloxroute_tips += tip.tip_amount;
                 }
             }
             _ => continue,
         };
     }
 
+    // Fees are burned from the total collected by the leader, not per transaction
+    let base_fees_charged = total_fees.min(total_base_fees);
+    let priority_fees_charged = total_fees - base_fees_charged;
+    let base_fee_burn = base_fees_charged * BASE_FEE_BURN_PERCENT / 100;
+    let legacy_burn = total_fees * LEGACY_FEE_BURN_PERCENT / 100;
+
+    let fee_reward = rewards.and_then(|rewards| {
+        rewards
+            .iter()
+            .find(|reward| reward.reward_type == Some(RewardType::Fee))
+            .map(|reward| reward.lamports)
+    });
+
+    // Blocks before SIMD-0096 also burned half of priority fees. Prefer whichever
+    // rule matches the reward actually paid, defaulting to the current rule.
+    let total_fees_burned = match fee_reward {
+        Some(reward)
+            if priority_fees_charged > 0 && reward == (total_fees - legacy_burn) as i64 =>
+        {
+            legacy_burn
+        }
+        _ => base_fee_burn,
+    };
+    let total_validator_fees = total_fees - total_fees_burned;
+
+    if rewards.is_some() {
+        match fee_reward {
+            Some(reward) if reward == total_validator_fees as i64 => {}
+            Some(reward) => discrepancies.push(RewardDiscrepancy::FeeReward {
+                expected: total_validator_fees,
+                actual: reward,
+            }),
+            None if total_validator_fees > 0 => {
+                discrepancies.push(RewardDiscrepancy::MissingFeeReward {
+                    expected: total_validator_fees,
+                })
+            }
+            None => {}
+        }
+    }
+
     let block = tree.get_mut(block_id).unwrap();
     let block = match block.get_mut() {
         Action::Block(block) => block,
@@ -139,5 +260,226 @@ fn calculate_rewards(tree: &mut ActionTree) {
 
     block.total_base_fees = Some(total_base_fees);
     block.total_priority_fees = Some(total_priority_fees);
-    block.total_tips = Some(total_tips);
+    block.total_tips = Some(total_jito_tips + total_bloxroute_tips);
+    block.total_jito_tips = Some(total_jito_tips);
+    block.total_bloxroute_tips = Some(total_bloxroute_tips);
+    block.total_fees_burned = Some(total_fees_burned);
+    block.total_validator_fees = Some(total_validator_fees);
+    block.fee_reward = fee_reward;
+    block.reward_discrepancies = Some(discrepancies);
+}
+
+/// Tips only land if the transaction that paid them succeeded
+fn tip_succeeded(tree: &ActionTree, tip_id: ActionNodeId) -> bool {
+    let mut parent_id = tree.get(tip_id).unwrap().parent();
+
+    while let Some(some_parent_id) = parent_id {
+        let parent = tree.get(some_parent_id).unwrap();
+
+        match parent.get() {
+            Action::ClassifiableTransaction(tx) => return tx.status.is_ok(),
+            _ => parent_id = parent.parent(),
+        }
+    }
+
+    false
+}
+
+/// Totals compute units for the block, and attributes compute units of top level
+/// instructions to the invoked program. Inner instructions are not counted separately
+/// since their usage is already included in the top level instruction.
+fn calculate_compute_units(tree: &mut ActionTree) {
+    let block_id = tree.root();
+
+    let mut total_compute_units = 0;
+    let mut programs: HashMap<Pubkey, ProgramComputeUnits> = HashMap::new();
+
+    for node_id in tree.children(block_id) {
+        let txn = match tree.get(node_id).unwrap().get() {
+            Action::ClassifiableTransaction(txn) => txn,
+            _ => continue,
+        };
+
+        total_compute_units += txn.compute_units_consumed.unwrap_or_default();
+
+        for ix in &txn.instructions {
+            if ix.stack_height != 1 {
+                continue;
+            }
+
+            let (compute_units, program_id) = match (
+                ix.compute_units_consumed,
+                txn.get_pubkey(ix.program_id_index),
+            ) {
+                (Some(compute_units), Some(program_id)) => (compute_units, program_id),
+                _ => continue,
+            };
+
+            let program = programs
+                .entry(program_id)
+                .or_insert_with(|| ProgramComputeUnits {
+                    program_id,
+                    compute_units: 0,
+                    instructions: 0,
+                });
+
+            program.compute_units += compute_units;
+            program.instructions += 1;
+        }
+    }
+
+    let mut program_compute_units: Vec<ProgramComputeUnits> = programs.into_values().collect();
+    program_compute_units.sort_by(|a, b| b.compute_units.cmp(&a.compute_units));
+
+    let block = tree.get_mut(block_id).unwrap();
+    let block = match block.get_mut() {
+        Action::Block(block) => block,
+        _ => unreachable!("Root node should be a block"),
+    };
+
+    block.total_compute_units = Some(total_compute_units);
+    block.program_compute_units = Some(program_compute_units);
+}
+
+#[cfg(test)]
+mod tests {
+    use actions::JitoTip;
+    use solana_sdk::{
+        compute_budget::ComputeBudgetInstruction,
+        message::{Message, VersionedMessage},
+        transaction::VersionedTransaction,
+    };
+    use solana_transaction_status::{Reward, UiTransactionStatusMeta};
+
+    use super::*;
+
+    /// Transaction requesting 100k compute units at 10k micro-lamports, so its expected
+    /// fee is 5000 base and 1000 priority
+    fn transaction(fee: u64) -> ClassifiableTransaction {
+        let payer = Pubkey::new_unique();
+        let meta: UiTransactionStatusMeta = serde_json::from_value(serde_json::json!({
+            "err": null,
+            "status": { "Ok": null },
+            "fee": fee,
+            "preBalances": [],
+            "postBalances": [],
+        }))
+        .unwrap();
+
+        let mut txn = ClassifiableTransaction::new(
+            VersionedTransaction {
+                signatures: vec![Signature::new_unique()],
+                message: VersionedMessage::Legacy(Message::new(
+                    &[
+                        ComputeBudgetInstruction::set_compute_unit_limit(100_000),
+                        ComputeBudgetInstruction::set_compute_unit_price(10_000),
+                    ],
+                    Some(&payer),
+                )),
+            },
+            meta,
+        );
+        txn.compute_unit_limit = Some(100_000);
+        txn.compute_unit_price = Some(10_000);
+        txn
+    }
+
+    fn block_tree(transactions: Vec<ClassifiableTransaction>) -> ActionTree {
+        let mut tree = ActionTree::new(Block::new(100, 99, 0, None).into());
+        for txn in transactions {
+            let payer = txn.static_keys[0];
+            let txn_id = tree.insert_child(tree.root(), txn.into());
+            tree.insert_child(txn_id, JitoTip::new(payer, 700).into());
+        }
+        tree
+    }
+
+    fn fee_reward(lamports: i64) -> Rewards {
+        vec![Reward {
+            pubkey: Pubkey::new_unique().to_string(),
+            lamports,
+            post_balance: 0,
+            reward_type: Some(RewardType::Fee),
+            commission: None,
+        }]
+    }
+
+    fn block(tree: &ActionTree) -> &Block {
+        match tree.get(tree.root()).unwrap().get() {
+            Action::Block(block) => block,
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn burns_half_of_base_fees() {
+        let mut tree = block_tree(vec![transaction(6000), transaction(6000)]);
+        calculate_rewards(&mut tree, Some(&fee_reward(7000)), &HashSet::new());
+
+        let block = block(&tree);
+        assert_eq!(block.total_base_fees, Some(10_000));
+        assert_eq!(block.total_priority_fees, Some(2000));
+        assert_eq!(block.total_fees_burned, Some(5000));
+        assert_eq!(block.total_validator_fees, Some(7000));
+        assert_eq!(block.total_jito_tips, Some(1400));
+        assert_eq!(block.total_tips, Some(1400));
+        assert_eq!(block.fee_reward, Some(7000));
+        assert_eq!(block.reward_discrepancies.as_ref().unwrap().len(), 0);
+    }
+
+    #[test]
+    fn burns_half_of_all_fees_before_simd_0096() {
+        let mut tree = block_tree(vec![transaction(6000)]);
+        calculate_rewards(&mut tree, Some(&fee_reward(3000)), &HashSet::new());
+
+        let block = block(&tree);
+        assert_eq!(block.total_fees_burned, Some(3000));
+        assert_eq!(block.total_validator_fees, Some(3000));
+        assert_eq!(block.reward_discrepancies.as_ref().unwrap().len(), 0);
+    }
+
+    #[test]
+    fn reports_fee_reward_discrepancies() {
+        let mut tree = block_tree(vec![transaction(6000)]);
+        calculate_rewards(&mut tree, Some(&fee_reward(4000)), &HashSet::new());
+        assert!(matches!(
+            block(&tree).reward_discrepancies.as_deref().unwrap(),
+            [RewardDiscrepancy::FeeReward {
+                expected: 3500,
+                actual: 4000,
+            }]
+        ));
+
+        let mut tree = block_tree(vec![transaction(6000)]);
+        calculate_rewards(&mut tree, Some(&vec![]), &HashSet::new());
+        assert!(matches!(
+            block(&tree).reward_discrepancies.as_deref().unwrap(),
+            [RewardDiscrepancy::MissingFeeReward { expected: 3500 }]
+        ));
+
+        // Nothing to reconcile against without rewards
+        let mut tree = block_tree(vec![transaction(6000)]);
+        calculate_rewards(&mut tree, None, &HashSet::new());
+        assert_eq!(block(&tree).reward_discrepancies.as_ref().unwrap().len(), 0);
+    }
+
+    #[test]
+    fn reports_transaction_fee_discrepancies_of_classified_transactions() {
+        let overcharged = transaction(9000);
+        let unclassified = transaction(9000);
+        let (overcharged_signature, unclassified_signature) =
+            (overcharged.signature, unclassified.signature);
+
+        let mut tree = block_tree(vec![overcharged, unclassified]);
+        calculate_rewards(&mut tree, None, &HashSet::from([unclassified_signature]));
+
+        match block(&tree).reward_discrepancies.as_deref().unwrap() {
+            [RewardDiscrepancy::TransactionFee {
+                signature,
+                expected: 6000,
+                actual: 9000,
+            }] => assert_eq!(*signature, overcharged_signature),
+            discrepancies => panic!("Unexpected discrepancies: {:?}", discrepancies),
+        }
+    }
 }
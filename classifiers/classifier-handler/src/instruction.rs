@@ -1,46 +1,30 @@
 use actions::{ActionNodeId, ActionTrait, ActionTree, ProgramInvocation};
 use classifier_core::{ClassifiableInstruction, ClassifiableTransaction};
-use classifier_trait::InstructionClassifier;
 use thiserror::Error;
 
+use crate::ClassifierRegistry;
+
 #[derive(Debug, Error)]
 pub enum ClassifyInstructionError {
     #[error("Missing program id")]
     MissingProgramId,
 
-    #[error("Unknown program id")]
-    UnknownProgramId,
-
     #[error(transparent)]
     ClassificationError(#[from] anyhow::Error),
 }
 
 type Result<T> = std::result::Result<T, ClassifyInstructionError>;
 
-/// Utility macro to match instruction to its correct classifier
-macro_rules! classify_instruction_matcher {
-    ($program_id:expr, $txn:expr, $ix:expr, $($classifier:ty),* $(,)?) => {
-        match $program_id {
-            $(
-                <$classifier>::ID => <$classifier>::classify_instruction($txn, $ix)
-                    .map_err(|err| {
-                        let classifier = stringify!($classifier);
-                        ClassifyInstructionError::ClassificationError(Into::<anyhow::Error>::into(err)
-                            .context(format!("Classifier: {}", classifier)))
-                    }),
-            )*
-            _ => Err(ClassifyInstructionError::UnknownProgramId),
-        }
-    };
-}
-
 /// Classifies an instruction, recursing into its inner instructions if
-/// necessary.
+/// necessary. The name of the classifier which handled each instruction is
+/// recorded in `instruction_classifiers`, by instruction index.
 pub fn classify_instruction(
     txn: &ClassifiableTransaction,
     mut index: usize,
     tree: &mut ActionTree,
     parent: ActionNodeId,
+    registry: &ClassifierRegistry,
+    instruction_classifiers: &mut [Option<String>],
 ) -> Result<usize> {
     let ix: &ClassifiableInstruction = &txn.instructions[index];
     let mut indexes_used = 1;
@@ -51,67 +35,21 @@ pub fn classify_instruction(
 
     // println!("PROGRAM ID: {:?}", program_id);
 
-    let action_result = classify_instruction_matcher!(
-        program_id,
-        txn,
-        ix,
-        //
-        // Register all classifier modules here
-        //
-
-        //
-        // Solana classifiers
-        //
-        solana_classifier::ComputeBudgetClassifier,
-        solana_classifier::VoteClassifier,
-        solana_classifier::SystemProgramClassifier,
-        solana_classifier::GenericTokenProgramClassifier<solana_classifier::OriginalToken>,
-        solana_classifier::GenericTokenProgramClassifier<solana_classifier::Token2022>,
-        solana_classifier::AssociatedTokenClassifier,
-        //
-        // Third party classifiers
-        //
-        anchor_classifiers::WhirlpoolsClassifier,
-        anchor_classifiers::JupiterV6Classifier,
-        anchor_classifiers::MeteoraDlmmClassifier,
-        anchor_classifiers::RaydiumClmmClassifier,
-        misc_classifiers::PhoenixV1Classifier,
-        misc_classifiers::RaydiumAmmClassifier,
-        //
-        // Star atlas (shows up everywhere)
-        //
-        misc_classifiers::StarAtlasGalacticMarketplaceClassifier,
-        misc_classifiers::StarAtlasSAGEClassifier,
-        misc_classifiers::StarAtlasCraftingClassifier,
-        misc_classifiers::StarAtlasCargoClassifier,
-        misc_classifiers::StarAtlasPlayerProfileClassifier,
-        misc_classifiers::StarAtlasProfileVaultClassifier,
-        misc_classifiers::StarAtlasProfileFactionClassifier,
-        misc_classifiers::StarAtlasPointsClassifier,
-        misc_classifiers::StarAtlasPrimeClassifier,
-        misc_classifiers::StarAtlasClaimStakesClassifier,
-        misc_classifiers::StarAtlasSCOREClassifier,
-        misc_classifiers::StarAtlasSAGEEscapeVelocityClassifier,
-        misc_classifiers::StarAtlasDAOProxyRewarderClassifier,
-        misc_classifiers::StarAtlasLockerClassifier,
-        misc_classifiers::StarAtlasPolisLockerClassifier,
-        misc_classifiers::StarAtlasPolisLockerSnapshotsClassifier,
-        misc_classifiers::StarAtlasFactionEnlistmentClassifier,
-    );
+    let action_result = registry.classify_instruction(&program_id, txn, ix);
 
     // println!("ACTION RESULT: {:?}", action_result);
 
     let action = match action_result {
-        Ok(Some(action)) => Some(action),
-        Ok(None) => Some(ProgramInvocation { program_id }.into()),
+        Ok(Some((classifier, action))) => {
+            instruction_classifiers[index] = Some(classifier.to_string());
+            Some(action)
+        }
 
         // Still want to classify unknown programs
-        Err(ClassifyInstructionError::UnknownProgramId) => {
-            Some(ProgramInvocation { program_id }.into())
-        }
+        Ok(None) => Some(ProgramInvocation { program_id }.into()),
 
         // All other errors indicate some sort of actual failure
-        Err(err) => return Err(err),
+        Err(err) => return Err(err.into()),
     };
 
     let (recurse, child) = if let Some(action) = action {
@@ -132,7 +70,14 @@ pub fn classify_instruction(
         }
 
         if recurse {
-            let r_indexes_used = classify_instruction(txn, index, tree, child.unwrap())?;
+            let r_indexes_used = classify_instruction(
+                txn,
+                index,
+                tree,
+                child.unwrap(),
+                registry,
+                instruction_classifiers,
+            )?;
             index += r_indexes_used;
             indexes_used += r_indexes_used;
         } else {
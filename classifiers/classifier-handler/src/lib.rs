@@ -1,7 +1,11 @@
 mod block;
 mod instruction;
+mod registry;
 mod transaction;
 
 pub use block::*;
 pub use instruction::*;
+pub use registry::*;
 pub use transaction::*;
+
+pub use anchor_classifiers::{DynamicAnchorClassifier, DynamicAnchorError};
@@ -0,0 +1,485 @@
+use std::{collections::HashMap, path::Path};
+
+use actions::Action;
+use anchor_classifiers::{DynamicAnchorClassifier, DynamicAnchorError};
+use classifier_core::{ClassifiableInstruction, ClassifiableTransaction};
+use classifier_trait::DynInstructionClassifier;
+use solana_sdk::pubkey::Pubkey;
+
+/// Name reported for instructions decoded from runtime IDLs
+pub const DYNAMIC_ANCHOR_CLASSIFIER_NAME: &str = "DynamicAnchorClassifier";
+
+/// Selects which registered classifiers are used. Classifiers are matched by name or by
+/// program id.
+#[derive(Debug, Clone, Default)]
+pub struct ClassifierConfig {
+    /// If set, only these classifiers are used
+    pub enabled: Option<Vec<String>>,
+
+    /// Classifiers which are never used, even if enabled
+    pub disabled: Vec<String>,
+}
+
+impl ClassifierConfig {
+    fn is_enabled(&self, name: &str, program_id: Option<&Pubkey>) -> bool {
+        let program_id = program_id.map(|program_id| program_id.to_string());
+        let matches = |key: &String| key == name || Some(key) == program_id.as_ref();
+
+        let enabled = match &self.enabled {
+            Some(enabled) => enabled.iter().any(matches),
+            None => true,
+        };
+
+        enabled && !self.disabled.iter().any(matches)
+    }
+}
+
+struct RegisteredClassifier {
+    name: String,
+    classifier: Box<dyn DynInstructionClassifier>,
+}
+
+/// Instruction classifiers keyed by the program they handle. Programs without a
+/// registered classifier fall back to the dynamic Anchor classifier, if one is set.
+#[derive(Default)]
+pub struct ClassifierRegistry {
+    classifiers: HashMap<Pubkey, RegisteredClassifier>,
+    dynamic_classifier: Option<DynamicAnchorClassifier>,
+}
+
+impl ClassifierRegistry {
+    /// Registry of the default classifiers, with IDLs in `idl_dir` decoded by the dynamic
+    /// classifier, restricted to the classifiers enabled by `config`.
+    pub fn load(
+        idl_dir: Option<&Path>,
+        config: &ClassifierConfig,
+    ) -> Result<Self, DynamicAnchorError> {
+        let mut registry = Self::with_default_classifiers();
+
+        if let Some(idl_dir) = idl_dir {
+            registry.set_dynamic_classifier(DynamicAnchorClassifier::load(idl_dir)?);
+        }
+
+        registry.configure(config);
+
+        Ok(registry)
+    }
+
+    /// Registry of all classifiers built into this crate
+    pub fn with_default_classifiers() -> Self {
+        let mut registry = Self::default();
+
+        //
+        // Solana classifiers
+        //
+        registry.register(solana_classifier::ComputeBudgetClassifier);
+        registry.register(solana_classifier::VoteClassifier);
+        registry.register(solana_classifier::SystemProgramClassifier);
+        registry.register(solana_classifier::GenericTokenProgramClassifier::<
+            solana_classifier::OriginalToken,
+        >::new());
+        registry.register(solana_classifier::GenericTokenProgramClassifier::<
+            solana_classifier::Token2022,
+        >::new());
+        registry.register(solana_classifier::AssociatedTokenClassifier);
+
+        //
+        // Third party classifiers
+        //
+        registry.register(anchor_classifiers::WhirlpoolsClassifier);
+        registry.register(anchor_classifiers::JupiterV6Classifier);
+        registry.register(anchor_classifiers::MeteoraDlmmClassifier);
+        registry.register(anchor_classifiers::RaydiumClmmClassifier);
+        registry.register(misc_classifiers::PhoenixV1Classifier);
+        registry.register(misc_classifiers::RaydiumAmmClassifier);
+
+        //
+        // Star atlas (shows up everywhere)
+        //
+        registry.register(misc_classifiers::StarAtlasGalacticMarketplaceClassifier);
+        registry.register(misc_classifiers::StarAtlasSAGEClassifier);
+        registry.register(misc_classifiers::StarAtlasCraftingClassifier);
+        registry.register(misc_classifiers::StarAtlasCargoClassifier);
+        registry.register(misc_classifiers::StarAtlasPlayerProfileClassifier);
+        registry.register(misc_classifiers::StarAtlasProfileVaultClassifier);
+        registry.register(misc_classifiers::StarAtlasProfileFactionClassifier);
+        registry.register(misc_classifiers::StarAtlasPointsClassifier);
+        registry.register(misc_classifiers::StarAtlasPrimeClassifier);
+        registry.register(misc_classifiers::StarAtlasClaimStakesClassifier);
+        registry.register(misc_classifiers::StarAtlasSCOREClassifier);
+        registry.register(misc_classifiers::StarAtlasSAGEEscapeVelocityClassifier);
+        registry.register(misc_classifiers::StarAtlasDAOProxyRewarderClassifier);
+        registry.register(misc_classifiers::StarAtlasLockerClassifier);
+        registry.register(misc_classifiers::StarAtlasPolisLockerClassifier);
+        registry.register(misc_classifiers::StarAtlasPolisLockerSnapshotsClassifier);
+        registry.register(misc_classifiers::StarAtlasFactionEnlistmentClassifier);
+
+        registry
+    }
+
+    /// Register a classifier for its program, returning the name of the classifier it
+    /// replaced, if any.
+    pub fn register<C: DynInstructionClassifier + 'static>(
+        &mut self,
+        classifier: C,
+    ) -> Option<String> {
+        self.register_boxed(Box::new(classifier))
+    }
+
+    pub fn register_boxed(
+        &mut self,
+        classifier: Box<dyn DynInstructionClassifier>,
+    ) -> Option<String> {
+        let registered = RegisteredClassifier {
+            name: short_type_name(classifier.name()),
+            classifier,
+        };
+
+        self.classifiers
+            .insert(registered.classifier.program_id(), registered)
+            .map(|replaced| replaced.name)
+    }
+
+    /// Remove the classifier for a program, returning its name
+    pub fn unregister(&mut self, program_id: &Pubkey) -> Option<String> {
+        self.classifiers
+            .remove(program_id)
+            .map(|removed| removed.name)
+    }
+
+    pub fn set_dynamic_classifier(&mut self, dynamic_classifier: DynamicAnchorClassifier) {
+        self.dynamic_classifier = Some(dynamic_classifier);
+    }
+
+    /// Drop every classifier not enabled by `config`
+    pub fn configure(&mut self, config: &ClassifierConfig) {
+        self.classifiers.retain(|program_id, registered| {
+            let enabled = config.is_enabled(&registered.name, Some(program_id));
+            if !enabled {
+                tracing::info!("Disabled classifier {}", registered.name);
+            }
+
+            enabled
+        });
+
+        if self.dynamic_classifier.is_some()
+            && !config.is_enabled(DYNAMIC_ANCHOR_CLASSIFIER_NAME, None)
+        {
+            tracing::info!("Disabled classifier {}", DYNAMIC_ANCHOR_CLASSIFIER_NAME);
+            self.dynamic_classifier = None;
+        }
+    }
+
+    /// Names of registered classifiers and the programs they handle
+    pub fn classifiers(&self) -> impl Iterator<Item = (&Pubkey, &str)> {
+        self.classifiers
+            .iter()
+            .map(|(program_id, registered)| (program_id, registered.name.as_str()))
+    }
+
+    pub fn dynamic_classifier(&self) -> Option<&DynamicAnchorClassifier> {
+        self.dynamic_classifier.as_ref()
+    }
+
+    /// Classify an instruction with the program's registered classifier, falling back to
+    /// the dynamic classifier if none is registered or the registered classifier does
+    /// not handle the instruction. Returns the name of the classifier which produced the
+    /// action.
+    ///
+    /// Errors of the registered classifier are returned. The dynamic classifier's IDLs
+    /// may be outdated or incomplete, so its errors are logged and ignored.
+    pub fn classify_instruction(
+        &self,
+        program_id: &Pubkey,
+        txn: &ClassifiableTransaction,
+        ix: &ClassifiableInstruction,
+    ) -> Result<Option<(&str, Action)>, anyhow::Error> {
+        if let Some(registered) = self.classifiers.get(program_id) {
+            let action = registered
+                .classifier
+                .classify_instruction(txn, ix)
+                .map_err(|err| err.context(format!("Classifier: {}", registered.name)))?;
+
+            if let Some(action) = action {
+                return Ok(Some((registered.name.as_str(), action)));
+            }
+        }
+
+        let dynamic_classifier = match &self.dynamic_classifier {
+            Some(dynamic_classifier) => dynamic_classifier,
+            None => return Ok(None),
+        };
+
+        match dynamic_classifier.classify_instruction(program_id, txn, ix) {
+            Ok(action) => Ok(action.map(|action| (DYNAMIC_ANCHOR_CLASSIFIER_NAME, action))),
+            Err(err) => {
+                tracing::debug!(
+                    "Failed to decode instruction of {} from IDL: {:?}, signature: {}",
+                    program_id,
+                    err,
+                    txn.signature,
+                );
+                Ok(None)
+            }
+        }
+    }
+}
+
+/// Strips module paths from a type name, including those of generic arguments, e.g.
+/// `a::Foo<b::Bar>` becomes `Foo<Bar>`.
+fn short_type_name(name: &str) -> String {
+    let mut short = String::with_capacity(name.len());
+    let mut segment_start = 0;
+    let mut chars = name.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            ':' if chars.peek() == Some(&':') => {
+                chars.next();
+                short.truncate(segment_start);
+            }
+            '<' | '>' | ',' | ' ' | '(' | ')' | '[' | ']' | '&' | ';' => {
+                short.push(c);
+                segment_start = short.len();
+            }
+            _ => short.push(c),
+        }
+    }
+
+    short
+}
+
+#[cfg(test)]
+mod tests {
+    use actions::SetComputeUnitPrice;
+    use classifier_trait::ClassifyInstructionResult;
+    use solana_sdk::{
+        instruction::Instruction,
+        message::{Message, VersionedMessage},
+        signature::Signature,
+        transaction::VersionedTransaction,
+    };
+    use solana_transaction_status::UiTransactionStatusMeta;
+
+    use super::*;
+
+    const DISCRIMINATOR: [u8; 8] = [1, 2, 3, 4, 5, 6, 7, 8];
+
+    #[derive(Clone, Copy)]
+    enum Outcome {
+        Handled,
+        Unhandled,
+        Failed,
+    }
+
+    struct TestClassifier {
+        program_id: Pubkey,
+        outcome: Outcome,
+    }
+
+    impl DynInstructionClassifier for TestClassifier {
+        fn program_id(&self) -> Pubkey {
+            self.program_id
+        }
+
+        fn name(&self) -> &str {
+            "tests::TestClassifier"
+        }
+
+        fn classify_instruction(
+            &self,
+            _txn: &ClassifiableTransaction,
+            _ix: &ClassifiableInstruction,
+        ) -> ClassifyInstructionResult {
+            match self.outcome {
+                Outcome::Handled => Ok(Some(SetComputeUnitPrice { micro_lamports: 1 }.into())),
+                Outcome::Unhandled => Ok(None),
+                Outcome::Failed => Err(anyhow::anyhow!("Invalid instruction")),
+            }
+        }
+    }
+
+    /// Dynamic classifier with an IDL for `program_id` holding a `ping` instruction
+    fn dynamic_classifier(program_id: Pubkey) -> DynamicAnchorClassifier {
+        let idl_dir = std::env::temp_dir().join(format!(
+            "registry-idls-{}-{}",
+            program_id,
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&idl_dir).unwrap();
+
+        let idl = serde_json::json!({
+            "address": program_id.to_string(),
+            "metadata": { "name": "test", "version": "0.1.0", "spec": "0.1.0" },
+            "instructions": [{
+                "name": "ping",
+                "discriminator": DISCRIMINATOR,
+                "accounts": [],
+                "args": [],
+            }],
+        });
+        std::fs::write(idl_dir.join("test.json"), idl.to_string()).unwrap();
+
+        let classifier = DynamicAnchorClassifier::load(&idl_dir).unwrap();
+        std::fs::remove_dir_all(&idl_dir).unwrap();
+
+        assert!(classifier.contains(&program_id));
+        classifier
+    }
+
+    fn transaction(program_id: Pubkey) -> ClassifiableTransaction {
+        let meta: UiTransactionStatusMeta = serde_json::from_value(serde_json::json!({
+            "err": null,
+            "status": { "Ok": null },
+            "fee": 5000,
+            "preBalances": [],
+            "postBalances": [],
+        }))
+        .unwrap();
+
+        ClassifiableTransaction::new(
+            VersionedTransaction {
+                signatures: vec![Signature::new_unique()],
+                message: VersionedMessage::Legacy(Message::new(
+                    &[Instruction::new_with_bytes(
+                        program_id,
+                        &DISCRIMINATOR,
+                        vec![],
+                    )],
+                    Some(&Pubkey::new_unique()),
+                )),
+            },
+            meta,
+        )
+    }
+
+    /// Registry with a test classifier for a program which also has an IDL
+    fn registry(outcome: Outcome) -> (ClassifierRegistry, Pubkey) {
+        let program_id = Pubkey::new_unique();
+
+        let mut registry = ClassifierRegistry::default();
+        registry.register(TestClassifier {
+            program_id,
+            outcome,
+        });
+        registry.set_dynamic_classifier(dynamic_classifier(program_id));
+
+        (registry, program_id)
+    }
+
+    fn classifier_name(
+        registry: &ClassifierRegistry,
+        program_id: &Pubkey,
+    ) -> Result<Option<String>, anyhow::Error> {
+        let txn = transaction(*program_id);
+        let classified = registry.classify_instruction(program_id, &txn, &txn.instructions[0])?;
+
+        Ok(classified.map(|(name, _)| name.to_string()))
+    }
+
+    #[test]
+    fn config_enables_classifiers_by_name_or_program_id() {
+        let program_id = Pubkey::new_unique();
+
+        assert!(ClassifierConfig::default().is_enabled("Test", Some(&program_id)));
+
+        let config = ClassifierConfig {
+            enabled: Some(vec!["Test".to_string(), program_id.to_string()]),
+            disabled: vec![],
+        };
+        assert!(config.is_enabled("Test", None));
+        assert!(config.is_enabled("Other", Some(&program_id)));
+        assert!(!config.is_enabled("Other", Some(&Pubkey::new_unique())));
+        assert!(!config.is_enabled("Other", None));
+    }
+
+    #[test]
+    fn config_disables_enabled_classifiers() {
+        let program_id = Pubkey::new_unique();
+        let config = ClassifierConfig {
+            enabled: Some(vec!["Test".to_string()]),
+            disabled: vec![program_id.to_string()],
+        };
+
+        assert!(config.is_enabled("Test", Some(&Pubkey::new_unique())));
+        assert!(!config.is_enabled("Test", Some(&program_id)));
+    }
+
+    #[test]
+    fn configure_keeps_enabled_default_classifiers() {
+        let mut registry = ClassifierRegistry::with_default_classifiers();
+        registry.configure(&ClassifierConfig {
+            enabled: Some(vec![
+                "SystemProgramClassifier".to_string(),
+                solana_sdk::compute_budget::ID.to_string(),
+                "VoteClassifier".to_string(),
+            ]),
+            disabled: vec![solana_sdk::vote::program::ID.to_string()],
+        });
+
+        let mut classifiers = registry
+            .classifiers()
+            .map(|(program_id, name)| (*program_id, name.to_string()))
+            .collect::<Vec<_>>();
+        classifiers.sort_by(|a, b| a.1.cmp(&b.1));
+
+        assert_eq!(
+            classifiers,
+            vec![
+                (
+                    solana_sdk::compute_budget::ID,
+                    "ComputeBudgetClassifier".to_string()
+                ),
+                (
+                    solana_sdk::system_program::ID,
+                    "SystemProgramClassifier".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn registered_classifier_is_preferred() {
+        let (registry, program_id) = registry(Outcome::Handled);
+
+        assert_eq!(
+            classifier_name(&registry, &program_id).unwrap().as_deref(),
+            Some("TestClassifier")
+        );
+    }
+
+    #[test]
+    fn unhandled_instructions_fall_back_to_dynamic_classifier() {
+        let (registry, program_id) = registry(Outcome::Unhandled);
+
+        assert_eq!(
+            classifier_name(&registry, &program_id).unwrap().as_deref(),
+            Some(DYNAMIC_ANCHOR_CLASSIFIER_NAME)
+        );
+    }
+
+    #[test]
+    fn registered_classifier_errors_are_returned() {
+        let (registry, program_id) = registry(Outcome::Failed);
+
+        assert!(classifier_name(&registry, &program_id).is_err());
+    }
+
+    #[test]
+    fn programs_without_classifier_use_dynamic_classifier() {
+        let (mut registry, program_id) = registry(Outcome::Handled);
+        registry.unregister(&program_id);
+
+        assert_eq!(
+            classifier_name(&registry, &program_id).unwrap().as_deref(),
+            Some(DYNAMIC_ANCHOR_CLASSIFIER_NAME)
+        );
+
+        registry.configure(&ClassifierConfig {
+            enabled: None,
+            disabled: vec![DYNAMIC_ANCHOR_CLASSIFIER_NAME.to_string()],
+        });
+        assert!(registry.dynamic_classifier().is_none());
+        assert_eq!(classifier_name(&registry, &program_id).unwrap(), None);
+    }
+}
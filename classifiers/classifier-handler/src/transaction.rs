@@ -1,11 +1,16 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, str::FromStr};
 
-use actions::{Action, ActionNodeId, ActionTree, AssociatedToken, Token};
-use classifier_core::ClassifiableTransaction;
+use actions::{Action, ActionNodeId, ActionTree, AssociatedToken, System, Token};
+use classifier_core::{AddressLookupTableSource, ClassifiableTransaction};
+use solana_classifier::{OriginalToken, Token2022, TokenProgramId};
 use solana_sdk::pubkey::Pubkey;
+use solana_transaction_status::EncodedTransactionWithStatusMeta;
 use thiserror::Error;
 
-use crate::instruction::{classify_instruction, ClassifyInstructionError};
+use crate::{
+    instruction::{classify_instruction, ClassifyInstructionError},
+    ClassifierRegistry,
+};
 
 #[derive(Debug, Error)]
 pub enum ClassifyError {
@@ -15,20 +20,67 @@ pub enum ClassifyError {
 
 type Result<T> = std::result::Result<T, ClassifyError>;
 
+#[derive(Debug, Error)]
+pub enum ClassifyEncodedTransactionError {
+    #[error("Failed to decode transaction")]
+    DecodeError,
+
+    #[error("No meta data in transaction")]
+    MissingTransactionMeta,
+
+    #[error("Failed to classify transaction: {0}")]
+    ClassifyError(#[from] ClassifyError),
+}
+
+/// Classifies a single transaction outside of its block, into a tree rooted at the
+/// transaction. Block level actions such as fee totals and Jito bundles are not available.
+pub fn classify_encoded_transaction(
+    txn: EncodedTransactionWithStatusMeta,
+    lookup_tables: Option<&dyn AddressLookupTableSource>,
+    registry: &ClassifierRegistry,
+) -> std::result::Result<ActionTree, ClassifyEncodedTransactionError> {
+    let v_txn = txn
+        .transaction
+        .decode()
+        .ok_or(ClassifyEncodedTransactionError::DecodeError)?;
+
+    let meta = txn
+        .meta
+        .ok_or(ClassifyEncodedTransactionError::MissingTransactionMeta)?;
+
+    let c_txn = ClassifiableTransaction::new_with_lookup_tables(v_txn, meta, lookup_tables);
+
+    let mut tree = ActionTree::new(c_txn.clone().into());
+    let transaction_id = tree.root();
+
+    classify_transaction(&c_txn, &mut tree, transaction_id, registry)?;
+
+    Ok(tree)
+}
+
 pub fn classify_transaction(
     txn: &ClassifiableTransaction,
     tree: &mut ActionTree,
     transaction_id: ActionNodeId,
+    registry: &ClassifierRegistry,
 ) -> Result<()> {
     let mut idx = 0;
+    let mut instruction_classifiers: Vec<Option<String>> = vec![None; txn.instructions.len()];
 
     while idx < txn.instructions.len() {
-        let indexes_used = classify_instruction(&txn, idx, tree, transaction_id)?;
+        let indexes_used = classify_instruction(
+            txn,
+            idx,
+            tree,
+            transaction_id,
+            registry,
+            &mut instruction_classifiers,
+        )?;
         idx += indexes_used;
     }
 
     // Update transaction with created token accounts
-    let created_tokens = find_created_token_accounts(tree, transaction_id);
+    let created_tokens = find_created_token_accounts(txn, tree, transaction_id);
     let mut_txn = match tree.get_mut(transaction_id).unwrap().get_mut() {
         Action::ClassifiableTransaction(txn) => txn,
         _ => unreachable!(),
@@ -38,10 +90,42 @@ pub fn classify_transaction(
         mut_txn.created_tokens = Some(created_tokens);
     }
 
+    mut_txn.instruction_classifiers = instruction_classifiers;
+
+    // Update transaction with requested compute budget
+    let (compute_unit_limit, compute_unit_price) = find_compute_budget(tree, transaction_id);
+    let mut_txn = match tree.get_mut(transaction_id).unwrap().get_mut() {
+        Action::ClassifiableTransaction(txn) => txn,
+        _ => unreachable!(),
+    };
+
+    mut_txn.compute_unit_limit = compute_unit_limit;
+    mut_txn.compute_unit_price = compute_unit_price;
+
     Ok(())
 }
 
+/// Compute unit limit and price (in micro-lamports) requested by top level instructions
+fn find_compute_budget(
+    tree: &ActionTree,
+    transaction_id: ActionNodeId,
+) -> (Option<u32>, Option<u64>) {
+    let mut compute_unit_limit = None;
+    let mut compute_unit_price = None;
+
+    for child_id in tree.children(transaction_id) {
+        match tree.get(child_id).unwrap().get() {
+            Action::SetComputeBudgetLimit(limit) => compute_unit_limit = Some(limit.units),
+            Action::SetComputeUnitPrice(price) => compute_unit_price = Some(price.micro_lamports),
+            _ => continue,
+        }
+    }
+
+    (compute_unit_limit, compute_unit_price)
+}
+
 fn find_created_token_accounts(
+    txn: &ClassifiableTransaction,
     tree: &ActionTree,
     transaction_id: ActionNodeId,
 ) -> HashMap<Pubkey, Pubkey> {
@@ -49,6 +133,9 @@ fn find_created_token_accounts(
     // These are not included in pre/post token account balances sent by the RPC.
     let mut created_tokens = HashMap::new();
 
+    // Accounts allocated to a token program, initialized with a mint afterwards
+    let mut created_accounts = Vec::new();
+
     for node_id in tree.descendants(transaction_id) {
         let node = tree.get(node_id).unwrap().get();
 
@@ -68,9 +155,124 @@ fn find_created_token_accounts(
             Action::Token(Token::InitializeAccount3(ix)) => {
                 created_tokens.insert(ix.account, ix.mint);
             }
+            Action::System(System::CreateAccount(ix)) if is_token_program(&ix.owner) => {
+                created_accounts.push(ix.to);
+            }
+            Action::System(System::CreateAccountWithSeed(ix)) if is_token_program(&ix.owner) => {
+                created_accounts.push(ix.to);
+            }
             _ => continue,
         }
     }
 
+    // The initialize instruction may not be in the tree if it was invoked under an
+    // action that does not recurse. Accounts that were not closed again still show up
+    // in the post token balances.
+    for account in created_accounts {
+        if created_tokens.contains_key(&account) {
+            continue;
+        }
+
+        let mint = txn
+            .get_post_token_balance(&account)
+            .ok()
+            .and_then(|balance| Pubkey::from_str(&balance.mint).ok());
+
+        if let Some(mint) = mint {
+            created_tokens.insert(account, mint);
+        }
+    }
+
     created_tokens
 }
+
+fn is_token_program(program_id: &Pubkey) -> bool {
+    *program_id == OriginalToken::ID || *program_id == Token2022::ID
+}
+
+#[cfg(test)]
+mod tests {
+    use actions::NativeTransfer;
+    use solana_sdk::{system_instruction, transaction::Transaction};
+    use solana_transaction_status::{
+        Encodable, EncodedTransaction, TransactionBinaryEncoding, UiTransactionEncoding,
+        UiTransactionStatusMeta,
+    };
+
+    use super::*;
+
+    fn meta() -> UiTransactionStatusMeta {
+        serde_json::from_value(serde_json::json!({
+            "err": null,
+            "status": { "Ok": null },
+            "fee": 5000,
+            "preBalances": [],
+            "postBalances": [],
+        }))
+        .unwrap()
+    }
+
+    fn encoded_transfer(from: Pubkey, to: Pubkey) -> EncodedTransaction {
+        Transaction::new_with_payer(&[system_instruction::transfer(&from, &to, 10)], Some(&from))
+            .encode(UiTransactionEncoding::Base64)
+    }
+
+    #[test]
+    fn classifies_encoded_transaction_into_tree_rooted_at_it() {
+        let (from, to) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let txn = EncodedTransactionWithStatusMeta {
+            transaction: encoded_transfer(from, to),
+            meta: Some(meta()),
+            version: None,
+        };
+
+        let tree = classify_encoded_transaction(
+            txn,
+            None,
+            &ClassifierRegistry::with_default_classifiers(),
+        )
+        .unwrap();
+
+        assert!(matches!(
+            tree.get(tree.root()).unwrap().get(),
+            Action::ClassifiableTransaction(_)
+        ));
+
+        let actions = tree
+            .children(tree.root())
+            .map(|node_id| tree.get(node_id).unwrap().get().clone())
+            .collect::<Vec<_>>();
+        assert_eq!(actions, vec![NativeTransfer::new(from, to, 10).into()]);
+    }
+
+    #[test]
+    fn rejects_transaction_without_meta() {
+        let txn = EncodedTransactionWithStatusMeta {
+            transaction: encoded_transfer(Pubkey::new_unique(), Pubkey::new_unique()),
+            meta: None,
+            version: None,
+        };
+
+        assert!(matches!(
+            classify_encoded_transaction(txn, None, &ClassifierRegistry::default()),
+            Err(ClassifyEncodedTransactionError::MissingTransactionMeta)
+        ));
+    }
+
+    #[test]
+    fn rejects_undecodable_transaction() {
+        let txn = EncodedTransactionWithStatusMeta {
+            transaction: EncodedTransaction::Binary(
+                "not base64".to_string(),
+                TransactionBinaryEncoding::Base64,
+            ),
+            meta: Some(meta()),
+            version: None,
+        };
+
+        assert!(matches!(
+            classify_encoded_transaction(txn, None, &ClassifierRegistry::default()),
+            Err(ClassifyEncodedTransactionError::DecodeError)
+        ));
+    }
+}
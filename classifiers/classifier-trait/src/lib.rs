@@ -9,8 +9,45 @@ pub trait InstructionClassifier {
     /// Used to identify which instruction classifier to use
     const ID: Pubkey;
 
+    /// Instruction account indexes are resolved through `txn`, including addresses
+    /// loaded from lookup tables.
     fn classify_instruction(
         txn: &ClassifiableTransaction,
         ix: &ClassifiableInstruction,
     ) -> ClassifyInstructionResult;
 }
+
+/// Object safe counterpart of `InstructionClassifier`, so classifiers can be registered
+/// by program id at runtime. Implemented for every `InstructionClassifier`.
+pub trait DynInstructionClassifier: Send + Sync {
+    /// Program whose instructions this classifier handles
+    fn program_id(&self) -> Pubkey;
+
+    /// Name used to configure the classifier and to report which classifier handled
+    /// an instruction
+    fn name(&self) -> &str;
+
+    fn classify_instruction(
+        &self,
+        txn: &ClassifiableTransaction,
+        ix: &ClassifiableInstruction,
+    ) -> ClassifyInstructionResult;
+}
+
+impl<T: InstructionClassifier + Send + Sync> DynInstructionClassifier for T {
+    fn program_id(&self) -> Pubkey {
+        T::ID
+    }
+
+    fn name(&self) -> &str {
+        std::any::type_name::<T>()
+    }
+
+    fn classify_instruction(
+        &self,
+        txn: &ClassifiableTransaction,
+        ix: &ClassifiableInstruction,
+    ) -> ClassifyInstructionResult {
+        T::classify_instruction(txn, ix)
+    }
+}
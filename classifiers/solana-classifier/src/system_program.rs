@@ -1,5 +1,6 @@
 use actions::{
-    is_bloxroute_tip_address, is_jito_tip_address, Action, BloxrouteTip, JitoTip, NativeTransfer,
+    is_bloxroute_tip_address, is_jito_tip_address, system_actions, Action, BloxrouteTip, JitoTip,
+    NativeTransfer, System,
 };
 use classifier_core::{ClassifiableInstruction, ClassifiableTransaction};
 use classifier_trait::{ClassifyInstructionResult, InstructionClassifier};
@@ -34,14 +35,67 @@ impl InstructionClassifier for SystemProgramClassifier {
 
         let action = match parsed_ix {
             SystemInstruction::Transfer { lamports } => classify_transfer(lamports, txn, ix)?,
-
-            _ => return Ok(None),
+            SystemInstruction::TransferWithSeed { lamports, .. } => {
+                classify_transfer_with_seed(lamports, txn, ix)?
+            }
+            SystemInstruction::CreateAccount {
+                lamports,
+                space,
+                owner,
+            } => classify_create_account(lamports, space, owner, txn, ix)?,
+            SystemInstruction::CreateAccountWithSeed {
+                base,
+                seed,
+                lamports,
+                space,
+                owner,
+            } => classify_create_account_with_seed(base, seed, lamports, space, owner, txn, ix)?,
+            SystemInstruction::Assign { owner } => classify_assign(owner, txn, ix)?,
+            SystemInstruction::AssignWithSeed { base, seed, owner } => {
+                classify_assign_with_seed(base, seed, owner, txn, ix)?
+            }
+            SystemInstruction::Allocate { space } => classify_allocate(space, txn, ix)?,
+            SystemInstruction::AllocateWithSeed {
+                base,
+                seed,
+                space,
+                owner,
+            } => classify_allocate_with_seed(base, seed, space, owner, txn, ix)?,
+            SystemInstruction::AdvanceNonceAccount => classify_advance_nonce_account(txn, ix)?,
+            SystemInstruction::WithdrawNonceAccount(lamports) => {
+                classify_withdraw_nonce_account(lamports, txn, ix)?
+            }
+            SystemInstruction::InitializeNonceAccount(authority) => {
+                classify_initialize_nonce_account(authority, txn, ix)?
+            }
+            SystemInstruction::AuthorizeNonceAccount(new_authority) => {
+                classify_authorize_nonce_account(new_authority, txn, ix)?
+            }
+            SystemInstruction::UpgradeNonceAccount => classify_upgrade_nonce_account(txn, ix)?,
         };
 
         Ok(Some(action))
     }
 }
 
+fn get_account(
+    txn: &ClassifiableTransaction,
+    ix: &ClassifiableInstruction,
+    position: usize,
+) -> Result<Pubkey> {
+    txn.get_instruction_account(ix, position)
+        .map(|account| account.pubkey)
+        .ok_or_else(|| ClassifySystemInstructionError::MissingAccount)
+}
+
+fn check_account_len_gte(ix: &ClassifiableInstruction, expected: usize) -> Result<()> {
+    if ix.accounts.len() < expected {
+        return Err(ClassifySystemInstructionError::InvalidInstruction);
+    }
+
+    Ok(())
+}
+
 /// # Account references
 ///   0. `[WRITE, SIGNER]` Funding account
 ///   1. `[WRITE]` Recipient account
@@ -62,11 +116,558 @@ fn classify_transfer(
         .get_pubkey(ix.accounts[1])
         .ok_or_else(|| ClassifySystemInstructionError::MissingAccount)?;
 
+    Ok(transfer_action(funding, recipient, lamports))
+}
+
+fn transfer_action(funding: Pubkey, recipient: Pubkey, lamports: u64) -> Action {
     if is_jito_tip_address(&recipient) {
-        Ok(JitoTip::new(funding, lamports).into())
+        JitoTip::new(funding, lamports).into()
     } else if is_bloxroute_tip_address(&recipient) {
-        Ok(BloxrouteTip::new(funding, lamports).into())
+        BloxrouteTip::new(funding, lamports).into()
     } else {
-        Ok(NativeTransfer::new(funding, recipient, lamports).into())
+        NativeTransfer::new(funding, recipient, lamports).into()
+    }
+}
+
+/// # Account references
+///   0. `[WRITE]` Funding account
+///   1. `[SIGNER]` Base for funding account
+///   2. `[WRITE]` Recipient account
+fn classify_transfer_with_seed(
+    lamports: u64,
+    txn: &ClassifiableTransaction,
+    ix: &ClassifiableInstruction,
+) -> Result<Action> {
+    check_account_len_gte(ix, 3)?;
+
+    let funding = get_account(txn, ix, 0)?;
+    let recipient = get_account(txn, ix, 2)?;
+
+    Ok(transfer_action(funding, recipient, lamports))
+}
+
+/// # Account references
+///   0. `[WRITE, SIGNER]` Funding account
+///   1. `[WRITE, SIGNER]` New account
+fn classify_create_account(
+    lamports: u64,
+    space: u64,
+    owner: Pubkey,
+    txn: &ClassifiableTransaction,
+    ix: &ClassifiableInstruction,
+) -> Result<Action> {
+    check_account_len_gte(ix, 2)?;
+
+    Ok(System::CreateAccount(system_actions::CreateAccount {
+        from: get_account(txn, ix, 0)?,
+        to: get_account(txn, ix, 1)?,
+        lamports,
+        space,
+        owner,
+    })
+    .into())
+}
+
+/// # Account references
+///   0. `[WRITE, SIGNER]` Funding account
+///   1. `[WRITE]` Created account
+///   2. `[SIGNER]` (optional) Base account
+fn classify_create_account_with_seed(
+    base: Pubkey,
+    seed: String,
+    lamports: u64,
+    space: u64,
+    owner: Pubkey,
+    txn: &ClassifiableTransaction,
+    ix: &ClassifiableInstruction,
+) -> Result<Action> {
+    check_account_len_gte(ix, 2)?;
+
+    Ok(
+        System::CreateAccountWithSeed(system_actions::CreateAccountWithSeed {
+            from: get_account(txn, ix, 0)?,
+            to: get_account(txn, ix, 1)?,
+            base,
+            seed,
+            lamports,
+            space,
+            owner,
+        })
+        .into(),
+    )
+}
+
+/// # Account references
+///   0. `[WRITE, SIGNER]` Assigned account public key
+fn classify_assign(
+    owner: Pubkey,
+    txn: &ClassifiableTransaction,
+    ix: &ClassifiableInstruction,
+) -> Result<Action> {
+    check_account_len_gte(ix, 1)?;
+
+    Ok(System::Assign(system_actions::Assign {
+        account: get_account(txn, ix, 0)?,
+        owner,
+    })
+    .into())
+}
+
+/// # Account references
+///   0. `[WRITE]` Assigned account
+///   1. `[SIGNER]` Base account
+fn classify_assign_with_seed(
+    base: Pubkey,
+    seed: String,
+    owner: Pubkey,
+    txn: &ClassifiableTransaction,
+    ix: &ClassifiableInstruction,
+) -> Result<Action> {
+    check_account_len_gte(ix, 1)?;
+
+    Ok(System::AssignWithSeed(system_actions::AssignWithSeed {
+        account: get_account(txn, ix, 0)?,
+        base,
+        seed,
+        owner,
+    })
+    .into())
+}
+
+/// # Account references
+///   0. `[WRITE, SIGNER]` New account
+fn classify_allocate(
+    space: u64,
+    txn: &ClassifiableTransaction,
+    ix: &ClassifiableInstruction,
+) -> Result<Action> {
+    check_account_len_gte(ix, 1)?;
+
+    Ok(System::Allocate(system_actions::Allocate {
+        account: get_account(txn, ix, 0)?,
+        space,
+    })
+    .into())
+}
+
+/// # Account references
+///   0. `[WRITE]` Allocated account
+///   1. `[SIGNER]` Base account
+fn classify_allocate_with_seed(
+    base: Pubkey,
+    seed: String,
+    space: u64,
+    owner: Pubkey,
+    txn: &ClassifiableTransaction,
+    ix: &ClassifiableInstruction,
+) -> Result<Action> {
+    check_account_len_gte(ix, 1)?;
+
+    Ok(System::AllocateWithSeed(system_actions::AllocateWithSeed {
+        account: get_account(txn, ix, 0)?,
+        base,
+        seed,
+        space,
+        owner,
+    })
+    .into())
+}
+
+/// # Account references
+///   0. `[WRITE]` Nonce account
+///   1. `[]` RecentBlockhashes sysvar
+///   2. `[SIGNER]` Nonce authority
+fn classify_advance_nonce_account(
+    txn: &ClassifiableTransaction,
+    ix: &ClassifiableInstruction,
+) -> Result<Action> {
+    check_account_len_gte(ix, 3)?;
+
+    Ok(
+        System::AdvanceNonceAccount(system_actions::AdvanceNonceAccount {
+            nonce_account: get_account(txn, ix, 0)?,
+            authority: get_account(txn, ix, 2)?,
+        })
+        .into(),
+    )
+}
+
+/// # Account references
+///   0. `[WRITE]` Nonce account
+///   1. `[WRITE]` Recipient account
+///   2. `[]` RecentBlockhashes sysvar
+///   3. `[]` Rent sysvar
+///   4. `[SIGNER]` Nonce authority
+fn classify_withdraw_nonce_account(
+    lamports: u64,
+    txn: &ClassifiableTransaction,
+    ix: &ClassifiableInstruction,
+) -> Result<Action> {
+    check_account_len_gte(ix, 5)?;
+
+    Ok(
+        System::WithdrawNonceAccount(system_actions::WithdrawNonceAccount {
+            nonce_account: get_account(txn, ix, 0)?,
+            to: get_account(txn, ix, 1)?,
+            authority: get_account(txn, ix, 4)?,
+            lamports,
+        })
+        .into(),
+    )
+}
+
+/// # Account references
+///   0. `[WRITE]` Nonce account
+///   1. `[]` RecentBlockhashes sysvar
+///   2. `[]` Rent sysvar
+fn classify_initialize_nonce_account(
+    authority: Pubkey,
+    txn: &ClassifiableTransaction,
+    ix: &ClassifiableInstruction,
+) -> Result<Action> {
+    check_account_len_gte(ix, 1)?;
+
+    Ok(
+        System::InitializeNonceAccount(system_actions::InitializeNonceAccount {
+            nonce_account: get_account(txn, ix, 0)?,
+            authority,
+        })
+        .into(),
+    )
+}
+
+/// # Account references
+///   0. `[WRITE]` Nonce account
+///   1. `[SIGNER]` Nonce authority
+fn classify_authorize_nonce_account(
+    new_authority: Pubkey,
+    txn: &ClassifiableTransaction,
+    ix: &ClassifiableInstruction,
+) -> Result<Action> {
+    check_account_len_gte(ix, 2)?;
+
+    Ok(
+        System::AuthorizeNonceAccount(system_actions::AuthorizeNonceAccount {
+            nonce_account: get_account(txn, ix, 0)?,
+            authority: get_account(txn, ix, 1)?,
+            new_authority,
+        })
+        .into(),
+    )
+}
+
+/// # Account references
+///   0. `[WRITE]` Nonce account
+fn classify_upgrade_nonce_account(
+    txn: &ClassifiableTransaction,
+    ix: &ClassifiableInstruction,
+) -> Result<Action> {
+    check_account_len_gte(ix, 1)?;
+
+    Ok(
+        System::UpgradeNonceAccount(system_actions::UpgradeNonceAccount {
+            nonce_account: get_account(txn, ix, 0)?,
+        })
+        .into(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use actions::JITO_TIP_ADDRESSES;
+    use solana_sdk::{
+        hash::Hash,
+        instruction::Instruction,
+        message::{Message, VersionedMessage},
+        nonce,
+        signature::Signature,
+        system_instruction,
+        transaction::VersionedTransaction,
+    };
+    use solana_transaction_status::UiTransactionStatusMeta;
+
+    use super::*;
+
+    /// Classify the instruction at `index` of a transaction holding `instructions`
+    fn classify(instructions: &[Instruction], index: usize) -> Action {
+        let meta: UiTransactionStatusMeta = serde_json::from_value(serde_json::json!({
+            "err": null,
+            "status": { "Ok": null },
+            "fee": 5000,
+            "preBalances": [],
+            "postBalances": [],
+        }))
+        .unwrap();
+
+        let payer = instructions[0].accounts[0].pubkey;
+        let txn = ClassifiableTransaction::new(
+            VersionedTransaction {
+                signatures: vec![Signature::new_unique()],
+                message: VersionedMessage::Legacy(Message::new_with_blockhash(
+                    instructions,
+                    Some(&payer),
+                    &Hash::new_unique(),
+                )),
+            },
+            meta,
+        );
+
+        SystemProgramClassifier::classify_instruction(&txn, &txn.instructions[index])
+            .unwrap()
+            .unwrap()
+    }
+
+    #[test]
+    fn classifies_transfer() {
+        let (from, to) = (Pubkey::new_unique(), Pubkey::new_unique());
+
+        assert_eq!(
+            classify(&[system_instruction::transfer(&from, &to, 10)], 0),
+            NativeTransfer::new(from, to, 10).into()
+        );
+    }
+
+    #[test]
+    fn classifies_transfer_to_jito_tip_account() {
+        let from = Pubkey::new_unique();
+        let ix = system_instruction::transfer(&from, &JITO_TIP_ADDRESSES[0], 10);
+
+        assert_eq!(classify(&[ix], 0), JitoTip::new(from, 10).into());
+    }
+
+    #[test]
+    fn classifies_transfer_with_seed() {
+        let (base, owner, to) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let from = Pubkey::create_with_seed(&base, "seed", &owner).unwrap();
+        let ix = system_instruction::transfer_with_seed(
+            &from,
+            &base,
+            "seed".to_string(),
+            &owner,
+            &to,
+            10,
+        );
+
+        assert_eq!(classify(&[ix], 0), NativeTransfer::new(from, to, 10).into());
+    }
+
+    #[test]
+    fn classifies_create_account() {
+        let (from, to, owner) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+
+        assert_eq!(
+            classify(
+                &[system_instruction::create_account(
+                    &from, &to, 10, 165, &owner
+                )],
+                0
+            ),
+            System::CreateAccount(system_actions::CreateAccount {
+                from,
+                to,
+                lamports: 10,
+                space: 165,
+                owner,
+            })
+            .into()
+        );
+    }
+
+    #[test]
+    fn classifies_create_account_with_seed() {
+        let (from, base, owner) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let to = Pubkey::create_with_seed(&base, "seed", &owner).unwrap();
+        let ix = system_instruction::create_account_with_seed(
+            &from, &to, &base, "seed", 10, 165, &owner,
+        );
+
+        assert_eq!(
+            classify(&[ix], 0),
+            System::CreateAccountWithSeed(system_actions::CreateAccountWithSeed {
+                from,
+                to,
+                base,
+                seed: "seed".to_string(),
+                lamports: 10,
+                space: 165,
+                owner,
+            })
+            .into()
+        );
+    }
+
+    #[test]
+    fn classifies_assign() {
+        let (account, owner) = (Pubkey::new_unique(), Pubkey::new_unique());
+
+        assert_eq!(
+            classify(&[system_instruction::assign(&account, &owner)], 0),
+            System::Assign(system_actions::Assign { account, owner }).into()
+        );
+    }
+
+    #[test]
+    fn classifies_assign_with_seed() {
+        let (base, owner) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let account = Pubkey::create_with_seed(&base, "seed", &owner).unwrap();
+        let ix = system_instruction::assign_with_seed(&account, &base, "seed", &owner);
+
+        assert_eq!(
+            classify(&[ix], 0),
+            System::AssignWithSeed(system_actions::AssignWithSeed {
+                account,
+                base,
+                seed: "seed".to_string(),
+                owner,
+            })
+            .into()
+        );
+    }
+
+    #[test]
+    fn classifies_allocate() {
+        let account = Pubkey::new_unique();
+
+        assert_eq!(
+            classify(&[system_instruction::allocate(&account, 165)], 0),
+            System::Allocate(system_actions::Allocate {
+                account,
+                space: 165,
+            })
+            .into()
+        );
+    }
+
+    #[test]
+    fn classifies_allocate_with_seed() {
+        let (base, owner) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let account = Pubkey::create_with_seed(&base, "seed", &owner).unwrap();
+        let ix = system_instruction::allocate_with_seed(&account, &base, "seed", 165, &owner);
+
+        assert_eq!(
+            classify(&[ix], 0),
+            System::AllocateWithSeed(system_actions::AllocateWithSeed {
+                account,
+                base,
+                seed: "seed".to_string(),
+                space: 165,
+                owner,
+            })
+            .into()
+        );
+    }
+
+    #[test]
+    fn classifies_advance_nonce_account() {
+        let (nonce_account, authority) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let ix = system_instruction::advance_nonce_account(&nonce_account, &authority);
+
+        assert_eq!(
+            classify(&[ix], 0),
+            System::AdvanceNonceAccount(system_actions::AdvanceNonceAccount {
+                nonce_account,
+                authority,
+            })
+            .into()
+        );
+    }
+
+    #[test]
+    fn classifies_withdraw_nonce_account() {
+        let (nonce_account, authority, to) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let ix = system_instruction::withdraw_nonce_account(&nonce_account, &authority, &to, 10);
+
+        assert_eq!(
+            classify(&[ix], 0),
+            System::WithdrawNonceAccount(system_actions::WithdrawNonceAccount {
+                nonce_account,
+                to,
+                authority,
+                lamports: 10,
+            })
+            .into()
+        );
+    }
+
+    #[test]
+    fn classifies_initialize_nonce_account() {
+        let (from, nonce_account, authority) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let instructions =
+            system_instruction::create_nonce_account(&from, &nonce_account, &authority, 10);
+
+        assert_eq!(
+            classify(&instructions, 0),
+            System::CreateAccount(system_actions::CreateAccount {
+                from,
+                to: nonce_account,
+                lamports: 10,
+                space: nonce::State::size() as u64,
+                owner: solana_sdk::system_program::ID,
+            })
+            .into()
+        );
+        assert_eq!(
+            classify(&instructions, 1),
+            System::InitializeNonceAccount(system_actions::InitializeNonceAccount {
+                nonce_account,
+                authority,
+            })
+            .into()
+        );
+    }
+
+    #[test]
+    fn classifies_authorize_nonce_account() {
+        let (nonce_account, authority, new_authority) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let ix =
+            system_instruction::authorize_nonce_account(&nonce_account, &authority, &new_authority);
+
+        assert_eq!(
+            classify(&[ix], 0),
+            System::AuthorizeNonceAccount(system_actions::AuthorizeNonceAccount {
+                nonce_account,
+                authority,
+                new_authority,
+            })
+            .into()
+        );
+    }
+
+    #[test]
+    fn classifies_upgrade_nonce_account() {
+        let nonce_account = Pubkey::new_unique();
+
+        assert_eq!(
+            classify(
+                &[system_instruction::upgrade_nonce_account(nonce_account)],
+                0
+            ),
+            System::UpgradeNonceAccount(system_actions::UpgradeNonceAccount { nonce_account })
+                .into()
+        );
     }
 }
@@ -3,7 +3,23 @@ use anchor_spl::token_2022;
 use classifier_core::{ClassifiableInstruction, ClassifiableTransaction};
 use classifier_trait::{ClassifyInstructionResult, InstructionClassifier};
 use solana_sdk::{program_option::COption, pubkey::Pubkey};
-use spl_token::instruction::{AuthorityType, TokenInstruction};
+use spl_token_2022::{
+    extension::{
+        confidential_transfer::instruction::ConfidentialTransferInstruction,
+        metadata_pointer::instruction::{
+            InitializeInstructionData as InitializeMetadataPointerData, MetadataPointerInstruction,
+            UpdateInstructionData as UpdateMetadataPointerData,
+        },
+        transfer_fee::instruction::TransferFeeInstruction,
+        transfer_hook::instruction::{
+            InitializeInstructionData as InitializeTransferHookData, TransferHookInstruction,
+            UpdateInstructionData as UpdateTransferHookData,
+        },
+    },
+    instruction::{
+        decode_instruction_data, decode_instruction_type, TokenInstruction as Token2022Instruction,
+    },
+};
 use std::marker::PhantomData;
 
 pub trait TokenProgramId {
@@ -22,82 +38,159 @@ impl TokenProgramId for Token2022 {
     const ID: Pubkey = token_2022::ID;
 }
 
-// Generic classifier that uses the marker type
+// Classifier for each token program, keyed by the marker type
 pub struct GenericTokenProgramClassifier<T: TokenProgramId> {
     phantom: PhantomData<T>,
 }
 
-impl<T: TokenProgramId> InstructionClassifier for GenericTokenProgramClassifier<T> {
-    const ID: Pubkey = T::ID;
+impl<T: TokenProgramId> GenericTokenProgramClassifier<T> {
+    pub fn new() -> Self {
+        Self {
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<T: TokenProgramId> Default for GenericTokenProgramClassifier<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl InstructionClassifier for GenericTokenProgramClassifier<OriginalToken> {
+    const ID: Pubkey = OriginalToken::ID;
 
     fn classify_instruction(
         txn: &ClassifiableTransaction,
         ix: &ClassifiableInstruction,
     ) -> ClassifyInstructionResult {
-        let token_instruction = TokenInstruction::unpack(&ix.data)
+        // The original program's instructions are a prefix of Token-2022's, with the same
+        // layout, so both are unpacked by Token-2022
+        let token_instruction = Token2022Instruction::unpack(&ix.data)
             .map_err(|e| anyhow::anyhow!("Failed to unpack token instruction: {:?}", e))?;
 
+        classify_shared_instruction(txn, ix, &token_instruction).unwrap_or_else(|| {
+            Err(anyhow::anyhow!(
+                "Token-2022 instruction sent to the token program: {:?}",
+                token_instruction
+            ))
+        })
+    }
+}
+
+/// Token-2022 shares the original program's instruction layout for its first 25
+/// instructions, followed by instructions for its extensions.
+impl InstructionClassifier for GenericTokenProgramClassifier<Token2022> {
+    const ID: Pubkey = Token2022::ID;
+
+    fn classify_instruction(
+        txn: &ClassifiableTransaction,
+        ix: &ClassifiableInstruction,
+    ) -> ClassifyInstructionResult {
+        let token_instruction = Token2022Instruction::unpack(&ix.data)
+            .map_err(|e| anyhow::anyhow!("Failed to unpack token-2022 instruction: {:?}", e))?;
+
+        if let Some(result) = classify_shared_instruction(txn, ix, &token_instruction) {
+            return result;
+        }
+
+        // Extension instructions are prefixed by the extension's instruction type
+        let extension_data = &ix.data[1..];
+
         match token_instruction {
-            TokenInstruction::InitializeMint { .. } => classify_initialize_mint(txn, ix),
-            TokenInstruction::InitializeAccount => classify_initialize_account(txn, ix),
-            TokenInstruction::InitializeMultisig { .. } => classify_initialize_multisig(txn, ix),
-            TokenInstruction::Transfer { amount } => classify_transfer(txn, ix, amount),
-            TokenInstruction::Approve { amount } => classify_approve(txn, ix, amount),
-            TokenInstruction::Revoke => classify_revoke(txn, ix),
-            TokenInstruction::SetAuthority {
-                authority_type,
-                new_authority,
-            } => classify_set_authority(txn, ix, authority_type, new_authority),
-            TokenInstruction::MintTo { amount } => classify_mint_to(txn, ix, amount),
-            TokenInstruction::Burn { amount } => classify_burn(txn, ix, amount),
-            TokenInstruction::CloseAccount => classify_close_account(txn, ix),
-            TokenInstruction::FreezeAccount => classify_freeze_account(txn, ix),
-            TokenInstruction::ThawAccount => classify_thaw_account(txn, ix),
-            TokenInstruction::TransferChecked { amount, decimals } => {
-                classify_transfer_checked(txn, ix, amount, decimals)
-            }
-            TokenInstruction::ApproveChecked { amount, decimals } => {
-                classify_approve_checked(txn, ix, amount, decimals)
+            Token2022Instruction::InitializeMintCloseAuthority { close_authority } => {
+                classify_initialize_mint_close_authority(txn, ix, close_authority)
             }
-            TokenInstruction::MintToChecked { amount, decimals } => {
-                classify_mint_to_checked(txn, ix, amount, decimals)
+            Token2022Instruction::TransferFeeExtension => {
+                classify_transfer_fee_extension(txn, ix, extension_data)
             }
-            TokenInstruction::BurnChecked { amount, decimals } => {
-                classify_burn_checked(txn, ix, amount, decimals)
+            Token2022Instruction::ConfidentialTransferExtension => {
+                classify_confidential_transfer_extension(txn, ix, extension_data)
             }
-            TokenInstruction::InitializeAccount2 { owner } => {
-                classify_initialize_account2(txn, ix, owner)
+            Token2022Instruction::TransferHookExtension => {
+                classify_transfer_hook_extension(txn, ix, extension_data)
             }
-            TokenInstruction::SyncNative => classify_sync_native(txn, ix),
-            TokenInstruction::InitializeAccount3 { owner } => {
-                classify_initialize_account3(txn, ix, owner)
+            Token2022Instruction::MetadataPointerExtension => {
+                classify_metadata_pointer_extension(txn, ix, extension_data)
             }
-            TokenInstruction::InitializeMultisig2 { .. } => classify_initialize_multisig2(txn, ix),
-            TokenInstruction::InitializeMint2 {
-                decimals,
-                mint_authority,
-                freeze_authority,
-            } => classify_initialize_mint2(
-                txn,
-                ix,
-                decimals,
-                mint_authority,
-                freeze_authority.into(),
-            ),
-            TokenInstruction::GetAccountDataSize => classify_get_account_data_size(txn, ix),
-            TokenInstruction::InitializeImmutableOwner => {
-                classify_initialize_immutable_owner(txn, ix)
+            Token2022Instruction::InitializeNonTransferableMint => {
+                classify_initialize_non_transferable_mint(txn, ix)
             }
-            TokenInstruction::AmountToUiAmount { amount } => {
-                classify_amount_to_ui_amount(txn, ix, amount)
-            }
-            TokenInstruction::UiAmountToAmount { ui_amount } => {
-                classify_ui_amount_to_amount(txn, ix, ui_amount)
+            Token2022Instruction::InitializePermanentDelegate { delegate } => {
+                classify_initialize_permanent_delegate(txn, ix, delegate)
             }
+
+            // Remaining extensions are recorded as program invocations
+            _ => Ok(None),
         }
     }
 }
 
+/// Classify the instructions both token programs share. None for Token-2022 extension
+/// instructions.
+fn classify_shared_instruction(
+    txn: &ClassifiableTransaction,
+    ix: &ClassifiableInstruction,
+    token_instruction: &Token2022Instruction,
+) -> Option<ClassifyInstructionResult> {
+    let result = match *token_instruction {
+        Token2022Instruction::InitializeMint { .. } => classify_initialize_mint(txn, ix),
+        Token2022Instruction::InitializeAccount => classify_initialize_account(txn, ix),
+        Token2022Instruction::InitializeMultisig { .. } => classify_initialize_multisig(txn, ix),
+        #[allow(deprecated)]
+        Token2022Instruction::Transfer { amount } => classify_transfer(txn, ix, amount),
+        Token2022Instruction::Approve { amount } => classify_approve(txn, ix, amount),
+        Token2022Instruction::Revoke => classify_revoke(txn, ix),
+        Token2022Instruction::SetAuthority {
+            ref authority_type,
+            new_authority,
+        } => classify_set_authority(txn, ix, authority_type.clone() as u8, new_authority),
+        Token2022Instruction::MintTo { amount } => classify_mint_to(txn, ix, amount),
+        Token2022Instruction::Burn { amount } => classify_burn(txn, ix, amount),
+        Token2022Instruction::CloseAccount => classify_close_account(txn, ix),
+        Token2022Instruction::FreezeAccount => classify_freeze_account(txn, ix),
+        Token2022Instruction::ThawAccount => classify_thaw_account(txn, ix),
+        Token2022Instruction::TransferChecked { amount, decimals } => {
+            classify_transfer_checked(txn, ix, amount, decimals)
+        }
+        Token2022Instruction::ApproveChecked { amount, decimals } => {
+            classify_approve_checked(txn, ix, amount, decimals)
+        }
+        Token2022Instruction::MintToChecked { amount, decimals } => {
+            classify_mint_to_checked(txn, ix, amount, decimals)
+        }
+        Token2022Instruction::BurnChecked { amount, decimals } => {
+            classify_burn_checked(txn, ix, amount, decimals)
+        }
+        Token2022Instruction::InitializeAccount2 { owner } => {
+            classify_initialize_account2(txn, ix, owner)
+        }
+        Token2022Instruction::SyncNative => classify_sync_native(txn, ix),
+        Token2022Instruction::InitializeAccount3 { owner } => {
+            classify_initialize_account3(txn, ix, owner)
+        }
+        Token2022Instruction::InitializeMultisig2 { .. } => classify_initialize_multisig2(txn, ix),
+        Token2022Instruction::InitializeMint2 {
+            decimals,
+            mint_authority,
+            freeze_authority,
+        } => classify_initialize_mint2(txn, ix, decimals, mint_authority, freeze_authority.into()),
+        Token2022Instruction::GetAccountDataSize { .. } => classify_get_account_data_size(txn, ix),
+        Token2022Instruction::InitializeImmutableOwner => {
+            classify_initialize_immutable_owner(txn, ix)
+        }
+        Token2022Instruction::AmountToUiAmount { amount } => {
+            classify_amount_to_ui_amount(txn, ix, amount)
+        }
+        Token2022Instruction::UiAmountToAmount { ui_amount } => {
+            classify_ui_amount_to_amount(txn, ix, ui_amount)
+        }
+        _ => return None,
+    };
+
+    Some(result)
+}
+
 fn check_account_len(ix: &ClassifiableInstruction, expected: usize) -> Result<(), anyhow::Error> {
     if ix.accounts.len() != expected {
         return Err(anyhow::anyhow!(
@@ -125,6 +218,16 @@ fn check_account_len_gte(
     Ok(())
 }
 
+fn get_account_pubkey(
+    txn: &ClassifiableTransaction,
+    ix: &ClassifiableInstruction,
+    position: usize,
+) -> Result<Pubkey, anyhow::Error> {
+    txn.get_instruction_account(ix, position)
+        .map(|account| account.pubkey)
+        .ok_or_else(|| anyhow::anyhow!("Failed to get account pubkey from transaction"))
+}
+
 fn classify_initialize_mint(
     txn: &ClassifiableTransaction,
     ix: &ClassifiableInstruction,
@@ -258,7 +361,7 @@ fn classify_revoke(
 fn classify_set_authority(
     txn: &ClassifiableTransaction,
     ix: &ClassifiableInstruction,
-    authority_type: AuthorityType,
+    authority_type: u8,
     new_authority: COption<Pubkey>,
 ) -> ClassifyInstructionResult {
     check_account_len_gte(ix, 2)?;
@@ -274,7 +377,7 @@ fn classify_set_authority(
         Token::SetAuthority(token_actions::SetAuthority {
             account,
             current_authority,
-            authority_type: authority_type as u8,
+            authority_type,
             new_authority: new_authority.into(),
         })
         .into(),
@@ -730,3 +833,251 @@ fn classify_ui_amount_to_amount(
         .into(),
     ))
 }
+
+//
+// Token-2022 extensions
+//
+
+fn classify_initialize_mint_close_authority(
+    txn: &ClassifiableTransaction,
+    ix: &ClassifiableInstruction,
+    close_authority: COption<Pubkey>,
+) -> ClassifyInstructionResult {
+    check_account_len(ix, 1)?;
+
+    let mint = get_account_pubkey(txn, ix, 0)?;
+
+    Ok(Some(
+        Token::InitializeMintCloseAuthority(token_actions::InitializeMintCloseAuthority {
+            mint,
+            close_authority: close_authority.into(),
+        })
+        .into(),
+    ))
+}
+
+fn classify_transfer_fee_extension(
+    txn: &ClassifiableTransaction,
+    ix: &ClassifiableInstruction,
+    data: &[u8],
+) -> ClassifyInstructionResult {
+    let instruction = TransferFeeInstruction::unpack(data)
+        .map_err(|e| anyhow::anyhow!("Failed to unpack transfer fee instruction: {:?}", e))?;
+
+    let action = match instruction {
+        TransferFeeInstruction::InitializeTransferFeeConfig {
+            transfer_fee_config_authority,
+            withdraw_withheld_authority,
+            transfer_fee_basis_points,
+            maximum_fee,
+        } => {
+            check_account_len(ix, 1)?;
+
+            Token::InitializeTransferFeeConfig(token_actions::InitializeTransferFeeConfig {
+                mint: get_account_pubkey(txn, ix, 0)?,
+                transfer_fee_config_authority: transfer_fee_config_authority.into(),
+                withdraw_withheld_authority: withdraw_withheld_authority.into(),
+                transfer_fee_basis_points,
+                maximum_fee,
+            })
+        }
+        TransferFeeInstruction::TransferCheckedWithFee {
+            amount,
+            decimals,
+            fee,
+        } => {
+            check_account_len_gte(ix, 4)?;
+
+            Token::TransferCheckedWithFee(token_actions::TransferCheckedWithFee {
+                source: get_account_pubkey(txn, ix, 0)?,
+                mint: get_account_pubkey(txn, ix, 1)?,
+                destination: get_account_pubkey(txn, ix, 2)?,
+                owner: get_account_pubkey(txn, ix, 3)?,
+                amount,
+                decimals,
+                fee,
+            })
+        }
+        TransferFeeInstruction::WithdrawWithheldTokensFromMint => {
+            check_account_len_gte(ix, 3)?;
+
+            Token::WithdrawWithheldTokensFromMint(token_actions::WithdrawWithheldTokensFromMint {
+                mint: get_account_pubkey(txn, ix, 0)?,
+                destination: get_account_pubkey(txn, ix, 1)?,
+                authority: get_account_pubkey(txn, ix, 2)?,
+            })
+        }
+        TransferFeeInstruction::WithdrawWithheldTokensFromAccounts { num_token_accounts } => {
+            let num_token_accounts = num_token_accounts as usize;
+            check_account_len_gte(ix, 3 + num_token_accounts)?;
+
+            // Source accounts follow any multisig signers
+            let sources = (ix.accounts.len() - num_token_accounts..ix.accounts.len())
+                .map(|position| get_account_pubkey(txn, ix, position))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            Token::WithdrawWithheldTokensFromAccounts(
+                token_actions::WithdrawWithheldTokensFromAccounts {
+                    mint: get_account_pubkey(txn, ix, 0)?,
+                    destination: get_account_pubkey(txn, ix, 1)?,
+                    authority: get_account_pubkey(txn, ix, 2)?,
+                    sources,
+                },
+            )
+        }
+        TransferFeeInstruction::HarvestWithheldTokensToMint => {
+            check_account_len_gte(ix, 1)?;
+
+            let sources = (1..ix.accounts.len())
+                .map(|position| get_account_pubkey(txn, ix, position))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            Token::HarvestWithheldTokensToMint(token_actions::HarvestWithheldTokensToMint {
+                mint: get_account_pubkey(txn, ix, 0)?,
+                sources,
+            })
+        }
+        TransferFeeInstruction::SetTransferFee {
+            transfer_fee_basis_points,
+            maximum_fee,
+        } => {
+            check_account_len_gte(ix, 2)?;
+
+            Token::SetTransferFee(token_actions::SetTransferFee {
+                mint: get_account_pubkey(txn, ix, 0)?,
+                authority: get_account_pubkey(txn, ix, 1)?,
+                transfer_fee_basis_points,
+                maximum_fee,
+            })
+        }
+    };
+
+    Ok(Some(action.into()))
+}
+
+fn classify_confidential_transfer_extension(
+    txn: &ClassifiableTransaction,
+    ix: &ClassifiableInstruction,
+    data: &[u8],
+) -> ClassifyInstructionResult {
+    let instruction: ConfidentialTransferInstruction = decode_instruction_type(data)
+        .map_err(|e| anyhow::anyhow!("Failed to decode confidential transfer: {:?}", e))?;
+
+    check_account_len_gte(ix, 1)?;
+
+    Ok(Some(
+        Token::ConfidentialTransfer(token_actions::ConfidentialTransfer {
+            instruction: format!("{:?}", instruction),
+            account: get_account_pubkey(txn, ix, 0)?,
+        })
+        .into(),
+    ))
+}
+
+fn classify_transfer_hook_extension(
+    txn: &ClassifiableTransaction,
+    ix: &ClassifiableInstruction,
+    data: &[u8],
+) -> ClassifyInstructionResult {
+    let instruction: TransferHookInstruction = decode_instruction_type(data)
+        .map_err(|e| anyhow::anyhow!("Failed to decode transfer hook instruction: {:?}", e))?;
+
+    let action = match instruction {
+        TransferHookInstruction::Initialize => {
+            check_account_len(ix, 1)?;
+
+            let data: &InitializeTransferHookData = decode_instruction_data(data)
+                .map_err(|e| anyhow::anyhow!("Failed to decode transfer hook data: {:?}", e))?;
+
+            Token::InitializeTransferHook(token_actions::InitializeTransferHook {
+                mint: get_account_pubkey(txn, ix, 0)?,
+                authority: data.authority.into(),
+                program_id: data.program_id.into(),
+            })
+        }
+        TransferHookInstruction::Update => {
+            check_account_len_gte(ix, 2)?;
+
+            let data: &UpdateTransferHookData = decode_instruction_data(data)
+                .map_err(|e| anyhow::anyhow!("Failed to decode transfer hook data: {:?}", e))?;
+
+            Token::UpdateTransferHook(token_actions::UpdateTransferHook {
+                mint: get_account_pubkey(txn, ix, 0)?,
+                authority: get_account_pubkey(txn, ix, 1)?,
+                program_id: data.program_id.into(),
+            })
+        }
+    };
+
+    Ok(Some(action.into()))
+}
+
+fn classify_metadata_pointer_extension(
+    txn: &ClassifiableTransaction,
+    ix: &ClassifiableInstruction,
+    data: &[u8],
+) -> ClassifyInstructionResult {
+    let instruction: MetadataPointerInstruction = decode_instruction_type(data)
+        .map_err(|e| anyhow::anyhow!("Failed to decode metadata pointer instruction: {:?}", e))?;
+
+    let action = match instruction {
+        MetadataPointerInstruction::Initialize => {
+            check_account_len(ix, 1)?;
+
+            let data: &InitializeMetadataPointerData = decode_instruction_data(data)
+                .map_err(|e| anyhow::anyhow!("Failed to decode metadata pointer data: {:?}", e))?;
+
+            Token::InitializeMetadataPointer(token_actions::InitializeMetadataPointer {
+                mint: get_account_pubkey(txn, ix, 0)?,
+                authority: data.authority.into(),
+                metadata_address: data.metadata_address.into(),
+            })
+        }
+        MetadataPointerInstruction::Update => {
+            check_account_len_gte(ix, 2)?;
+
+            let data: &UpdateMetadataPointerData = decode_instruction_data(data)
+                .map_err(|e| anyhow::anyhow!("Failed to decode metadata pointer data: {:?}", e))?;
+
+            Token::UpdateMetadataPointer(token_actions::UpdateMetadataPointer {
+                mint: get_account_pubkey(txn, ix, 0)?,
+                authority: get_account_pubkey(txn, ix, 1)?,
+                metadata_address: data.metadata_address.into(),
+            })
+        }
+    };
+
+    Ok(Some(action.into()))
+}
+
+fn classify_initialize_non_transferable_mint(
+    txn: &ClassifiableTransaction,
+    ix: &ClassifiableInstruction,
+) -> ClassifyInstructionResult {
+    check_account_len(ix, 1)?;
+
+    let mint = get_account_pubkey(txn, ix, 0)?;
+
+    Ok(Some(
+        Token::InitializeNonTransferableMint(token_actions::InitializeNonTransferableMint { mint })
+            .into(),
+    ))
+}
+
+fn classify_initialize_permanent_delegate(
+    txn: &ClassifiableTransaction,
+    ix: &ClassifiableInstruction,
+    delegate: Pubkey,
+) -> ClassifyInstructionResult {
+    check_account_len(ix, 1)?;
+
+    let mint = get_account_pubkey(txn, ix, 0)?;
+
+    Ok(Some(
+        Token::InitializePermanentDelegate(token_actions::InitializePermanentDelegate {
+            mint,
+            delegate,
+        })
+        .into(),
+    ))
+}
@@ -1,9 +1,12 @@
-use actions::{Action, CompactUpdateVoteState, Vote};
+use actions::{Action, Vote, VoteUpdate};
 use classifier_core::{ClassifiableInstruction, ClassifiableTransaction};
 use classifier_trait::{ClassifyInstructionResult, InstructionClassifier};
 use solana_sdk::{
     pubkey::Pubkey,
-    vote::{instruction::VoteInstruction, state::VoteStateUpdate},
+    vote::{
+        instruction::VoteInstruction,
+        state::{Vote as TowerVote, VoteStateUpdate},
+    },
 };
 use thiserror::Error;
 
@@ -19,14 +22,25 @@ impl InstructionClassifier for VoteClassifier {
         let decoded: VoteInstruction = bincode::deserialize(&ix.data)?;
 
         let action = match decoded {
+            VoteInstruction::Vote(vote) => Vote::Vote(classify_vote(txn, ix, vote)?),
+            VoteInstruction::VoteSwitch(vote, _) => Vote::VoteSwitch(classify_vote(txn, ix, vote)?),
+            VoteInstruction::UpdateVoteState(update) => {
+                Vote::UpdateVoteState(classify_vote_state_update(txn, ix, update)?)
+            }
+            VoteInstruction::UpdateVoteStateSwitch(update, _) => {
+                Vote::UpdateVoteStateSwitch(classify_vote_state_update(txn, ix, update)?)
+            }
             VoteInstruction::CompactUpdateVoteState(update) => {
-                Some(classify_compact_update_vote_state(txn, ix, update)?)
+                Vote::CompactUpdateVoteState(classify_vote_state_update(txn, ix, update)?)
+            }
+            VoteInstruction::CompactUpdateVoteStateSwitch(update, _) => {
+                Vote::CompactUpdateVoteStateSwitch(classify_vote_state_update(txn, ix, update)?)
             }
 
             _ => return Ok(None),
         };
 
-        Ok(action)
+        Ok(Some(Action::from(action)))
     }
 }
 
@@ -41,24 +55,206 @@ pub enum ClassifyVoteError {
 
 type Result<T> = std::result::Result<T, ClassifyVoteError>;
 
-fn classify_compact_update_vote_state(
+fn get_account(
+    txn: &ClassifiableTransaction,
+    ixn: &ClassifiableInstruction,
+    position: usize,
+) -> Result<Pubkey> {
+    txn.get_instruction_account(ixn, position)
+        .map(|account| account.pubkey)
+        .ok_or_else(|| ClassifyVoteError::MissingAccounts)
+}
+
+/// # Account references
+///   0. `[WRITE]` Vote account
+///   1. `[]` Slot hashes sysvar
+///   2. `[]` Clock sysvar
+///   3. `[SIGNER]` Vote authority
+fn classify_vote(
+    txn: &ClassifiableTransaction,
+    ixn: &ClassifiableInstruction,
+    vote: TowerVote,
+) -> Result<VoteUpdate> {
+    if ixn.accounts.len() < 4 {
+        return Err(ClassifyVoteError::MissingAccounts);
+    }
+
+    Ok(VoteUpdate {
+        vote_account: get_account(txn, ixn, 0)?,
+        vote_authority: get_account(txn, ixn, 3)?,
+        slots: vote.slots,
+        root: None,
+        hash: vote.hash,
+        timestamp: vote.timestamp,
+    })
+}
+
+/// # Account references
+///   0. `[WRITE]` Vote account
+///   1. `[SIGNER]` Vote authority
+fn classify_vote_state_update(
     txn: &ClassifiableTransaction,
     ixn: &ClassifiableInstruction,
     update: VoteStateUpdate,
-) -> Result<Action> {
-    if ixn.accounts.len() < 1 {
+) -> Result<VoteUpdate> {
+    if ixn.accounts.len() < 2 {
         return Err(ClassifyVoteError::MissingAccounts);
     }
 
-    let vote_authority = txn
-        .get_pubkey(ixn.accounts[0])
-        .ok_or_else(|| ClassifyVoteError::MissingAccounts)?;
+    Ok(VoteUpdate {
+        vote_account: get_account(txn, ixn, 0)?,
+        vote_authority: get_account(txn, ixn, 1)?,
+        slots: update.slots(),
+        root: update.root,
+        hash: update.hash,
+        timestamp: update.timestamp,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_sdk::{
+        hash::Hash,
+        instruction::Instruction,
+        message::{Message, VersionedMessage},
+        signature::Signature,
+        transaction::VersionedTransaction,
+        vote::instruction,
+    };
+    use solana_transaction_status::UiTransactionStatusMeta;
+
+    use super::*;
+
+    fn classify(ix: Instruction) -> Option<Action> {
+        let meta: UiTransactionStatusMeta = serde_json::from_value(serde_json::json!({
+            "err": null,
+            "status": { "Ok": null },
+            "fee": 5000,
+            "preBalances": [],
+            "postBalances": [],
+        }))
+        .unwrap();
+
+        let txn = ClassifiableTransaction::new(
+            VersionedTransaction {
+                signatures: vec![Signature::new_unique()],
+                message: VersionedMessage::Legacy(Message::new(&[ix], None)),
+            },
+            meta,
+        );
+
+        VoteClassifier::classify_instruction(&txn, &txn.instructions[0]).unwrap()
+    }
+
+    fn vote_state_update() -> VoteStateUpdate {
+        let mut update = VoteStateUpdate::from(vec![(98, 2), (99, 1)]);
+        update.root = Some(90);
+        update.hash = Hash::new_unique();
+        update.timestamp = Some(1_700_000_000);
+        update
+    }
 
-    let vote_action: Vote = CompactUpdateVoteState {
-        vote_authority,
-        update,
+    fn expected_update(
+        vote_account: Pubkey,
+        vote_authority: Pubkey,
+        update: &VoteStateUpdate,
+    ) -> VoteUpdate {
+        VoteUpdate {
+            vote_account,
+            vote_authority,
+            slots: vec![98, 99],
+            root: Some(90),
+            hash: update.hash,
+            timestamp: update.timestamp,
+        }
     }
-    .into();
 
-    Ok(vote_action.into())
+    #[test]
+    fn classifies_tower_votes() {
+        let (vote_account, vote_authority) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let vote = TowerVote {
+            slots: vec![98, 99],
+            hash: Hash::new_unique(),
+            timestamp: None,
+        };
+        let expected = VoteUpdate {
+            vote_account,
+            vote_authority,
+            slots: vec![98, 99],
+            root: None,
+            hash: vote.hash,
+            timestamp: None,
+        };
+
+        assert_eq!(
+            classify(instruction::vote(
+                &vote_account,
+                &vote_authority,
+                vote.clone()
+            )),
+            Some(Vote::Vote(expected.clone()).into())
+        );
+        assert_eq!(
+            classify(instruction::vote_switch(
+                &vote_account,
+                &vote_authority,
+                vote,
+                Hash::new_unique()
+            )),
+            Some(Vote::VoteSwitch(expected).into())
+        );
+    }
+
+    #[test]
+    fn classifies_vote_state_updates() {
+        let (vote_account, vote_authority) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let update = vote_state_update();
+        let expected = expected_update(vote_account, vote_authority, &update);
+        let proof = Hash::new_unique();
+
+        let cases = [
+            (
+                instruction::update_vote_state(&vote_account, &vote_authority, update.clone()),
+                Vote::UpdateVoteState(expected.clone()),
+            ),
+            (
+                instruction::update_vote_state_switch(
+                    &vote_account,
+                    &vote_authority,
+                    update.clone(),
+                    proof,
+                ),
+                Vote::UpdateVoteStateSwitch(expected.clone()),
+            ),
+            (
+                instruction::compact_update_vote_state(
+                    &vote_account,
+                    &vote_authority,
+                    update.clone(),
+                ),
+                Vote::CompactUpdateVoteState(expected.clone()),
+            ),
+            (
+                instruction::compact_update_vote_state_switch(
+                    &vote_account,
+                    &vote_authority,
+                    update,
+                    proof,
+                ),
+                Vote::CompactUpdateVoteStateSwitch(expected),
+            ),
+        ];
+
+        for (ix, vote) in cases {
+            assert_eq!(classify(ix), Some(vote.into()));
+        }
+    }
+
+    #[test]
+    fn ignores_other_vote_instructions() {
+        let (vote_account, authority) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let ix = instruction::withdraw(&vote_account, &authority, 10, &Pubkey::new_unique());
+
+        assert_eq!(classify(ix), None);
+    }
 }
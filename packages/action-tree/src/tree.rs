@@ -45,6 +45,27 @@ where
         child_id
     }
 
+    /// Copies all nodes of `other` under `parent`, preserving their order. Returns the
+    /// id of the copied root.
+    pub fn append_tree(
+        &mut self,
+        parent: ActionNodeId,
+        other: &ActionTree<Action>,
+    ) -> ActionNodeId {
+        let root_id = self.insert_child(parent, other.arena[other.root_id].get().clone());
+
+        let mut stack = vec![(other.root_id, root_id)];
+        while let Some((other_id, node_id)) = stack.pop() {
+            for other_child_id in other_id.children(&other.arena) {
+                let child_id =
+                    self.insert_child(node_id, other.arena[other_child_id].get().clone());
+                stack.push((other_child_id, child_id));
+            }
+        }
+
+        root_id
+    }
+
     pub fn remove_subtree(&mut self, node: ActionNodeId) {
         node.remove_subtree(&mut self.arena);
     }
@@ -1,5 +1,11 @@
+use classifier_core::{
+    document,
+    document::{ToDocumentValue, Value},
+    json::{object_schema, JsonSchema, ToJsonValue},
+};
 use macros::action;
 use serde::Serialize;
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
 use super::ActionTrait;
 
 #[derive(Serialize)]
@@ -12,8 +18,316 @@ pub struct Block {
     pub total_base_fees: Option<u64>,
     pub total_priority_fees: Option<u64>,
     pub total_tips: Option<u64>,
+    pub total_jito_tips: Option<u64>,
+    pub total_bloxroute_tips: Option<u64>,
+
+    pub total_fees_burned: Option<u64>,
+    pub total_validator_fees: Option<u64>,
+    pub fee_reward: Option<i64>,
+    pub reward_discrepancies: Option<Vec<RewardDiscrepancy>>,
+
+    pub total_compute_units: Option<u64>,
+    pub program_compute_units: Option<Vec<ProgramComputeUnits>>,
+    pub mev_compute_units: Option<MevComputeUnits>,
+    pub vote_summary: Option<VoteSummary>,
 
 		pub validator_pubkey: Option<String>,
+    pub validator_name: Option<String>,
+    pub validator_vote_account: Option<String>,
+    pub leader_source: Option<LeaderSource>,
+
+    pub total_sandwiches: Option<u64>,
+    pub total_atomic_arbitrages: Option<u64>,
+}
+
+/// Where the block leader's identity was resolved from
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LeaderSource {
+    /// Identity credited with the block's fee reward
+    FeeReward,
+
+    /// Identity scheduled for the slot in a local leader schedule
+    LeaderSchedule,
+}
+
+impl LeaderSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LeaderSource::FeeReward => "feeReward",
+            LeaderSource::LeaderSchedule => "leaderSchedule",
+        }
+    }
+}
+
+impl ToJsonValue for LeaderSource {
+    fn to_json_value(&self) -> serde_json::Value {
+        serde_json::Value::from(self.as_str())
+    }
+}
+
+impl JsonSchema for LeaderSource {
+    fn json_schema() -> serde_json::Value {
+        serde_json::json!({ "enum": ["feeReward", "leaderSchedule"] })
+    }
+}
+
+impl ToDocumentValue for LeaderSource {
+    fn to_document_value(&self) -> Value {
+        Value::from(self.as_str())
+    }
+}
+
+/// Mismatch found while reconciling computed fees against on-chain data
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub enum RewardDiscrepancy {
+    /// Block has no fee reward entry for the leader
+    MissingFeeReward { expected: u64 },
+
+    /// Fee reward paid to the leader does not match the computed validator share
+    FeeReward { expected: u64, actual: i64 },
+
+    /// Fee charged for a transaction does not match its base fee plus the priority
+    /// fee implied by its compute budget
+    TransactionFee {
+        signature: Signature,
+        expected: u64,
+        actual: u64,
+    },
+}
+
+impl ToJsonValue for RewardDiscrepancy {
+    fn to_json_value(&self) -> serde_json::Value {
+        match self {
+            RewardDiscrepancy::MissingFeeReward { expected } => serde_json::json!({
+                "type": "missingFeeReward",
+                "expected": expected.to_json_value(),
+            }),
+            RewardDiscrepancy::FeeReward { expected, actual } => serde_json::json!({
+                "type": "feeReward",
+                "expected": expected.to_json_value(),
+                "actual": actual.to_json_value(),
+            }),
+            RewardDiscrepancy::TransactionFee {
+                signature,
+                expected,
+                actual,
+            } => serde_json::json!({
+                "type": "transactionFee",
+                "signature": signature.to_json_value(),
+                "expected": expected.to_json_value(),
+                "actual": actual.to_json_value(),
+            }),
+        }
+    }
+}
+
+impl JsonSchema for RewardDiscrepancy {
+    fn json_schema() -> serde_json::Value {
+        serde_json::json!({
+            "oneOf": [
+                object_schema(vec![
+                    ("type", serde_json::json!({ "const": "missingFeeReward" })),
+                    ("expected", u64::json_schema()),
+                ]),
+                object_schema(vec![
+                    ("type", serde_json::json!({ "const": "feeReward" })),
+                    ("expected", u64::json_schema()),
+                    ("actual", i64::json_schema()),
+                ]),
+                object_schema(vec![
+                    ("type", serde_json::json!({ "const": "transactionFee" })),
+                    ("signature", Signature::json_schema()),
+                    ("expected", u64::json_schema()),
+                    ("actual", u64::json_schema()),
+                ]),
+            ],
+        })
+    }
+}
+
+impl ToDocumentValue for RewardDiscrepancy {
+    fn to_document_value(&self) -> Value {
+        let document = match self {
+            RewardDiscrepancy::MissingFeeReward { expected } => document! {
+                "type": "missingFeeReward",
+                "expected": *expected as i64,
+            },
+            RewardDiscrepancy::FeeReward { expected, actual } => document! {
+                "type": "feeReward",
+                "expected": *expected as i64,
+                "actual": *actual,
+            },
+            RewardDiscrepancy::TransactionFee {
+                signature,
+                expected,
+                actual,
+            } => document! {
+                "type": "transactionFee",
+                "signature": *signature,
+                "expected": *expected as i64,
+                "actual": *actual as i64,
+            },
+        };
+
+        Value::Document(document)
+    }
+}
+
+/// Compute units consumed by top level instructions of a single program within a block
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct ProgramComputeUnits {
+    pub program_id: Pubkey,
+    pub compute_units: u64,
+    pub instructions: u64,
+}
+
+impl ToJsonValue for ProgramComputeUnits {
+    fn to_json_value(&self) -> serde_json::Value {
+        serde_json::json!({
+            "programId": self.program_id.to_json_value(),
+            "computeUnits": self.compute_units.to_json_value(),
+            "instructions": self.instructions.to_json_value(),
+        })
+    }
+}
+
+impl JsonSchema for ProgramComputeUnits {
+    fn json_schema() -> serde_json::Value {
+        object_schema(vec![
+            ("programId", Pubkey::json_schema()),
+            ("computeUnits", u64::json_schema()),
+            ("instructions", u64::json_schema()),
+        ])
+    }
+}
+
+impl ToDocumentValue for ProgramComputeUnits {
+    fn to_document_value(&self) -> Value {
+        Value::Document(document! {
+            "program_id": self.program_id,
+            "compute_units": self.compute_units as i64,
+            "instructions": self.instructions as i64,
+        })
+    }
+}
+
+/// Compute units consumed by transactions in a block, grouped by MEV label
+#[derive(Serialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct MevComputeUnits {
+    pub atomic_arbitrage: u64,
+    pub sandwich_attack: u64,
+    pub sandwich_victim: u64,
+    pub other: u64,
+}
+
+impl ToJsonValue for MevComputeUnits {
+    fn to_json_value(&self) -> serde_json::Value {
+        serde_json::json!({
+            "atomicArbitrage": self.atomic_arbitrage.to_json_value(),
+            "sandwichAttack": self.sandwich_attack.to_json_value(),
+            "sandwichVictim": self.sandwich_victim.to_json_value(),
+            "other": self.other.to_json_value(),
+        })
+    }
+}
+
+impl JsonSchema for MevComputeUnits {
+    fn json_schema() -> serde_json::Value {
+        object_schema(vec![
+            ("atomicArbitrage", u64::json_schema()),
+            ("sandwichAttack", u64::json_schema()),
+            ("sandwichVictim", u64::json_schema()),
+            ("other", u64::json_schema()),
+        ])
+    }
+}
+
+impl ToDocumentValue for MevComputeUnits {
+    fn to_document_value(&self) -> Value {
+        Value::Document(document! {
+            "atomic_arbitrage": self.atomic_arbitrage as i64,
+            "sandwich_attack": self.sandwich_attack as i64,
+            "sandwich_victim": self.sandwich_victim as i64,
+            "other": self.other as i64,
+        })
+    }
+}
+
+/// Vote activity within a block
+#[derive(Serialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct VoteSummary {
+    /// Vote instructions in successful transactions
+    pub votes: u64,
+
+    /// Vote instructions in failed transactions
+    pub failed_votes: u64,
+
+    /// Compute units consumed by transactions containing a vote
+    pub compute_units: u64,
+
+    /// Slots between the block and the newest slot each successful vote targets
+    pub min_latency: Option<u64>,
+    pub max_latency: Option<u64>,
+    pub median_latency: Option<u64>,
+}
+
+impl VoteSummary {
+    /// Share of the block's compute units consumed by vote transactions
+    pub fn compute_unit_share(&self, total_compute_units: u64) -> Option<f64> {
+        if total_compute_units == 0 {
+            return None;
+        }
+
+        Some(self.compute_units as f64 / total_compute_units as f64)
+    }
+
+    pub fn to_json(&self, total_compute_units: Option<u64>) -> serde_json::Value {
+        let compute_unit_share =
+            total_compute_units.and_then(|total| self.compute_unit_share(total));
+
+        serde_json::json!({
+            "votes": self.votes.to_json_value(),
+            "failedVotes": self.failed_votes.to_json_value(),
+            "computeUnits": self.compute_units.to_json_value(),
+            "computeUnitShare": compute_unit_share,
+            "minLatency": self.min_latency.to_json_value(),
+            "maxLatency": self.max_latency.to_json_value(),
+            "medianLatency": self.median_latency.to_json_value(),
+        })
+    }
+}
+
+impl ToJsonValue for VoteSummary {
+    fn to_json_value(&self) -> serde_json::Value {
+        self.to_json(None)
+    }
+}
+
+impl JsonSchema for VoteSummary {
+    fn json_schema() -> serde_json::Value {
+        object_schema(vec![
+            ("votes", u64::json_schema()),
+            ("failedVotes", u64::json_schema()),
+            ("computeUnits", u64::json_schema()),
+            ("computeUnitShare", Option::<f64>::json_schema()),
+            ("minLatency", Option::<u64>::json_schema()),
+            ("maxLatency", Option::<u64>::json_schema()),
+            ("medianLatency", Option::<u64>::json_schema()),
+        ])
+    }
+}
+
+impl ToDocumentValue for VoteSummary {
+    fn to_document_value(&self) -> Value {
+        Value::Document(document! {
+            "votes": self.votes as i64,
+            "failed_votes": self.failed_votes as i64,
+            "compute_units": self.compute_units as i64,
+            "min_latency": self.min_latency.map(|latency| latency as i64),
+            "max_latency": self.max_latency.map(|latency| latency as i64),
+            "median_latency": self.median_latency.map(|latency| latency as i64),
+        })
+    }
 }
 
 impl Block {
@@ -23,9 +337,24 @@ impl Block {
             parent_slot,
             block_time,
             validator_pubkey,
+            validator_name: None,
+            validator_vote_account: None,
+            leader_source: None,
+            total_sandwiches: None,
+            total_atomic_arbitrages: None,
             total_base_fees: None,
             total_priority_fees: None,
             total_tips: None,
+            total_jito_tips: None,
+            total_bloxroute_tips: None,
+            total_fees_burned: None,
+            total_validator_fees: None,
+            fee_reward: None,
+            reward_discrepancies: None,
+            total_compute_units: None,
+            program_compute_units: None,
+            mev_compute_units: None,
+            vote_summary: None,
         }
     }
 }
@@ -39,19 +368,34 @@ impl ActionTrait for Block {
         unreachable!("Blocks should not be document roots")
     }
 
-    fn serializable(&self) -> bool {
-        true
-    }
-
     fn to_json(&self) -> serde_json::Value {
         serde_json::json!({
             "type": "block",
-            "slot": self.slot,
-            "parent_slot": self.parent_slot,
-            "block_time": self.block_time,
-            "total_base_fees": self.total_base_fees,
-            "total_priority_fees": self.total_priority_fees,
-            "total_tips": self.total_tips,
+            "slot": self.slot.to_json_value(),
+            "parentSlot": self.parent_slot.to_json_value(),
+            "blockTime": self.block_time.to_json_value(),
+            "validatorPubkey": self.validator_pubkey,
+            "validatorName": self.validator_name,
+            "validatorVoteAccount": self.validator_vote_account,
+            "leaderSource": self.leader_source.to_json_value(),
+            "totalSandwiches": self.total_sandwiches.to_json_value(),
+            "totalAtomicArbitrages": self.total_atomic_arbitrages.to_json_value(),
+            "totalBaseFees": self.total_base_fees.to_json_value(),
+            "totalPriorityFees": self.total_priority_fees.to_json_value(),
+            "totalTips": self.total_tips.to_json_value(),
+            "totalJitoTips": self.total_jito_tips.to_json_value(),
+            "totalBloxrouteTips": self.total_bloxroute_tips.to_json_value(),
+            "totalFeesBurned": self.total_fees_burned.to_json_value(),
+            "totalValidatorFees": self.total_validator_fees.to_json_value(),
+            "feeReward": self.fee_reward.to_json_value(),
+            "rewardDiscrepancies": self.reward_discrepancies.to_json_value(),
+            "totalComputeUnits": self.total_compute_units.to_json_value(),
+            "programComputeUnits": self.program_compute_units.to_json_value(),
+            "mevComputeUnits": self.mev_compute_units.to_json_value(),
+            "voteSummary": self
+                .vote_summary
+                .as_ref()
+                .map(|votes| votes.to_json(self.total_compute_units)),
         })
     }
 }
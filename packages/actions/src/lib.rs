@@ -2,6 +2,7 @@ mod block;
 mod jito;
 mod post_processing;
 mod protocols;
+mod schema;
 mod serialize_tree;
 mod solana;
 mod transaction;
@@ -10,10 +11,11 @@ pub use block::*;
 pub use jito::*;
 pub use post_processing::*;
 pub use protocols::*;
+pub use schema::*;
 pub use serialize_tree::*;
 pub use solana::*;
 
-use classifier_core::ClassifiableTransaction;
+use classifier_core::{json::ToJsonValue, ClassifiableTransaction};
 use macros::define_actions;
 
 pub type ActionTree = action_tree::ActionTree<Action>;
@@ -27,7 +29,7 @@ define_actions! {
     Action,
 
     // Trait all actions must implement
-    pub trait ActionTrait {
+    pub trait ActionTrait: ToJsonValue {
         /// Helper function for the instruction classifier. If an action stems from both
         /// 1) An instruction which may have inner instructions, and
         /// 2) Inner instructions which may contain more useful actions,
@@ -57,12 +59,16 @@ define_actions! {
             Ok(None)
         }
 
+        /// Whether the action and its children are included in serialized trees. Actions
+        /// opt out by returning false.
         fn serializable(&self) -> bool {
-            false
+            true
         }
 
+        /// JSON of the action, derived by `#[action]` and `#[action_enum]` unless
+        /// overridden. The action's type is added by the tree serializer.
         fn to_json(&self) -> serde_json::Value {
-            unreachable!("Action should not be serialized")
+            self.to_json_value()
         }
     },
 
@@ -77,6 +83,7 @@ define_actions! {
     // Solana system actions
     ProgramInvocation,
     NativeTransfer,
+    System,
     Vote,
     SetComputeBudgetLimit,
     SetComputeUnitPrice,
@@ -84,6 +91,7 @@ define_actions! {
     AssociatedToken,
 
     // 3rd party actions
+    AnchorInstruction,
     JitoTip,
     BloxrouteTip,
     WhirlpoolsAction,
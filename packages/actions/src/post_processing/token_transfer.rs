@@ -1,8 +1,39 @@
+use classifier_core::ClassifiableTransaction;
 use solana_sdk::pubkey::Pubkey;
 
 #[derive(Debug)]
 pub struct TokenTransfer {
     pub source: Pubkey,
     pub destination: Pubkey,
+
+    /// Mint named by the transfer instruction. Native SOL moved by the System Program
+    /// is reported as [`classifier_core::NATIVE_MINT`], the mint of wrapped SOL.
+    pub mint: Option<Pubkey>,
+
+    /// Amount debited from the source
     pub amount: u64,
+
+    /// Token-2022 transfer fee withheld from the amount
+    pub fee: u64,
+}
+
+impl TokenTransfer {
+    /// Amount credited to the destination
+    pub fn received_amount(&self) -> u64 {
+        self.amount.saturating_sub(self.fee)
+    }
+
+    /// Mint of the transferred tokens, falling back to the mint of `token_account` when
+    /// the instruction does not name one. Swaps in and out of SOL therefore report the
+    /// same mint whether the SOL was wrapped or not.
+    pub fn mint(
+        &self,
+        txn: &ClassifiableTransaction,
+        token_account: &Pubkey,
+    ) -> Result<Pubkey, anyhow::Error> {
+        match self.mint {
+            Some(mint) => Ok(mint),
+            None => txn.get_mint_for_token_account(token_account),
+        }
+    }
 }
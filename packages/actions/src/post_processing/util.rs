@@ -1,11 +1,19 @@
 use action_tree::ActionNodeId;
+use classifier_core::{ClassifiableTransaction, NATIVE_MINT};
 use solana_sdk::pubkey::Pubkey;
 
 use crate::{Action, ActionTree, Token};
 
 use super::TokenTransfer;
 
+/// Find a direct child transfer between two accounts.
+///
+/// Transfer fees are stated by `TransferCheckedWithFee`. Other transfers of fee-bearing
+/// Token-2022 mints withhold a fee computed from the mint's config, which is not part of
+/// the instruction, so it is inferred from the destination's balance change instead,
+/// see [`ClassifiableTransaction::inferred_transfer_fee`].
 pub fn find_transfer(
+    txn: &ClassifiableTransaction,
     tree: &ActionTree,
     node_id: ActionNodeId,
     from: &Pubkey,
@@ -21,7 +29,9 @@ pub fn find_transfer(
                         return Some(TokenTransfer {
                             source: transfer.source,
                             destination: transfer.destination,
+                            mint: None,
                             amount: transfer.amount,
+                            fee: txn.inferred_transfer_fee(&transfer.destination, transfer.amount),
                         });
                     }
                 }
@@ -30,15 +40,135 @@ pub fn find_transfer(
                         return Some(TokenTransfer {
                             source: transfer.source,
                             destination: transfer.destination,
+                            mint: Some(transfer.mint),
                             amount: transfer.amount,
+                            fee: txn.inferred_transfer_fee(&transfer.destination, transfer.amount),
+                        });
+                    }
+                }
+                Token::TransferCheckedWithFee(transfer) => {
+                    if transfer.source == *from && transfer.destination == *to {
+                        return Some(TokenTransfer {
+                            source: transfer.source,
+                            destination: transfer.destination,
+                            mint: Some(transfer.mint),
+                            amount: transfer.amount,
+                            fee: transfer.fee,
                         });
                     }
                 }
                 _ => {}
             },
+            // Pools holding native SOL move it with system transfers rather than wSOL
+            Action::NativeTransfer(transfer) => {
+                if transfer.from == *from && transfer.to == *to {
+                    return Some(TokenTransfer {
+                        source: transfer.from,
+                        destination: transfer.to,
+                        mint: Some(NATIVE_MINT),
+                        amount: transfer.lamports,
+                        fee: 0,
+                    });
+                }
+            }
             _ => {}
         }
     }
 
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use solana_sdk::{
+        message::{Message, VersionedMessage},
+        signature::Signature,
+        system_instruction,
+        transaction::VersionedTransaction,
+    };
+    use solana_transaction_status::UiTransactionStatusMeta;
+
+    use super::*;
+    use crate::{token_actions, NativeTransfer, SetComputeUnitPrice};
+
+    /// Transaction in which `token_account` holds `mint`
+    fn transaction(owner: Pubkey, token_account: Pubkey, mint: Pubkey) -> ClassifiableTransaction {
+        let token_balance = serde_json::json!({
+            "accountIndex": 1,
+            "mint": mint.to_string(),
+            "owner": owner.to_string(),
+            "programId": "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA",
+            "uiTokenAmount": {
+                "amount": "100",
+                "decimals": 6,
+                "uiAmount": null,
+                "uiAmountString": "0.0001",
+            },
+        });
+        let meta: UiTransactionStatusMeta = serde_json::from_value(serde_json::json!({
+            "err": null,
+            "status": { "Ok": null },
+            "fee": 5000,
+            "preBalances": [],
+            "postBalances": [],
+            "preTokenBalances": [token_balance],
+            "postTokenBalances": [token_balance],
+        }))
+        .unwrap();
+
+        ClassifiableTransaction::new(
+            VersionedTransaction {
+                signatures: vec![Signature::new_unique()],
+                message: VersionedMessage::Legacy(Message::new(
+                    &[system_instruction::transfer(&owner, &token_account, 0)],
+                    Some(&owner),
+                )),
+            },
+            meta,
+        )
+    }
+
+    #[test]
+    fn native_transfers_are_wrapped_sol_transfers() {
+        let (from, to) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let txn = transaction(from, Pubkey::new_unique(), Pubkey::new_unique());
+
+        let mut tree = ActionTree::new(SetComputeUnitPrice { micro_lamports: 1 }.into());
+        tree.insert_child(tree.root(), NativeTransfer::new(from, to, 1_000).into());
+
+        let transfer = find_transfer(&txn, &tree, tree.root(), &from, &to).unwrap();
+        assert_eq!(transfer.mint, Some(NATIVE_MINT));
+        assert_eq!(transfer.mint(&txn, &to).unwrap(), NATIVE_MINT);
+        assert_eq!(transfer.received_amount(), 1_000);
+
+        assert!(find_transfer(&txn, &tree, tree.root(), &to, &from).is_none());
+    }
+
+    #[test]
+    fn unchecked_transfers_take_the_token_account_mint() {
+        let (owner, source, destination, mint) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let txn = transaction(owner, destination, mint);
+
+        let mut tree = ActionTree::new(SetComputeUnitPrice { micro_lamports: 1 }.into());
+        tree.insert_child(
+            tree.root(),
+            Token::Transfer(token_actions::Transfer {
+                source,
+                destination,
+                owner,
+                amount: 100,
+            })
+            .into(),
+        );
+
+        let transfer = find_transfer(&txn, &tree, tree.root(), &source, &destination).unwrap();
+        assert_eq!(transfer.mint, None);
+        assert_eq!(transfer.mint(&txn, &destination).unwrap(), mint);
+        assert_eq!(transfer.received_amount(), 100);
+    }
+}
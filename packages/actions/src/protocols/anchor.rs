@@ -0,0 +1,27 @@
+use crate::ActionTrait;
+use macros::action;
+use solana_sdk::pubkey::Pubkey;
+
+/// Instruction of an Anchor program decoded at runtime from its IDL, for programs
+/// without a compiled classifier.
+#[action]
+pub struct AnchorInstruction {
+    pub program_id: Pubkey,
+    pub program_name: String,
+    pub instruction: String,
+
+    /// Accounts named by the IDL, in instruction order. Accounts of composite account
+    /// groups are prefixed with the group name, e.g. `group.account`.
+    pub accounts: Vec<AnchorInstructionAccount>,
+
+    /// Instruction arguments keyed by name
+    pub args: serde_json::Value,
+}
+
+#[action]
+pub struct AnchorInstructionAccount {
+    pub name: String,
+    pub pubkey: Pubkey,
+}
+
+impl ActionTrait for AnchorInstruction {}
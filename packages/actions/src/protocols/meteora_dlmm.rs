@@ -51,45 +51,43 @@ impl ActionTrait for MeteoraDlmmAction {
                 let input_mint = txn.get_mint_for_token_account(&action.user_token_in)?;
                 let x_for_y = input_mint == action.token_x_mint;
 
-                let output_amount = if x_for_y {
-                    find_transfer(tree, action_id, &action.reserve_y, &action.user_token_out)
-                        .ok_or_else(|| anyhow::anyhow!("Could not find transfer"))?
-                        .amount
+                let output_reserve = if x_for_y {
+                    &action.reserve_y
                 } else {
-                    find_transfer(tree, action_id, &action.reserve_x, &action.user_token_out)
-                        .ok_or_else(|| anyhow::anyhow!("Could not find transfer"))?
-                        .amount
+                    &action.reserve_x
                 };
+                let output_transfer =
+                    find_transfer(txn, tree, action_id, output_reserve, &action.user_token_out)
+                        .ok_or_else(|| anyhow::anyhow!("Could not find transfer"))?;
 
                 DexSwap {
                     input_mint,
-                    output_mint: txn.get_mint_for_token_account(&action.user_token_out)?,
+                    output_mint: output_transfer.mint(txn, &action.user_token_out)?,
                     input_token_account: action.user_token_in,
                     output_token_account: action.user_token_out,
                     input_amount: action.amount_in,
-                    output_amount,
+                    output_amount: output_transfer.received_amount(),
                 }
             }
             MeteoraDlmmAction::SwapExactOut(action) => {
                 let input_mint = txn.get_mint_for_token_account(&action.user_token_in)?;
                 let x_for_y = input_mint == action.token_x_mint;
 
-                let input_amount = if x_for_y {
-                    find_transfer(tree, action_id, &action.user_token_in, &action.reserve_x)
-                        .ok_or_else(|| anyhow::anyhow!("Could not find transfer"))?
-                        .amount
+                let input_reserve = if x_for_y {
+                    &action.reserve_x
                 } else {
-                    find_transfer(tree, action_id, &action.user_token_in, &action.reserve_y)
-                        .ok_or_else(|| anyhow::anyhow!("Could not find transfer"))?
-                        .amount
+                    &action.reserve_y
                 };
+                let input_transfer =
+                    find_transfer(txn, tree, action_id, &action.user_token_in, input_reserve)
+                        .ok_or_else(|| anyhow::anyhow!("Could not find transfer"))?;
 
                 DexSwap {
-                    input_mint: txn.get_mint_for_token_account(&action.user_token_in)?,
+                    input_mint: input_transfer.mint(txn, &action.user_token_in)?,
                     output_mint: txn.get_mint_for_token_account(&action.user_token_out)?,
                     input_token_account: action.user_token_in,
                     output_token_account: action.user_token_out,
-                    input_amount,
+                    input_amount: input_transfer.amount,
                     output_amount: action.out_amount,
                 }
             }
@@ -1,3 +1,4 @@
+mod anchor;
 mod bloxroute;
 mod jupiter_v6;
 mod meteora_dlmm;
@@ -7,6 +8,7 @@ mod raydium_clmm;
 mod star_atlas;
 mod whirlpools;
 
+pub use anchor::*;
 pub use bloxroute::*;
 pub use jupiter_v6::*;
 pub use meteora_dlmm::*;
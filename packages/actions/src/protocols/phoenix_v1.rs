@@ -1,5 +1,9 @@
 use crate::ActionTrait;
 use borsh::BorshDeserialize;
+use classifier_core::{
+    document::{ToDocumentValue, Value},
+    json::{JsonSchema, ToJsonValue},
+};
 use macros::action_enum;
 
 #[derive(BorshDeserialize, Debug, Clone, PartialEq, Eq)]
@@ -17,12 +21,62 @@ pub enum SelfTradeBehavior {
     DecrementTake = 2,
 }
 
+impl ToDocumentValue for Side {
+    fn to_document_value(&self) -> Value {
+        Value::from(match self {
+            Side::Bid => "bid",
+            Side::Ask => "ask",
+        })
+    }
+}
+
+impl ToJsonValue for Side {
+    fn to_json_value(&self) -> serde_json::Value {
+        serde_json::Value::from(match self {
+            Side::Bid => "bid",
+            Side::Ask => "ask",
+        })
+    }
+}
+
+impl JsonSchema for Side {
+    fn json_schema() -> serde_json::Value {
+        serde_json::json!({ "enum": ["bid", "ask"] })
+    }
+}
+
+impl ToDocumentValue for SelfTradeBehavior {
+    fn to_document_value(&self) -> Value {
+        Value::from(match self {
+            SelfTradeBehavior::Abort => "abort",
+            SelfTradeBehavior::CancelProvide => "cancel_provide",
+            SelfTradeBehavior::DecrementTake => "decrement_take",
+        })
+    }
+}
+
+impl ToJsonValue for SelfTradeBehavior {
+    fn to_json_value(&self) -> serde_json::Value {
+        serde_json::Value::from(match self {
+            SelfTradeBehavior::Abort => "abort",
+            SelfTradeBehavior::CancelProvide => "cancelProvide",
+            SelfTradeBehavior::DecrementTake => "decrementTake",
+        })
+    }
+}
+
+impl JsonSchema for SelfTradeBehavior {
+    fn json_schema() -> serde_json::Value {
+        serde_json::json!({ "enum": ["abort", "cancelProvide", "decrementTake"] })
+    }
+}
+
 #[action_enum]
 pub enum PhoenixV1Action {
     Swap(SwapAction),
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[action_enum(tag = "order_type")]
 pub enum SwapAction {
     ImmediateOrCancel {
         side: Side,
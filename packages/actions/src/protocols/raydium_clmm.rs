@@ -42,19 +42,30 @@ impl ActionTrait for RaydiumClmmAction {
                     (&action.input_token_account, &action.input_vault)
                 };
 
-                let non_base_amount = find_transfer(tree, action_id, from, to)
-                    .ok_or_else(|| anyhow::anyhow!("Could not find transfer"))?
-                    .amount;
+                let non_base_transfer = find_transfer(txn, tree, action_id, from, to)
+                    .ok_or_else(|| anyhow::anyhow!("Could not find transfer"))?;
 
                 let (input_amount, output_amount) = if action.is_base_input {
-                    (action.amount, non_base_amount)
+                    (action.amount, non_base_transfer.received_amount())
                 } else {
-                    (non_base_amount, action.amount)
+                    (non_base_transfer.amount, action.amount)
+                };
+
+                let (input_mint, output_mint) = if action.is_base_input {
+                    (
+                        txn.get_mint_for_token_account(&action.input_token_account)?,
+                        non_base_transfer.mint(txn, &action.output_token_account)?,
+                    )
+                } else {
+                    (
+                        non_base_transfer.mint(txn, &action.input_token_account)?,
+                        txn.get_mint_for_token_account(&action.output_token_account)?,
+                    )
                 };
 
                 DexSwap {
-                    input_mint: txn.get_mint_for_token_account(&action.input_token_account)?,
-                    output_mint: txn.get_mint_for_token_account(&action.output_token_account)?,
+                    input_mint,
+                    output_mint,
                     input_token_account: action.input_token_account,
                     output_token_account: action.output_token_account,
                     input_amount,
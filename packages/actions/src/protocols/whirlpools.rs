@@ -124,16 +124,27 @@ fn whirlpool_into_dex_swap(
     output_token_account: Pubkey,
     output_pool_account: Pubkey,
 ) -> Result<DexSwap, anyhow::Error> {
-    let input_transfer = find_transfer(tree, action_id, &input_token_account, &input_pool_account)
-        .ok_or_else(|| anyhow::anyhow!("No input transfer found"))?;
+    let input_transfer = find_transfer(
+        txn,
+        tree,
+        action_id,
+        &input_token_account,
+        &input_pool_account,
+    )
+    .ok_or_else(|| anyhow::anyhow!("No input transfer found"))?;
 
-    let output_transfer =
-        find_transfer(tree, action_id, &output_pool_account, &output_token_account)
-            .ok_or_else(|| anyhow::anyhow!("No output transfer found"))?;
+    let output_transfer = find_transfer(
+        txn,
+        tree,
+        action_id,
+        &output_pool_account,
+        &output_token_account,
+    )
+    .ok_or_else(|| anyhow::anyhow!("No output transfer found"))?;
 
-    let input_mint = txn.get_mint_for_token_account(&input_token_account)?;
+    let input_mint = input_transfer.mint(txn, &input_token_account)?;
 
-    let output_mint = txn.get_mint_for_token_account(&output_token_account)?;
+    let output_mint = output_transfer.mint(txn, &output_token_account)?;
 
     Ok(DexSwap {
         input_mint,
@@ -141,6 +152,6 @@ fn whirlpool_into_dex_swap(
         input_token_account,
         output_token_account,
         input_amount: input_transfer.amount,
-        output_amount: output_transfer.amount,
+        output_amount: output_transfer.received_amount(),
     })
 }
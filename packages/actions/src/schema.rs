@@ -0,0 +1,353 @@
+use classifier_core::{
+    json::{JsonSchema, SCHEMA_VERSION},
+    TransactionTag,
+};
+
+use crate::Action;
+
+/// Schema of a node of a tree serialized by [`crate::serialize_block`] or
+/// [`crate::serialize_transaction`]. Children reference the node schema at `node_ref`.
+pub fn action_node_schema(node_ref: &str) -> serde_json::Value {
+    serde_json::json!({
+        "allOf": [
+            Action::json_schema(),
+            {
+                "type": "object",
+                "properties": {
+                    "id": {
+                        "type": "integer",
+                        "description": "Position of the node among the root's descendants, absent on the root",
+                    },
+                    "children": {
+                        "type": "array",
+                        "items": { "$ref": node_ref },
+                    },
+                },
+            },
+        ],
+    })
+}
+
+/// JSON Schema of serialized trees and the MEV tags of their transactions, at
+/// [`SCHEMA_VERSION`]
+pub fn output_schema() -> serde_json::Value {
+    serde_json::json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": "Classified action tree",
+        "schemaVersion": SCHEMA_VERSION,
+        "$ref": "#/$defs/ActionNode",
+        "$defs": {
+            "ActionNode": action_node_schema("#/$defs/ActionNode"),
+            "TransactionTag": TransactionTag::json_schema(),
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use classifier_core::{
+        document,
+        document::{ToDocumentValue, Value},
+        json::ToJsonValue,
+        AtomicArbitrageTag, ClassifiableTransaction, SandwichAttackTag,
+    };
+    use macros::{action, action_enum};
+    use solana_sdk::{
+        message::{Message, VersionedMessage},
+        pubkey::Pubkey,
+        signature::Signature,
+        transaction::VersionedTransaction,
+    };
+    use solana_transaction_status::UiTransactionStatusMeta;
+
+    use super::*;
+    use crate::{
+        serialize_block, ActionTree, Block, DexSwap, JitoTip, LeaderSource, NativeTransfer,
+        RewardDiscrepancy, SetComputeUnitPrice,
+    };
+
+    fn assert_valid(schema: &serde_json::Value, instance: &serde_json::Value) {
+        let validator = jsonschema::JSONSchema::compile(schema).unwrap();
+        let errors = match validator.validate(instance) {
+            Ok(()) => return,
+            Err(errors) => errors
+                .map(|err| format!("{} at {}", err, err.instance_path))
+                .collect::<Vec<_>>(),
+        };
+        panic!("{} does not match its schema: {:#?}", instance, errors);
+    }
+
+    #[action]
+    struct JsonOptions {
+        #[json(rename = "customKey")]
+        renamed_field: u64,
+        #[json(skip)]
+        skipped_field: u64,
+        large_amount: u64,
+        owner: Option<Pubkey>,
+    }
+
+    #[action]
+    struct JsonTuple(u8, Pubkey);
+
+    #[action_enum(tag = "event_kind")]
+    enum JsonEnum {
+        Unit,
+        Single(u64),
+        Tuple(u8, u8),
+        Named {
+            #[json(rename = "mintAddress")]
+            mint: Pubkey,
+            #[json(skip)]
+            cached_price: u64,
+            amount_out: u64,
+        },
+    }
+
+    #[test]
+    fn derived_struct_json_renames_and_skips_fields() {
+        let owner = Pubkey::new_unique();
+        let value = JsonOptions {
+            renamed_field: 1,
+            skipped_field: 2,
+            large_amount: u64::MAX,
+            owner: Some(owner),
+        };
+
+        let json = value.to_json_value();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "customKey": 1,
+                "largeAmount": u64::MAX.to_string(),
+                "owner": owner.to_string(),
+            })
+        );
+        assert_valid(&JsonOptions::json_schema(), &json);
+        assert_eq!(
+            JsonOptions::json_schema()["required"],
+            serde_json::json!(["customKey", "largeAmount", "owner"])
+        );
+    }
+
+    #[test]
+    fn derived_tuple_struct_json_is_an_array() {
+        let pubkey = Pubkey::new_unique();
+        let json = JsonTuple(3, pubkey).to_json_value();
+
+        assert_eq!(json, serde_json::json!([3, pubkey.to_string()]));
+        assert_valid(&JsonTuple::json_schema(), &json);
+    }
+
+    #[test]
+    fn derived_enum_json_is_tagged() {
+        let mint = Pubkey::new_unique();
+        let cases = [
+            (JsonEnum::Unit, serde_json::json!({ "eventKind": "unit" })),
+            (
+                JsonEnum::Single(7),
+                serde_json::json!({ "eventKind": "single", "value": 7 }),
+            ),
+            (
+                JsonEnum::Tuple(1, 2),
+                serde_json::json!({ "eventKind": "tuple", "value": [1, 2] }),
+            ),
+            (
+                JsonEnum::Named {
+                    mint,
+                    cached_price: 5,
+                    amount_out: 9,
+                },
+                serde_json::json!({
+                    "eventKind": "named",
+                    "mintAddress": mint.to_string(),
+                    "amountOut": 9,
+                }),
+            ),
+        ];
+
+        let schema = JsonEnum::json_schema();
+        for (value, expected) in cases {
+            let json = value.to_json_value();
+            assert_eq!(json, expected);
+            assert_valid(&schema, &json);
+        }
+
+        // A variant's fields do not match another variant's tag
+        assert!(!jsonschema::is_valid(
+            &schema,
+            &serde_json::json!({ "eventKind": "single", "value": [1, 2] })
+        ));
+    }
+
+    #[test]
+    fn derived_struct_document_keeps_every_field() {
+        let owner = Pubkey::new_unique();
+        let value = JsonOptions {
+            renamed_field: 1,
+            skipped_field: 2,
+            large_amount: u64::MAX,
+            owner: Some(owner),
+        };
+
+        // JSON options only apply to the API output
+        assert_eq!(
+            value.to_document_value(),
+            Value::Document(document! {
+                "renamed_field": 1u64,
+                "skipped_field": 2u64,
+                "large_amount": u64::MAX,
+                "owner": owner,
+            })
+        );
+        assert_eq!(
+            JsonTuple(3, owner).to_document_value(),
+            Value::Array(vec![Value::Int(3), Value::Pubkey(owner)])
+        );
+    }
+
+    #[test]
+    fn derived_enum_document_is_tagged() {
+        let mint = Pubkey::new_unique();
+        let cases = [
+            (JsonEnum::Unit, document! { "event_kind": "Unit" }),
+            (
+                JsonEnum::Single(7),
+                document! { "event_kind": "Single", "value": 7u64 },
+            ),
+            (
+                JsonEnum::Tuple(1, 2),
+                document! { "event_kind": "Tuple", "value": vec![1u8, 2u8] },
+            ),
+            (
+                JsonEnum::Named {
+                    mint,
+                    cached_price: 5,
+                    amount_out: 9,
+                },
+                document! {
+                    "event_kind": "Named",
+                    "mint": mint,
+                    "cached_price": 5u64,
+                    "amount_out": 9u64,
+                },
+            ),
+        ];
+
+        for (value, expected) in cases {
+            assert_eq!(value.to_document_value(), Value::Document(expected));
+        }
+    }
+
+    fn tags() -> Vec<TransactionTag> {
+        vec![
+            TransactionTag::AtomicArbitrage(AtomicArbitrageTag {
+                mint: Pubkey::new_unique(),
+                profit_amount: i128::MAX,
+                address: Pubkey::new_unique(),
+            }),
+            TransactionTag::SandwichAttack(SandwichAttackTag::Frontrun {
+                token_bought: Pubkey::new_unique(),
+                amount: u64::MAX,
+                attacker_pubkey: Pubkey::new_unique(),
+            }),
+            TransactionTag::SandwichAttack(SandwichAttackTag::Victim {
+                token_bought: Pubkey::new_unique(),
+                amount: 10,
+                victim_pubkey: Pubkey::new_unique(),
+            }),
+            TransactionTag::SandwichAttack(SandwichAttackTag::Backrun {
+                token_sold: Pubkey::new_unique(),
+                amount: 10,
+                attacker_pubkey: Pubkey::new_unique(),
+                profit_amount: -5,
+            }),
+        ]
+    }
+
+    /// Block with one tagged transaction, which sets a priority fee, transfers, swaps and tips
+    fn block_tree() -> ActionTree {
+        let payer = Pubkey::new_unique();
+        let meta: UiTransactionStatusMeta = serde_json::from_value(serde_json::json!({
+            "err": null,
+            "status": { "Ok": null },
+            "fee": 5000,
+            "preBalances": [1_000_000],
+            "postBalances": [995_000],
+            "logMessages": [],
+        }))
+        .unwrap();
+
+        let mut transaction = ClassifiableTransaction::new(
+            VersionedTransaction {
+                signatures: vec![Signature::default()],
+                message: VersionedMessage::Legacy(Message::new(&[], Some(&payer))),
+            },
+            meta,
+        );
+        transaction.tags = tags();
+
+        let mut block = Block::new(150, 149, 0, Some(payer.to_string()));
+        block.leader_source = Some(LeaderSource::FeeReward);
+        block.reward_discrepancies = Some(vec![
+            RewardDiscrepancy::MissingFeeReward { expected: 2500 },
+            RewardDiscrepancy::FeeReward {
+                expected: 2500,
+                actual: -1,
+            },
+            RewardDiscrepancy::TransactionFee {
+                signature: Signature::default(),
+                expected: 5000,
+                actual: 10_000,
+            },
+        ]);
+
+        let mut tree = ActionTree::new(block.into());
+        let transaction_id = tree.insert_child(tree.root(), transaction.into());
+        tree.insert_child(
+            transaction_id,
+            SetComputeUnitPrice {
+                micro_lamports: 1000,
+            }
+            .into(),
+        );
+        tree.insert_child(
+            transaction_id,
+            NativeTransfer {
+                from: payer,
+                to: Pubkey::new_unique(),
+                lamports: 100,
+            }
+            .into(),
+        );
+        tree.insert_child(
+            transaction_id,
+            DexSwap {
+                input_mint: Pubkey::new_unique(),
+                output_mint: Pubkey::new_unique(),
+                input_token_account: Pubkey::new_unique(),
+                output_token_account: Pubkey::new_unique(),
+                input_amount: 100,
+                output_amount: 200,
+            }
+            .into(),
+        );
+        tree.insert_child(transaction_id, JitoTip::new(payer, 1000).into());
+
+        tree
+    }
+
+    #[test]
+    fn serialized_block_matches_output_schema() {
+        let tree = block_tree();
+        assert_valid(&output_schema(), &serialize_block(&tree, tree.root()));
+    }
+
+    #[test]
+    fn tags_match_their_schema() {
+        let schema = TransactionTag::json_schema();
+        for tag in tags() {
+            assert_valid(&schema, &tag.to_json_value());
+        }
+    }
+}
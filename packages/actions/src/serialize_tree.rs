@@ -1,20 +1,29 @@
 use action_tree::ActionNodeId;
 
-use crate::{ActionTrait, ActionTree};
+use crate::{Action, ActionTrait, ActionTree};
 
 /// Currently assumes root node is a Block action. This is subject to change.
 pub fn serialize_block(tree: &ActionTree, block_id: ActionNodeId) -> serde_json::Value {
-    let mut descendants = tree.descendants(block_id);
+    serialize_subtree(tree, block_id)
+}
+
+/// Serializes a tree rooted at a single transaction, classified outside of its block
+pub fn serialize_transaction(tree: &ActionTree, transaction_id: ActionNodeId) -> serde_json::Value {
+    serialize_subtree(tree, transaction_id)
+}
 
-    let block = descendants.next().unwrap();
-    let value = tree.get(block).unwrap().get().to_json();
+fn serialize_subtree(tree: &ActionTree, root_id: ActionNodeId) -> serde_json::Value {
+    let mut descendants = tree.descendants(root_id);
+
+    let root = descendants.next().unwrap();
+    let value = action_json(tree.get(root).unwrap().get());
 
     // Stack to track node traversal
-    let mut parent_stack = vec![block];
+    let mut parent_stack = vec![root];
     let mut pruning = None;
 
     // Stack to track JSON serialization
-    let mut json_stack = vec![(block, value)];
+    let mut json_stack = vec![(root, value)];
 
     let mut i = 0;
 
@@ -41,7 +50,7 @@ pub fn serialize_block(tree: &ActionTree, block_id: ActionNodeId) -> serde_json:
         }
 
         if action.serializable() {
-            let mut node_json = action.to_json();
+            let mut node_json = action_json(action);
             node_json
                 .as_object_mut()
                 .unwrap()
@@ -61,6 +70,19 @@ pub fn serialize_block(tree: &ActionTree, block_id: ActionNodeId) -> serde_json:
     json_stack.pop().unwrap().1
 }
 
+/// JSON of an action, tagged with its type unless the action sets its own
+fn action_json(action: &Action) -> serde_json::Value {
+    let mut json = action.to_json();
+
+    if let Some(object) = json.as_object_mut() {
+        object
+            .entry("type")
+            .or_insert_with(|| serde_json::Value::from(action.type_name()));
+    }
+
+    json
+}
+
 fn update_stacks(
     parent_stack: &mut Vec<ActionNodeId>,
     json_stack: &mut Vec<(ActionNodeId, serde_json::Value)>,
@@ -1,5 +1,5 @@
 use crate::ActionTrait;
-use macros::action;
+use macros::{action, action_enum};
 use solana_sdk::pubkey::Pubkey;
 
 /// General catch-all action for invoking any program.
@@ -13,17 +13,6 @@ impl ActionTrait for ProgramInvocation {
     fn recurse_during_classify(&self) -> bool {
         true
     }
-
-    fn serializable(&self) -> bool {
-        true
-    }
-
-    fn to_json(&self) -> serde_json::Value {
-        serde_json::json!({
-            "type": "programInvocation",
-            "programId": self.program_id.to_string(),
-        })
-    }
 }
 
 #[action]
@@ -44,3 +33,112 @@ impl ActionTrait for NativeTransfer {
         false
     }
 }
+
+/// System program instructions other than lamport transfers, which are classified
+/// as [`NativeTransfer`] or a tip.
+#[action_enum]
+pub enum System {
+    CreateAccount(crate::system_actions::CreateAccount),
+    CreateAccountWithSeed(crate::system_actions::CreateAccountWithSeed),
+    Assign(crate::system_actions::Assign),
+    AssignWithSeed(crate::system_actions::AssignWithSeed),
+    Allocate(crate::system_actions::Allocate),
+    AllocateWithSeed(crate::system_actions::AllocateWithSeed),
+    AdvanceNonceAccount(crate::system_actions::AdvanceNonceAccount),
+    WithdrawNonceAccount(crate::system_actions::WithdrawNonceAccount),
+    InitializeNonceAccount(crate::system_actions::InitializeNonceAccount),
+    AuthorizeNonceAccount(crate::system_actions::AuthorizeNonceAccount),
+    UpgradeNonceAccount(crate::system_actions::UpgradeNonceAccount),
+}
+
+impl ActionTrait for System {
+    fn recurse_during_classify(&self) -> bool {
+        false
+    }
+}
+
+pub mod system_actions {
+    use macros::action;
+    use solana_sdk::pubkey::Pubkey;
+
+    #[action]
+    pub struct CreateAccount {
+        pub from: Pubkey,
+        pub to: Pubkey,
+        pub lamports: u64,
+        pub space: u64,
+        pub owner: Pubkey,
+    }
+
+    #[action]
+    pub struct CreateAccountWithSeed {
+        pub from: Pubkey,
+        pub to: Pubkey,
+        pub base: Pubkey,
+        pub seed: String,
+        pub lamports: u64,
+        pub space: u64,
+        pub owner: Pubkey,
+    }
+
+    #[action]
+    pub struct Assign {
+        pub account: Pubkey,
+        pub owner: Pubkey,
+    }
+
+    #[action]
+    pub struct AssignWithSeed {
+        pub account: Pubkey,
+        pub base: Pubkey,
+        pub seed: String,
+        pub owner: Pubkey,
+    }
+
+    #[action]
+    pub struct Allocate {
+        pub account: Pubkey,
+        pub space: u64,
+    }
+
+    #[action]
+    pub struct AllocateWithSeed {
+        pub account: Pubkey,
+        pub base: Pubkey,
+        pub seed: String,
+        pub space: u64,
+        pub owner: Pubkey,
+    }
+
+    #[action]
+    pub struct AdvanceNonceAccount {
+        pub nonce_account: Pubkey,
+        pub authority: Pubkey,
+    }
+
+    #[action]
+    pub struct WithdrawNonceAccount {
+        pub nonce_account: Pubkey,
+        pub to: Pubkey,
+        pub authority: Pubkey,
+        pub lamports: u64,
+    }
+
+    #[action]
+    pub struct InitializeNonceAccount {
+        pub nonce_account: Pubkey,
+        pub authority: Pubkey,
+    }
+
+    #[action]
+    pub struct AuthorizeNonceAccount {
+        pub nonce_account: Pubkey,
+        pub authority: Pubkey,
+        pub new_authority: Pubkey,
+    }
+
+    #[action]
+    pub struct UpgradeNonceAccount {
+        pub nonce_account: Pubkey,
+    }
+}
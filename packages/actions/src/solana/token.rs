@@ -29,11 +29,31 @@ pub enum Token {
     InitializeImmutableOwner(crate::token_actions::InitializeImmutableOwner),
     AmountToUiAmount(crate::token_actions::AmountToUiAmount),
     UiAmountToAmount(crate::token_actions::UiAmountToAmount),
+
+    // Token-2022 extensions
+    InitializeMintCloseAuthority(crate::token_actions::InitializeMintCloseAuthority),
+    InitializeTransferFeeConfig(crate::token_actions::InitializeTransferFeeConfig),
+    TransferCheckedWithFee(crate::token_actions::TransferCheckedWithFee),
+    WithdrawWithheldTokensFromMint(crate::token_actions::WithdrawWithheldTokensFromMint),
+    WithdrawWithheldTokensFromAccounts(crate::token_actions::WithdrawWithheldTokensFromAccounts),
+    HarvestWithheldTokensToMint(crate::token_actions::HarvestWithheldTokensToMint),
+    SetTransferFee(crate::token_actions::SetTransferFee),
+    ConfidentialTransfer(crate::token_actions::ConfidentialTransfer),
+    InitializeTransferHook(crate::token_actions::InitializeTransferHook),
+    UpdateTransferHook(crate::token_actions::UpdateTransferHook),
+    InitializeMetadataPointer(crate::token_actions::InitializeMetadataPointer),
+    UpdateMetadataPointer(crate::token_actions::UpdateMetadataPointer),
+    InitializeNonTransferableMint(crate::token_actions::InitializeNonTransferableMint),
+    InitializePermanentDelegate(crate::token_actions::InitializePermanentDelegate),
 }
 
 impl ActionTrait for Token {
     fn recurse_during_classify(&self) -> bool {
-        false
+        // Token-2022 transfers CPI into the mint's transfer hook program
+        matches!(
+            self,
+            Token::TransferChecked(_) | Token::TransferCheckedWithFee(_)
+        )
     }
 }
 
@@ -216,4 +236,107 @@ pub mod token_actions {
         pub mint: Pubkey,
         pub ui_amount: String,
     }
+
+    #[action]
+    pub struct InitializeMintCloseAuthority {
+        pub mint: Pubkey,
+        pub close_authority: Option<Pubkey>,
+    }
+
+    #[action]
+    pub struct InitializeTransferFeeConfig {
+        pub mint: Pubkey,
+        pub transfer_fee_config_authority: Option<Pubkey>,
+        pub withdraw_withheld_authority: Option<Pubkey>,
+        pub transfer_fee_basis_points: u16,
+        pub maximum_fee: u64,
+    }
+
+    /// `amount` is debited from the source, `amount - fee` is credited to the destination
+    #[action]
+    pub struct TransferCheckedWithFee {
+        pub source: Pubkey,
+        pub mint: Pubkey,
+        pub destination: Pubkey,
+        pub owner: Pubkey,
+        pub amount: u64,
+        pub decimals: u8,
+        pub fee: u64,
+    }
+
+    #[action]
+    pub struct WithdrawWithheldTokensFromMint {
+        pub mint: Pubkey,
+        pub destination: Pubkey,
+        pub authority: Pubkey,
+    }
+
+    #[action]
+    pub struct WithdrawWithheldTokensFromAccounts {
+        pub mint: Pubkey,
+        pub destination: Pubkey,
+        pub authority: Pubkey,
+        pub sources: Vec<Pubkey>,
+    }
+
+    #[action]
+    pub struct HarvestWithheldTokensToMint {
+        pub mint: Pubkey,
+        pub sources: Vec<Pubkey>,
+    }
+
+    #[action]
+    pub struct SetTransferFee {
+        pub mint: Pubkey,
+        pub authority: Pubkey,
+        pub transfer_fee_basis_points: u16,
+        pub maximum_fee: u64,
+    }
+
+    /// Confidential transfer amounts are encrypted, so only the instruction kind and
+    /// the account it operates on are recorded.
+    #[action]
+    pub struct ConfidentialTransfer {
+        pub instruction: String,
+        pub account: Pubkey,
+    }
+
+    #[action]
+    pub struct InitializeTransferHook {
+        pub mint: Pubkey,
+        pub authority: Option<Pubkey>,
+        pub program_id: Option<Pubkey>,
+    }
+
+    #[action]
+    pub struct UpdateTransferHook {
+        pub mint: Pubkey,
+        pub authority: Pubkey,
+        pub program_id: Option<Pubkey>,
+    }
+
+    #[action]
+    pub struct InitializeMetadataPointer {
+        pub mint: Pubkey,
+        pub authority: Option<Pubkey>,
+        pub metadata_address: Option<Pubkey>,
+    }
+
+    #[action]
+    pub struct UpdateMetadataPointer {
+        pub mint: Pubkey,
+        pub authority: Pubkey,
+        pub metadata_address: Option<Pubkey>,
+    }
+
+    #[action]
+    pub struct InitializeNonTransferableMint {
+        pub mint: Pubkey,
+    }
+
+    #[action]
+    pub struct InitializePermanentDelegate {
+        pub mint: Pubkey,
+        pub delegate: Pubkey,
+    }
 }
@@ -1,17 +1,46 @@
 use macros::{action, action_enum};
-use solana_sdk::{pubkey::Pubkey, vote::state::VoteStateUpdate};
+use solana_sdk::{hash::Hash, pubkey::Pubkey};
 
 use crate::ActionTrait;
 
-#[action_enum]
+/// Tower vote instructions. All variants carry the same vote details, the variant
+/// records which instruction was used to cast them.
+#[action_enum(tag = "instruction")]
 pub enum Vote {
-    CompactUpdateVoteState(CompactUpdateVoteState),
+    Vote(VoteUpdate),
+    VoteSwitch(VoteUpdate),
+    UpdateVoteState(VoteUpdate),
+    UpdateVoteStateSwitch(VoteUpdate),
+    CompactUpdateVoteState(VoteUpdate),
+    CompactUpdateVoteStateSwitch(VoteUpdate),
 }
 
 impl Vote {
     pub(crate) fn recurse_during_classify(&self) -> bool {
         false
     }
+
+    pub fn update(&self) -> &VoteUpdate {
+        match self {
+            Vote::Vote(update)
+            | Vote::VoteSwitch(update)
+            | Vote::UpdateVoteState(update)
+            | Vote::UpdateVoteStateSwitch(update)
+            | Vote::CompactUpdateVoteState(update)
+            | Vote::CompactUpdateVoteStateSwitch(update) => update,
+        }
+    }
+
+    pub fn instruction_name(&self) -> &'static str {
+        match self {
+            Vote::Vote(_) => "vote",
+            Vote::VoteSwitch(_) => "voteSwitch",
+            Vote::UpdateVoteState(_) => "updateVoteState",
+            Vote::UpdateVoteStateSwitch(_) => "updateVoteStateSwitch",
+            Vote::CompactUpdateVoteState(_) => "compactUpdateVoteState",
+            Vote::CompactUpdateVoteStateSwitch(_) => "compactUpdateVoteStateSwitch",
+        }
+    }
 }
 
 impl ActionTrait for Vote {
@@ -21,13 +50,22 @@ impl ActionTrait for Vote {
 }
 
 #[action]
-pub struct CompactUpdateVoteState {
+pub struct VoteUpdate {
+    pub vote_account: Pubkey,
     pub vote_authority: Pubkey,
-    pub update: VoteStateUpdate,
+
+    /// Slots voted on, oldest first
+    pub slots: Vec<u64>,
+    pub root: Option<u64>,
+
+    /// Bank hash of the newest voted slot
+    pub hash: Hash,
+    pub timestamp: Option<i64>,
 }
 
-impl Into<Vote> for CompactUpdateVoteState {
-    fn into(self) -> Vote {
-        Vote::CompactUpdateVoteState(self)
+impl VoteUpdate {
+    /// Newest slot this vote is for
+    pub fn target_slot(&self) -> Option<u64> {
+        self.slots.last().copied()
     }
 }
@@ -0,0 +1,250 @@
+use std::{collections::HashMap, time::Duration};
+
+use futures::{channel::mpsc as request_channel, SinkExt, StreamExt};
+use solana_sdk::reward_type::RewardType;
+use solana_transaction_status::{
+    BlockEncodingOptions, ConfirmedBlock, EncodeError, Reward, TransactionDetails,
+    UiConfirmedBlock, UiTransactionEncoding,
+};
+use thiserror::Error;
+use tokio::sync::mpsc;
+use tonic::{metadata::AsciiMetadataValue, transport::Endpoint, Request};
+use yellowstone_grpc_proto::{
+    convert_from::create_tx_with_meta,
+    geyser::{
+        geyser_client::GeyserClient, subscribe_update::UpdateOneof, CommitmentLevel,
+        SubscribeRequest, SubscribeRequestFilterBlocks, SubscribeUpdateBlock,
+    },
+    solana::storage::confirmed_block,
+};
+
+use super::{BlockSource, SourcedBlock, SourcedBlockReceiver};
+
+#[derive(Debug, Error)]
+pub enum GeyserSourceError {
+    #[error("Invalid endpoint: {0}")]
+    InvalidEndpoint(#[source] tonic::transport::Error),
+
+    #[error("Invalid x-token")]
+    InvalidToken,
+
+    #[error("Failed to connect: {0}")]
+    ConnectError(#[source] tonic::transport::Error),
+
+    #[error("Subscription failed: {0}")]
+    SubscribeError(#[from] tonic::Status),
+
+    #[error("Subscription stream closed")]
+    StreamClosed,
+
+    #[error("Failed to convert block {0}: {1}")]
+    ConversionError(u64, String),
+
+    #[error("Failed to encode block: {0}")]
+    EncodeError(#[from] EncodeError),
+}
+
+type Result<T> = std::result::Result<T, GeyserSourceError>;
+
+pub struct GeyserBlockSourceConfig {
+    /// gRPC endpoint of a Yellowstone compatible Geyser plugin, e.g. `http://127.0.0.1:10000`
+    pub endpoint: String,
+
+    /// Sent as the `x-token` header, for endpoints which require authentication
+    pub x_token: Option<String>,
+
+    /// Delay before reconnecting after the subscription fails
+    pub reconnect_backoff: Duration,
+
+    /// Blocks buffered for the live lane before the subscription stops being read
+    pub buffer: usize,
+}
+
+/// Block source which subscribes to confirmed blocks from a Yellowstone gRPC Geyser
+/// plugin, converting them to the encoding returned by RPC `getBlock`.
+pub struct GeyserBlockSource {
+    config: GeyserBlockSourceConfig,
+}
+
+impl GeyserBlockSource {
+    pub fn new(config: GeyserBlockSourceConfig) -> Self {
+        Self { config }
+    }
+
+    async fn subscription_task(
+        endpoint: String,
+        x_token: Option<String>,
+        reconnect_backoff: Duration,
+        block_tx: mpsc::Sender<SourcedBlock>,
+    ) {
+        loop {
+            match Self::subscribe_blocks(&endpoint, x_token.as_deref(), &block_tx).await {
+                // Receiver dropped, nothing is listening anymore
+                Ok(()) => return,
+                Err(err) => {
+                    tracing::error!("Geyser subscription to {} failed: {}", endpoint, err);
+                }
+            }
+
+            if block_tx.is_closed() {
+                return;
+            }
+
+            tokio::time::sleep(reconnect_backoff).await;
+        }
+    }
+
+    /// Streams blocks into the channel until the subscription fails or the receiver
+    /// is dropped
+    async fn subscribe_blocks(
+        endpoint: &str,
+        x_token: Option<&str>,
+        block_tx: &mpsc::Sender<SourcedBlock>,
+    ) -> Result<()> {
+        let x_token = x_token
+            .map(|x_token| {
+                x_token
+                    .parse::<AsciiMetadataValue>()
+                    .map_err(|_| GeyserSourceError::InvalidToken)
+            })
+            .transpose()?;
+
+        let channel = Endpoint::from_shared(endpoint.to_string())
+            .map_err(GeyserSourceError::InvalidEndpoint)?
+            .connect_timeout(Duration::from_secs(10))
+            .connect()
+            .await
+            .map_err(GeyserSourceError::ConnectError)?;
+
+        let mut client = GeyserClient::with_interceptor(channel, move |mut request: Request<()>| {
+            if let Some(x_token) = &x_token {
+                request.metadata_mut().insert("x-token", x_token.clone());
+            }
+            Ok::<_, tonic::Status>(request)
+        });
+
+        // The request stream is held open for the lifetime of the subscription
+        let (mut request_tx, request_rx) = request_channel::channel(1);
+        request_tx
+            .send(Self::subscribe_request())
+            .await
+            .map_err(|_| GeyserSourceError::StreamClosed)?;
+
+        let mut updates = client.subscribe(request_rx).await?.into_inner();
+        tracing::info!("Subscribed to Geyser blocks from {}", endpoint);
+
+        while let Some(update) = updates.next().await {
+            let block = match update?.update_oneof {
+                Some(UpdateOneof::Block(block)) => block,
+                _ => continue,
+            };
+
+            let slot = block.slot;
+            let block = match convert_block(block) {
+                Ok(block) => block,
+                Err(err) => {
+                    // The requester sees the gap and fetches the block over RPC
+                    tracing::error!("Skipping Geyser block {}: {}", slot, err);
+                    continue;
+                }
+            };
+
+            if block_tx.send(SourcedBlock { slot, block }).await.is_err() {
+                return Ok(());
+            }
+        }
+
+        Err(GeyserSourceError::StreamClosed)
+    }
+
+    fn subscribe_request() -> SubscribeRequest {
+        SubscribeRequest {
+            blocks: HashMap::from([(
+                "blocks".to_string(),
+                SubscribeRequestFilterBlocks {
+                    include_transactions: Some(true),
+                    include_accounts: Some(false),
+                    include_entries: Some(false),
+                    ..Default::default()
+                },
+            )]),
+            commitment: Some(CommitmentLevel::Confirmed as i32),
+            ..Default::default()
+        }
+    }
+}
+
+impl BlockSource for GeyserBlockSource {
+    fn subscribe(&self) -> SourcedBlockReceiver {
+        let (block_tx, block_rx) = mpsc::channel(self.config.buffer.max(1));
+
+        tokio::task::spawn(Self::subscription_task(
+            self.config.endpoint.clone(),
+            self.config.x_token.clone(),
+            self.config.reconnect_backoff,
+            block_tx,
+        ));
+
+        block_rx
+    }
+}
+
+/// Converts a Geyser block update into the block RPC would return for the same slot
+pub fn convert_block(block: SubscribeUpdateBlock) -> Result<UiConfirmedBlock> {
+    let slot = block.slot;
+
+    let mut transactions = block.transactions;
+    transactions.sort_by_key(|txn| txn.index);
+
+    let transactions = transactions
+        .into_iter()
+        .map(|txn| {
+            create_tx_with_meta(txn)
+                .map_err(|err| GeyserSourceError::ConversionError(slot, err.to_string()))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let rewards = block
+        .rewards
+        .map(|rewards| rewards.rewards.into_iter().map(convert_reward).collect())
+        .unwrap_or_default();
+
+    let block = ConfirmedBlock {
+        previous_blockhash: block.parent_blockhash,
+        blockhash: block.blockhash,
+        parent_slot: block.parent_slot,
+        transactions,
+        rewards,
+        block_time: block.block_time.map(|block_time| block_time.timestamp),
+        block_height: block
+            .block_height
+            .map(|block_height| block_height.block_height),
+    };
+
+    Ok(block.encode_with_options(
+        UiTransactionEncoding::Base64,
+        BlockEncodingOptions {
+            transaction_details: TransactionDetails::Full,
+            show_rewards: true,
+            max_supported_transaction_version: Some(0),
+        },
+    )?)
+}
+
+fn convert_reward(reward: confirmed_block::Reward) -> Reward {
+    let reward_type = match confirmed_block::RewardType::try_from(reward.reward_type) {
+        Ok(confirmed_block::RewardType::Fee) => Some(RewardType::Fee),
+        Ok(confirmed_block::RewardType::Rent) => Some(RewardType::Rent),
+        Ok(confirmed_block::RewardType::Staking) => Some(RewardType::Staking),
+        Ok(confirmed_block::RewardType::Voting) => Some(RewardType::Voting),
+        _ => None,
+    };
+
+    Reward {
+        pubkey: reward.pubkey,
+        lamports: reward.lamports,
+        post_balance: reward.post_balance,
+        reward_type,
+        commission: reward.commission.parse().ok(),
+    }
+}
@@ -0,0 +1,115 @@
+use std::{net::SocketAddr, pin::Pin, sync::Arc, time::Duration};
+
+use futures::{Stream, StreamExt};
+use tonic::{transport::Server, Request, Response, Status, Streaming};
+use yellowstone_grpc_proto::geyser::{
+    geyser_server::{Geyser, GeyserServer},
+    GetBlockHeightRequest, GetBlockHeightResponse, GetLatestBlockhashRequest,
+    GetLatestBlockhashResponse, GetSlotRequest, GetSlotResponse, GetVersionRequest,
+    GetVersionResponse, IsBlockhashValidRequest, IsBlockhashValidResponse, PingRequest,
+    PongResponse, SubscribeRequest, SubscribeUpdate,
+};
+
+/// Local Geyser gRPC server which replays a fixed list of updates to every subscriber,
+/// for exercising the Geyser block source without a validator.
+#[derive(Clone)]
+pub struct MockGeyserServer {
+    updates: Arc<Vec<SubscribeUpdate>>,
+
+    /// Delay between updates sent to a subscriber
+    interval: Duration,
+}
+
+impl MockGeyserServer {
+    pub fn new(updates: Vec<SubscribeUpdate>, interval: Duration) -> Self {
+        Self {
+            updates: Arc::new(updates),
+            interval,
+        }
+    }
+
+    /// Serve until the shutdown future resolves
+    pub async fn serve(
+        self,
+        addr: SocketAddr,
+        shutdown: impl std::future::Future<Output = ()>,
+    ) -> Result<(), tonic::transport::Error> {
+        Server::builder()
+            .add_service(GeyserServer::new(self))
+            .serve_with_shutdown(addr, shutdown)
+            .await
+    }
+}
+
+type SubscribeStream = Pin<Box<dyn Stream<Item = Result<SubscribeUpdate, Status>> + Send>>;
+
+#[tonic::async_trait]
+impl Geyser for MockGeyserServer {
+    type SubscribeStream = SubscribeStream;
+
+    async fn subscribe(
+        &self,
+        _request: Request<Streaming<SubscribeRequest>>,
+    ) -> Result<Response<Self::SubscribeStream>, Status> {
+        let updates = self.updates.clone();
+        let interval = self.interval;
+
+        // Updates are replayed regardless of the subscription filters. The stream stays
+        // open afterwards, like a subscription waiting for the next block.
+        let stream = futures::stream::unfold(0, move |index| {
+            let updates = updates.clone();
+            async move {
+                let update = updates.get(index)?.clone();
+                tokio::time::sleep(interval).await;
+
+                Some((Ok(update), index + 1))
+            }
+        })
+        .chain(futures::stream::pending());
+
+        Ok(Response::new(Box::pin(stream)))
+    }
+
+    async fn ping(&self, request: Request<PingRequest>) -> Result<Response<PongResponse>, Status> {
+        Ok(Response::new(PongResponse {
+            count: request.into_inner().count,
+        }))
+    }
+
+    async fn get_latest_blockhash(
+        &self,
+        _request: Request<GetLatestBlockhashRequest>,
+    ) -> Result<Response<GetLatestBlockhashResponse>, Status> {
+        Err(Status::unimplemented("Not supported by the mock server"))
+    }
+
+    async fn get_block_height(
+        &self,
+        _request: Request<GetBlockHeightRequest>,
+    ) -> Result<Response<GetBlockHeightResponse>, Status> {
+        Err(Status::unimplemented("Not supported by the mock server"))
+    }
+
+    async fn get_slot(
+        &self,
+        _request: Request<GetSlotRequest>,
+    ) -> Result<Response<GetSlotResponse>, Status> {
+        Err(Status::unimplemented("Not supported by the mock server"))
+    }
+
+    async fn is_blockhash_valid(
+        &self,
+        _request: Request<IsBlockhashValidRequest>,
+    ) -> Result<Response<IsBlockhashValidResponse>, Status> {
+        Err(Status::unimplemented("Not supported by the mock server"))
+    }
+
+    async fn get_version(
+        &self,
+        _request: Request<GetVersionRequest>,
+    ) -> Result<Response<GetVersionResponse>, Status> {
+        Ok(Response::new(GetVersionResponse {
+            version: "mock".to_string(),
+        }))
+    }
+}
@@ -0,0 +1,24 @@
+mod geyser;
+mod mock;
+
+pub use geyser::*;
+pub use mock::*;
+
+use solana_transaction_status::UiConfirmedBlock;
+use tokio::sync::mpsc;
+
+/// A block delivered by a block source, in the same form RPC returns it
+pub struct SourcedBlock {
+    pub slot: u64,
+    pub block: UiConfirmedBlock,
+}
+
+pub type SourcedBlockReceiver = mpsc::Receiver<SourcedBlock>;
+
+/// A source of newly confirmed blocks for the live indexing lane. Sources push complete
+/// blocks, so they are classified without fetching them from RPC.
+pub trait BlockSource: Send + Sync {
+    /// Start streaming blocks. Sources reconnect on their own, the stream only ends once
+    /// the receiver is dropped.
+    fn subscribe(&self) -> SourcedBlockReceiver;
+}
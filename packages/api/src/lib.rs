@@ -1,3 +1,4 @@
+pub mod ingestion;
 mod populator;
 mod routes;
 mod server;
@@ -0,0 +1,372 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::SystemTime,
+};
+
+use classifier_core::json::SCHEMA_VERSION;
+use inspection::database::block_store::BlockStore;
+
+pub struct BlockCacheConfig {
+    /// Maximum number of blocks held in memory
+    pub max_entries: usize,
+
+    /// Maximum size of the serialized blocks held in memory
+    pub max_bytes: usize,
+
+    /// Directory where classified blocks are persisted, so they survive restarts
+    pub disk_dir: Option<PathBuf>,
+
+    /// Maximum size of the blocks persisted to `disk_dir`. The oldest written blocks are
+    /// removed first.
+    pub disk_max_bytes: u64,
+}
+
+/// Serialized classified blocks keyed by slot. Lookups fall through memory, then disk,
+/// then blocks already indexed into the block store, so cached slots are served without fetching
+/// them from RPC.
+pub struct BlockCache {
+    config: BlockCacheConfig,
+    store: Option<Arc<dyn BlockStore>>,
+    entries: Mutex<CacheEntries>,
+
+    /// Size of the blocks persisted to disk. Held while writing, so writes and eviction
+    /// do not interleave.
+    disk_bytes: tokio::sync::Mutex<u64>,
+}
+
+struct CachedBlock {
+    json: Arc<serde_json::Value>,
+    size: usize,
+    last_used: u64,
+}
+
+#[derive(Default)]
+struct CacheEntries {
+    blocks: HashMap<u64, CachedBlock>,
+
+    /// Slots ordered by last use, for least recently used eviction
+    usage: BTreeMap<u64, u64>,
+    clock: u64,
+    bytes: usize,
+}
+
+impl CacheEntries {
+    fn get(&mut self, slot: u64) -> Option<Arc<serde_json::Value>> {
+        self.clock += 1;
+        let clock = self.clock;
+
+        let block = self.blocks.get_mut(&slot)?;
+        self.usage.remove(&block.last_used);
+        self.usage.insert(clock, slot);
+        block.last_used = clock;
+
+        Some(block.json.clone())
+    }
+
+    fn insert(&mut self, slot: u64, json: Arc<serde_json::Value>, size: usize) {
+        self.remove(slot);
+
+        self.clock += 1;
+        self.usage.insert(self.clock, slot);
+        self.bytes += size;
+        self.blocks.insert(
+            slot,
+            CachedBlock {
+                json,
+                size,
+                last_used: self.clock,
+            },
+        );
+    }
+
+    fn remove(&mut self, slot: u64) {
+        if let Some(block) = self.blocks.remove(&slot) {
+            self.usage.remove(&block.last_used);
+            self.bytes -= block.size;
+        }
+    }
+
+    fn evict(&mut self, max_entries: usize, max_bytes: usize) {
+        while self.blocks.len() > max_entries || self.bytes > max_bytes {
+            let slot = match self.usage.first_key_value() {
+                Some((_, slot)) => *slot,
+                None => break,
+            };
+
+            self.remove(slot);
+        }
+    }
+}
+
+impl BlockCache {
+    pub fn new(config: BlockCacheConfig, store: Option<Arc<dyn BlockStore>>) -> Self {
+        let disk_bytes = config
+            .disk_dir
+            .as_deref()
+            .map(|dir| disk_entries(dir).iter().map(|(_, size, _)| size).sum())
+            .unwrap_or_default();
+
+        Self {
+            config,
+            store,
+            entries: Mutex::new(CacheEntries::default()),
+            disk_bytes: tokio::sync::Mutex::new(disk_bytes),
+        }
+    }
+
+    pub async fn get(&self, slot: u64) -> Option<Arc<serde_json::Value>> {
+        if let Some(json) = self.entries.lock().unwrap().get(slot) {
+            return Some(json);
+        }
+
+        let serialized = match self.read_disk(slot).await {
+            Some(serialized) => serialized,
+            None => self.read_database(slot).await?,
+        };
+
+        let json = match serde_json::from_str::<serde_json::Value>(&serialized) {
+            Ok(json) => Arc::new(json),
+            Err(err) => {
+                tracing::error!("Failed to parse cached block {}: {:?}", slot, err);
+                return None;
+            }
+        };
+
+        self.insert_memory(slot, json.clone(), serialized.len());
+        Some(json)
+    }
+
+    pub async fn insert(&self, slot: u64, json: serde_json::Value) {
+        let serialized = json.to_string();
+
+        self.write_disk(slot, &serialized).await;
+        self.insert_memory(slot, Arc::new(json), serialized.len());
+    }
+
+    fn insert_memory(&self, slot: u64, json: Arc<serde_json::Value>, size: usize) {
+        // Blocks larger than the whole cache are only persisted
+        if size > self.config.max_bytes {
+            return;
+        }
+
+        let mut entries = self.entries.lock().unwrap();
+        entries.insert(slot, json, size);
+        entries.evict(self.config.max_entries, self.config.max_bytes);
+    }
+
+    /// Files are named by schema version, so blocks written by another version are
+    /// never read, and are evicted as the oldest blocks
+    fn disk_path(&self, slot: u64) -> Option<PathBuf> {
+        self.config
+            .disk_dir
+            .as_ref()
+            .map(|dir| dir.join(format!("{}.v{}.json", slot, SCHEMA_VERSION)))
+    }
+
+    async fn read_disk(&self, slot: u64) -> Option<String> {
+        let path = self.disk_path(slot)?;
+
+        match tokio::fs::read_to_string(&path).await {
+            Ok(serialized) => Some(serialized),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => None,
+            Err(err) => {
+                tracing::error!("Failed to read cached block {}: {:?}", slot, err);
+                None
+            }
+        }
+    }
+
+    async fn write_disk(&self, slot: u64, serialized: &str) {
+        let (dir, path) = match (&self.config.disk_dir, self.disk_path(slot)) {
+            (Some(dir), Some(path)) => (dir, path),
+            _ => return,
+        };
+
+        if serialized.len() as u64 > self.config.disk_max_bytes {
+            return;
+        }
+
+        let mut disk_bytes = self.disk_bytes.lock().await;
+
+        // A block written again replaces its previous file
+        if let Ok(metadata) = tokio::fs::metadata(&path).await {
+            *disk_bytes = disk_bytes.saturating_sub(metadata.len());
+        }
+
+        // Written to a temporary file and renamed, so readers never see a partial block
+        let temp_path = path.with_extension("json.tmp");
+        let written = match tokio::fs::write(&temp_path, serialized).await {
+            Ok(()) => tokio::fs::rename(&temp_path, &path).await,
+            Err(err) => Err(err),
+        };
+
+        if let Err(err) = written {
+            tracing::error!("Failed to write cached block {}: {:?}", slot, err);
+            let _ = tokio::fs::remove_file(&temp_path).await;
+            return;
+        }
+        *disk_bytes += serialized.len() as u64;
+
+        if *disk_bytes <= self.config.disk_max_bytes {
+            return;
+        }
+
+        let dir = dir.clone();
+        let max_bytes = self.config.disk_max_bytes;
+        let evict = tokio::task::spawn_blocking(move || evict_disk(&dir, max_bytes));
+
+        match evict.await {
+            Ok(remaining) => *disk_bytes = remaining,
+            Err(err) => tracing::error!("Failed to evict cached blocks: {:?}", err),
+        }
+    }
+
+    async fn read_database(&self, slot: u64) -> Option<String> {
+        let store = self.store.as_ref()?;
+
+        match store.read_block_json(slot).await {
+            Ok(serialized) => serialized,
+            Err(err) => {
+                tracing::error!("Failed to read indexed block {}: {:?}", slot, err);
+                None
+            }
+        }
+    }
+}
+
+/// Path, size and modification time of the blocks persisted in `dir`
+fn disk_entries(dir: &Path) -> Vec<(PathBuf, u64, SystemTime)> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) => {
+            tracing::error!(
+                "Failed to read cache directory {}: {:?}",
+                dir.display(),
+                err
+            );
+            return Vec::new();
+        }
+    };
+
+    entries
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            if path.extension().and_then(|extension| extension.to_str()) != Some("json") {
+                return None;
+            }
+
+            let metadata = std::fs::metadata(&path).ok()?;
+            Some((path, metadata.len(), metadata.modified().ok()?))
+        })
+        .collect()
+}
+
+/// Remove the oldest written blocks in `dir` until they fit in `max_bytes`, returning
+/// the size of the remaining blocks
+fn evict_disk(dir: &Path, max_bytes: u64) -> u64 {
+    let mut entries = disk_entries(dir);
+    entries.sort_by_key(|(_, _, modified)| *modified);
+
+    let mut bytes: u64 = entries.iter().map(|(_, size, _)| size).sum();
+    for (path, size, _) in entries {
+        if bytes <= max_bytes {
+            break;
+        }
+
+        match std::fs::remove_file(&path) {
+            Ok(()) => bytes -= size,
+            Err(err) => tracing::error!("Failed to evict {}: {:?}", path.display(), err),
+        }
+    }
+
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block(slot: u64) -> Arc<serde_json::Value> {
+        Arc::new(serde_json::json!({ "slot": slot }))
+    }
+
+    #[test]
+    fn entries_get_inserted_blocks() {
+        let mut entries = CacheEntries::default();
+        entries.insert(1, block(1), 10);
+        entries.insert(2, block(2), 20);
+
+        assert_eq!(entries.get(1), Some(block(1)));
+        assert_eq!(entries.get(3), None);
+        assert_eq!(entries.bytes, 30);
+    }
+
+    #[test]
+    fn entries_replace_blocks_inserted_again() {
+        let mut entries = CacheEntries::default();
+        entries.insert(1, block(1), 10);
+        entries.insert(1, block(2), 15);
+
+        assert_eq!(entries.get(1), Some(block(2)));
+        assert_eq!(entries.blocks.len(), 1);
+        assert_eq!(entries.usage.len(), 1);
+        assert_eq!(entries.bytes, 15);
+    }
+
+    #[test]
+    fn entries_evict_least_recently_used_blocks() {
+        let mut entries = CacheEntries::default();
+        entries.insert(1, block(1), 10);
+        entries.insert(2, block(2), 10);
+        entries.insert(3, block(3), 10);
+        entries.get(1);
+
+        entries.evict(2, usize::MAX);
+
+        assert_eq!(entries.get(2), None);
+        assert!(entries.get(1).is_some());
+        assert!(entries.get(3).is_some());
+        assert_eq!(entries.bytes, 20);
+    }
+
+    #[test]
+    fn entries_evict_until_under_max_bytes() {
+        let mut entries = CacheEntries::default();
+        entries.insert(1, block(1), 10);
+        entries.insert(2, block(2), 20);
+        entries.insert(3, block(3), 30);
+
+        entries.evict(usize::MAX, 35);
+
+        assert_eq!(entries.blocks.keys().copied().collect::<Vec<_>>(), vec![3]);
+        assert_eq!(entries.bytes, 30);
+    }
+
+    #[tokio::test]
+    async fn blocks_are_persisted_without_temporary_files() {
+        let dir = std::env::temp_dir().join(format!("block-cache-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let config = || BlockCacheConfig {
+            max_entries: 16,
+            max_bytes: 1 << 20,
+            disk_dir: Some(dir.clone()),
+            disk_max_bytes: 1 << 20,
+        };
+        BlockCache::new(config(), None)
+            .insert(7, serde_json::json!({ "slot": 7 }))
+            .await;
+
+        let files = std::fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect::<Vec<_>>();
+        let cached = BlockCache::new(config(), None).get(7).await;
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(files, vec![format!("7.v{}.json", SCHEMA_VERSION)]);
+        assert_eq!(cached, Some(block(7)));
+    }
+}
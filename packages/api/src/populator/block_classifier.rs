@@ -1,49 +1,115 @@
-use std::thread;
+use std::{sync::Arc, thread, time::Instant};
 
 use actions::ActionTree;
-use classifier_handler::classify_block;
+use classifier_core::AddressLookupTableSource;
+use classifier_handler::{classify_block, ClassifierRegistry};
 use inspection::{
     filtering::{post_process, PostProcessConfig},
     label_tree,
+    validators::ValidatorRegistry,
 };
 use solana_transaction_status::UiConfirmedBlock;
+use thiserror::Error;
 use tokio::sync::mpsc;
 
+use super::ClassifierMetrics;
+
 pub struct ClassifyBlockRequest {
     pub slot: u64,
     pub block: UiConfirmedBlock,
 }
 pub type ClassifyBlockRequestSender = crossbeam::channel::Sender<ClassifyBlockRequest>;
 
-pub type ClassifyResult = Option<ActionTree>;
+/// Reason a slot could not be classified
+#[derive(Debug, Clone, Error)]
+pub enum ClassifyFailure {
+    #[error("Slot was skipped")]
+    SkippedSlot,
+
+    #[error("Failed to fetch block: {0}")]
+    FetchFailed(String),
+
+    #[error("Failed to classify block: {0}")]
+    ClassificationFailed(String),
+
+    #[error("Timed out waiting for classification")]
+    TimedOut,
+
+    #[error("Classification was cancelled")]
+    Cancelled,
+
+    #[error("Failed to index block: {0}")]
+    IndexFailed(String),
+}
+
+impl ClassifyFailure {
+    /// Short identifier of the failure, for API responses
+    pub fn reason(&self) -> &'static str {
+        match self {
+            ClassifyFailure::SkippedSlot => "skippedSlot",
+            ClassifyFailure::FetchFailed(_) => "fetchFailed",
+            ClassifyFailure::ClassificationFailed(_) => "classificationFailed",
+            ClassifyFailure::TimedOut => "timedOut",
+            ClassifyFailure::Cancelled => "cancelled",
+            ClassifyFailure::IndexFailed(_) => "indexFailed",
+        }
+    }
+}
+
+pub type ClassifyResult = Result<ActionTree, ClassifyFailure>;
 pub type ClassifyBlockResponse = (u64, ClassifyResult);
 pub type ClassifyBlockResponseReceiver = mpsc::Receiver<ClassifyBlockResponse>;
 
+pub struct BlockClassifierConfig {
+    /// Number of threads classifying blocks
+    pub workers: usize,
+
+    /// Classify each block's transactions in parallel
+    pub parallel_transactions: bool,
+}
+
+/// BlockClassifier runs a pool of worker threads which share the classification request
+/// channel, so blocks are classified concurrently as they arrive from the BlockRequester.
 pub struct BlockClassifier {
-    thread_handle: Option<thread::JoinHandle<()>>,
+    thread_handles: Vec<thread::JoinHandle<()>>,
 }
 
 impl BlockClassifier {
     pub fn new(
         request_rx: crossbeam::channel::Receiver<ClassifyBlockRequest>,
         result_tx: mpsc::Sender<ClassifyBlockResponse>,
+        validator_registry: Arc<ValidatorRegistry>,
+        classifier_registry: Arc<ClassifierRegistry>,
+        lookup_tables: Arc<dyn AddressLookupTableSource + Send + Sync>,
+        metrics: Arc<ClassifierMetrics>,
+        config: BlockClassifierConfig,
     ) -> Self {
-        let mut thread = BlockClassifierThread {
-            request_rx,
-            result_tx,
-        };
-
-        let handle = thread::spawn(move || thread.thread_loop());
-
-        Self {
-            thread_handle: Some(handle),
-        }
+        let thread_handles = (0..config.workers.max(1))
+            .map(|worker| {
+                let mut thread = BlockClassifierThread {
+                    request_rx: request_rx.clone(),
+                    result_tx: result_tx.clone(),
+                    validator_registry: validator_registry.clone(),
+                    classifier_registry: classifier_registry.clone(),
+                    lookup_tables: lookup_tables.clone(),
+                    metrics: metrics.clone(),
+                    parallel_transactions: config.parallel_transactions,
+                };
+
+                thread::Builder::new()
+                    .name(format!("block-classifier-{}", worker))
+                    .spawn(move || thread.thread_loop())
+                    .expect("Failed to spawn block classifier thread")
+            })
+            .collect();
+
+        Self { thread_handles }
     }
 }
 
 impl Drop for BlockClassifier {
     fn drop(&mut self) {
-        if let Some(handle) = self.thread_handle.take() {
+        for handle in self.thread_handles.drain(..) {
             handle.join().unwrap();
         }
     }
@@ -52,18 +118,45 @@ impl Drop for BlockClassifier {
 struct BlockClassifierThread {
     request_rx: crossbeam::channel::Receiver<ClassifyBlockRequest>,
     result_tx: mpsc::Sender<ClassifyBlockResponse>,
+    validator_registry: Arc<ValidatorRegistry>,
+    classifier_registry: Arc<ClassifierRegistry>,
+
+    /// Resolves lookup tables of transactions whose meta lacks loaded addresses
+    lookup_tables: Arc<dyn AddressLookupTableSource + Send + Sync>,
+
+    metrics: Arc<ClassifierMetrics>,
+    parallel_transactions: bool,
 }
 
 impl BlockClassifierThread {
     pub(crate) fn thread_loop(&mut self) {
         while let Ok(request) = self.request_rx.recv() {
-            let classify_result = classify_block(request.slot, request.block, None);
+            self.metrics.classify_started();
+            let started = Instant::now();
+
+            let transactions = request
+                .block
+                .transactions
+                .as_ref()
+                .map(|transactions| transactions.len())
+                .unwrap_or_default();
+
+            let classify_result = classify_block(
+                request.slot,
+                request.block,
+                None,
+                Some(self.lookup_tables.as_ref()),
+                &self.classifier_registry,
+                self.parallel_transactions,
+            );
 
             // Handle classification error
             if let Err(err) = classify_result {
                 tracing::error!("Failed to classify block: {:?}", err);
+                self.metrics.classify_finished(started.elapsed(), None);
 
-                match self.result_tx.blocking_send((request.slot, None)) {
+                let failure = ClassifyFailure::ClassificationFailed(err.to_string());
+                match self.result_tx.blocking_send((request.slot, Err(failure))) {
                     Ok(_) => {}
                     Err(_) => {
                         tracing::error!("Failed to send classify result");
@@ -75,6 +168,7 @@ impl BlockClassifierThread {
 
             // Post process the result
             let mut tree = classify_result.unwrap();
+            self.validator_registry.enrich_block(&mut tree);
             label_tree(&mut tree);
 
             post_process(
@@ -86,8 +180,11 @@ impl BlockClassifierThread {
                 &mut tree,
             );
 
+            self.metrics
+                .classify_finished(started.elapsed(), Some(transactions));
+
             // Send docs
-            match self.result_tx.blocking_send((request.slot, Some(tree))) {
+            match self.result_tx.blocking_send((request.slot, Ok(tree))) {
                 Ok(_) => {}
                 Err(_) => {
                     tracing::error!("Failed to send classify result");
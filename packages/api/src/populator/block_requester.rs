@@ -1,22 +1,37 @@
 use std::{
-    collections::{HashMap, VecDeque},
+    collections::{BTreeSet, HashMap, VecDeque},
     sync::Arc,
     time::Duration,
 };
 
+use actions::serialize_block;
 use crossbeam::channel;
+use inspection::{
+    database::{
+        block_store::BlockStore,
+        document_builder::{build_block_documents, BlockDocuments},
+    },
+    rpc::is_skipped_slot,
+};
 use solana_client::{nonblocking::rpc_client::RpcClient, rpc_config::RpcBlockConfig};
+use solana_sdk::commitment_config::CommitmentConfig;
 use solana_transaction_status::{TransactionDetails, UiTransactionEncoding};
 use thiserror::Error;
-use tokio::sync::{mpsc, oneshot, Semaphore, TryAcquireError};
+use tokio::sync::{mpsc, oneshot, watch, Semaphore, TryAcquireError};
 
-use crate::populator::ClassifyBlockRequest;
+use crate::{
+    ingestion::{BlockSource, SourcedBlock, SourcedBlockReceiver},
+    populator::ClassifyBlockRequest,
+};
 
 use super::{
-    ClassifyBlockRequestSender, ClassifyBlockResponse, ClassifyBlockResponseReceiver,
-    ClassifyResult,
+    BlockStream, ClassifierMetrics, ClassifyBlockRequestSender, ClassifyBlockResponse,
+    ClassifyBlockResponseReceiver, ClassifyFailure, ClassifyResult, StreamedBlock,
 };
 
+/// Backfill blocks written to the store in one session
+const BACKFILL_WRITE_BATCH: usize = 16;
+
 pub type FetchBlockSender = mpsc::Sender<Vec<FetchBlockRequest>>;
 pub type FetchBlockReceiver = mpsc::Receiver<Vec<FetchBlockRequest>>;
 
@@ -36,9 +51,133 @@ pub enum BlockRequesterError {
 
 type Result<T> = std::result::Result<T, BlockRequesterError>;
 
+impl From<BlockRequesterError> for ClassifyFailure {
+    fn from(err: BlockRequesterError) -> Self {
+        match err {
+            BlockRequesterError::RpcFailure(err) if is_skipped_slot(&err) => {
+                ClassifyFailure::SkippedSlot
+            }
+            BlockRequesterError::RpcFailure(err) => ClassifyFailure::FetchFailed(err.to_string()),
+            BlockRequesterError::ClassifierDisconnected(_) => ClassifyFailure::Cancelled,
+        }
+    }
+}
+
 pub struct BlockRequesterConfig {
     pub requests_per_period: usize,
     pub period: Duration,
+
+    /// Blocks are only fetched while fewer than this many are being fetched or waiting
+    /// for a classifier, so saturated classifiers throttle RPC requests.
+    pub max_pending_blocks: usize,
+
+    /// Index blocks as they are confirmed
+    pub live: Option<LiveIndexingConfig>,
+
+    /// Index a historical range of blocks
+    pub backfill: Option<BackfillConfig>,
+
+    /// Times a block is refetched after a transient RPC failure
+    pub max_retries: u32,
+
+    /// Delay before the first retry, doubled on each subsequent retry
+    pub retry_backoff: Duration,
+}
+
+pub struct LiveIndexingConfig {
+    /// How often to poll for the latest confirmed slot
+    pub poll_interval: Duration,
+
+    /// Source streaming complete blocks. When set, live blocks are taken from it instead
+    /// of polling RPC for new slots and fetching them.
+    pub source: Option<Arc<dyn BlockSource>>,
+}
+
+pub struct BackfillConfig {
+    pub start_slot: u64,
+
+    /// Last slot to backfill, inclusive
+    pub end_slot: u64,
+}
+
+impl BackfillConfig {
+    /// Name of the checkpoint recording the progress of this backfill, so that a
+    /// restarted backfill of the same range resumes where it stopped.
+    pub fn checkpoint_name(&self) -> String {
+        format!("backfill-{}-{}", self.start_slot, self.end_slot)
+    }
+}
+
+/// Lanes which index blocks into the database
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum IndexingLane {
+    Live,
+    Backfill,
+}
+
+#[derive(Default)]
+struct SlotRequest {
+    /// Users waiting on the classification of this slot
+    subscribers: Vec<oneshot::Sender<ClassifyResult>>,
+
+    /// Lane which writes this slot to the database, if any
+    lane: Option<IndexingLane>,
+
+    /// Whether the block has been requested from RPC, or is waiting to be retried
+    dispatched: bool,
+
+    /// Failed attempts to fetch the block
+    failed_attempts: u32,
+}
+
+struct LiveLane {
+    poll_interval: Duration,
+    last_slot: Option<u64>,
+    queue: VecDeque<u64>,
+
+    /// Blocks received from the block source, waiting for a classifier worker
+    sourced: VecDeque<SourcedBlock>,
+}
+
+struct BackfillLane {
+    checkpoint_name: String,
+    next_slot: u64,
+    end_slot: u64,
+
+    /// Backfill slots which have been dispatched but not yet written. Slots which fail
+    /// to be indexed stay here, so the checkpoint never passes them.
+    outstanding: BTreeSet<u64>,
+    checkpoint: u64,
+
+    /// Checkpoints to persist, written in order by a single task
+    checkpoint_tx: watch::Sender<u64>,
+
+    /// Documents of classified blocks, written in batches by a single task
+    write_tx: mpsc::UnboundedSender<(u64, BlockDocuments)>,
+}
+
+impl BackfillLane {
+    fn is_exhausted(&self) -> bool {
+        self.next_slot > self.end_slot
+    }
+
+    /// Every slot before the checkpoint has been written
+    fn current_checkpoint(&self) -> u64 {
+        self.outstanding.first().copied().unwrap_or(self.next_slot)
+    }
+
+    /// Records a slot as written, returning the checkpoint if it advanced
+    fn mark_indexed(&mut self, slot: u64) -> Option<u64> {
+        self.outstanding.remove(&slot);
+
+        let checkpoint = self.current_checkpoint();
+        if checkpoint <= self.checkpoint {
+            return None;
+        }
+        self.checkpoint = checkpoint;
+
+        Some(checkpoint)
+    }
 }
 
 /// BlockRequester handles coordinating requests for block data from the RPC client
@@ -46,8 +185,9 @@ pub struct BlockRequesterConfig {
 /// at all times, secondly prioritizing live block data indexing and lastly backfilling.
 ///
 /// This thread is the entrypoint to the overall classification flow. It fetches blocks,
-/// then forwards them to the classifier thread. Once the ActionTree has been created and labeled,
-/// it will be sent to the onshot channel provided by the initial FetchBlockRequest.
+/// then forwards them to the classifier workers. Once the ActionTree has been created and labeled,
+/// it will be sent to the onshot channel provided by the initial FetchBlockRequest. Blocks
+/// requested by the live and backfill lanes are written to the database.
 pub struct BlockRequester {
     thread_handle: Option<tokio::task::JoinHandle<()>>,
 }
@@ -61,23 +201,90 @@ impl Drop for BlockRequester {
 }
 
 impl BlockRequester {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         user_request_rx: FetchBlockReceiver,
         classifier_tx: ClassifyBlockRequestSender,
         classify_result_rx: ClassifyBlockResponseReceiver,
         rpc_client: Arc<RpcClient>,
+        store: Arc<dyn BlockStore>,
+        metrics: Arc<ClassifierMetrics>,
+        block_stream: BlockStream,
         config: BlockRequesterConfig,
     ) -> Self {
+        let (latest_slot_tx, latest_slot_rx) = mpsc::channel(16);
+        let (indexed_tx, indexed_rx) = mpsc::unbounded_channel();
+        let (failure_tx, failure_rx) = mpsc::unbounded_channel();
+        let (retry_tx, retry_rx) = mpsc::unbounded_channel();
+
+        let source_rx = config
+            .live
+            .as_ref()
+            .and_then(|live| live.source.as_ref())
+            .map(|source| source.subscribe());
+
+        let live = config.live.map(|live| LiveLane {
+            poll_interval: live.poll_interval,
+            last_slot: None,
+            queue: VecDeque::new(),
+            sourced: VecDeque::new(),
+        });
+
+        let backfill = config.backfill.map(|backfill| {
+            let (checkpoint_tx, checkpoint_rx) = watch::channel(backfill.start_slot);
+            tokio::spawn(BlockRequesterThread::checkpoint_task(
+                store.clone(),
+                backfill.checkpoint_name(),
+                checkpoint_rx,
+            ));
+
+            let (write_tx, write_rx) = mpsc::unbounded_channel();
+            tokio::spawn(BlockRequesterThread::backfill_write_task(
+                store.clone(),
+                write_rx,
+                indexed_tx.clone(),
+                failure_tx.clone(),
+            ));
+
+            BackfillLane {
+                checkpoint_name: backfill.checkpoint_name(),
+                next_slot: backfill.start_slot,
+                end_slot: backfill.end_slot,
+                outstanding: BTreeSet::new(),
+                checkpoint: backfill.start_slot,
+                checkpoint_tx,
+                write_tx,
+            }
+        });
+
         let mut thread = BlockRequesterThread {
             requests_per_period: config.requests_per_period,
             period: config.period,
+            max_pending_blocks: config.max_pending_blocks.max(1),
+            max_retries: config.max_retries,
+            retry_backoff: config.retry_backoff,
             user_request_rx,
             classifier_tx,
             classify_result_rx,
+            latest_slot_tx,
+            latest_slot_rx,
+            source_rx,
+            indexed_tx,
+            indexed_rx,
+            failure_tx,
+            failure_rx,
+            retry_tx,
+            retry_rx,
             rpc_client,
+            store,
+            metrics,
+            block_stream,
             semaphore: Semaphore::new(config.requests_per_period),
             in_progress: HashMap::new(),
             pending_queue: VecDeque::new(),
+            retry_queue: VecDeque::new(),
+            live,
+            backfill,
         };
 
         let handle = tokio::spawn(async move { thread.thread_loop().await });
@@ -91,29 +298,75 @@ impl BlockRequester {
 pub(crate) struct BlockRequesterThread {
     pub requests_per_period: usize,
     pub period: Duration,
+    pub max_pending_blocks: usize,
+    pub max_retries: u32,
+    pub retry_backoff: Duration,
 
     user_request_rx: FetchBlockReceiver,
     classifier_tx: ClassifyBlockRequestSender,
     classify_result_rx: ClassifyBlockResponseReceiver,
+    latest_slot_tx: mpsc::Sender<u64>,
+    latest_slot_rx: mpsc::Receiver<u64>,
+    source_rx: Option<SourcedBlockReceiver>,
+    indexed_tx: mpsc::UnboundedSender<(u64, IndexingLane)>,
+    indexed_rx: mpsc::UnboundedReceiver<(u64, IndexingLane)>,
+    failure_tx: mpsc::UnboundedSender<(u64, ClassifyFailure)>,
+    failure_rx: mpsc::UnboundedReceiver<(u64, ClassifyFailure)>,
+    retry_tx: mpsc::UnboundedSender<u64>,
+    retry_rx: mpsc::UnboundedReceiver<u64>,
 
     rpc_client: Arc<RpcClient>,
+    store: Arc<dyn BlockStore>,
+    metrics: Arc<ClassifierMetrics>,
+    block_stream: BlockStream,
     semaphore: Semaphore,
-    in_progress: HashMap<u64, Vec<oneshot::Sender<ClassifyResult>>>,
+    in_progress: HashMap<u64, SlotRequest>,
     pending_queue: VecDeque<u64>,
+    retry_queue: VecDeque<u64>,
+
+    live: Option<LiveLane>,
+    backfill: Option<BackfillLane>,
 }
 
 impl BlockRequesterThread {
     pub(crate) async fn thread_loop(&mut self) {
+        self.resume_backfill().await;
+
         let mut ticker = tokio::time::interval(self.period);
+        let mut live_ticker = tokio::time::interval(
+            self.live
+                .as_ref()
+                .map(|live| live.poll_interval)
+                .unwrap_or(self.period),
+        );
 
         loop {
             tokio::select! {
                 // Control rate limit for RPC requests
                 _ = ticker.tick() => {
                     self.semaphore.add_permits(self.requests_per_period);
+                    self.dispatch_pending();
+                }
+
+                // Poll for newly confirmed slots, unless blocks are streamed from a source
+                _ = live_ticker.tick(), if self.live.is_some() && self.source_rx.is_none() => {
+                    self.poll_latest_slot();
+                }
 
-                    while self.semaphore.available_permits() > 0 && !self.pending_queue.is_empty() {
-                        self.try_request_block();
+                // Receive streamed blocks, stopping while the classifiers are backed up
+                sourced = Self::recv_sourced(&mut self.source_rx), if self.accepts_sourced() => {
+                    match sourced {
+                        Some(sourced) => self.handle_sourced(sourced),
+                        None => {
+                            tracing::error!("Block source closed, polling RPC for live slots");
+                            self.source_rx = None;
+                        }
+                    }
+                }
+
+                latest_slot = self.latest_slot_rx.recv() => {
+                    if let Some(latest_slot) = latest_slot {
+                        self.handle_latest_slot(latest_slot);
                     }
                 }
 
@@ -136,40 +389,81 @@ impl BlockRequesterThread {
 
                     self.handle_result(classify_result);
                 }
+
+                // Retry or report blocks which could not be fetched
+                failure = self.failure_rx.recv() => {
+                    if let Some((slot, failure)) = failure {
+                        self.handle_failure(slot, failure);
+                    }
+                }
+
+                // Requeue blocks once their backoff has elapsed
+                retry = self.retry_rx.recv() => {
+                    if let Some(slot) = retry {
+                        self.handle_retry(slot);
+                    }
+                }
+
+                // Record progress of indexed blocks
+                indexed = self.indexed_rx.recv() => {
+                    if let Some((slot, lane)) = indexed {
+                        self.handle_indexed(slot, lane);
+                    }
+                }
             }
         }
 
         tracing::debug!("BlockRequesterThread exiting");
     }
 
+    async fn resume_backfill(&mut self) {
+        let backfill = match &mut self.backfill {
+            Some(backfill) => backfill,
+            None => return,
+        };
+
+        match self.store.read_checkpoint(&backfill.checkpoint_name).await {
+            Ok(Some(checkpoint)) => {
+                tracing::info!(
+                    "Resuming backfill {} from slot {}",
+                    backfill.checkpoint_name,
+                    checkpoint
+                );
+                backfill.next_slot = backfill.next_slot.max(checkpoint);
+                backfill.checkpoint = backfill.next_slot;
+            }
+            Ok(None) => {
+                tracing::info!("Starting backfill {}", backfill.checkpoint_name);
+            }
+            Err(err) => {
+                tracing::error!("Failed to read backfill checkpoint: {:?}", err);
+            }
+        }
+    }
+
     fn handle_requests(&mut self, requests: Vec<FetchBlockRequest>) {
         tracing::trace!("Received {} FetchBlockRequests", requests.len());
 
         // Add slots to in progress set
         for request in requests {
-            if self.in_progress.contains_key(&request.slot) {
-                continue;
-            }
+            let slot_request = self.in_progress.entry(request.slot).or_default();
+            slot_request.subscribers.push(request.response);
 
-            self.pending_queue.push_back(request.slot);
-            self.in_progress
-                .entry(request.slot)
-                .or_default()
-                .push(request.response);
+            // Slots already queued by another lane are moved ahead of it
+            if !slot_request.dispatched {
+                self.pending_queue.push_back(request.slot);
+            }
         }
 
-        // Try to request blocks until rate limit is reached
-        while self.semaphore.available_permits() > 0 && !self.pending_queue.is_empty() {
-            self.try_request_block();
-        }
+        self.dispatch_pending();
     }
 
     fn handle_result(&mut self, result: ClassifyBlockResponse) {
         let (slot, result) = result;
         tracing::trace!("Classified block for slot {}", slot);
 
-        let responses = self.in_progress.remove(&slot).unwrap_or_default();
-        for response in responses {
+        let slot_request = self.in_progress.remove(&slot).unwrap_or_default();
+        for response in slot_request.subscribers {
             match response.send(result.clone()) {
                 Ok(_) => {}
                 Err(_) => {
@@ -177,56 +471,717 @@ impl BlockRequesterThread {
                 }
             }
         }
+
+        if let Some(lane) = slot_request.lane {
+            tokio::task::spawn(Self::write_task(
+                self.store.clone(),
+                self.block_stream.clone(),
+                slot,
+                lane,
+                result,
+                self.backfill
+                    .as_ref()
+                    .map(|backfill| backfill.write_tx.clone()),
+                self.indexed_tx.clone(),
+                self.failure_tx.clone(),
+            ));
+        }
+
+        // A classifier worker is free again
+        self.dispatch_pending();
+    }
+
+    fn handle_failure(&mut self, slot: u64, failure: ClassifyFailure) {
+        if let ClassifyFailure::IndexFailed(_) = &failure {
+            self.handle_index_failure(slot, failure);
+            return;
+        }
+
+        let slot_request = match self.in_progress.get_mut(&slot) {
+            Some(slot_request) => slot_request,
+            None => return,
+        };
+
+        // Only transient RPC failures are retried, skipped slots will never have a block
+        if let ClassifyFailure::FetchFailed(_) = &failure {
+            if slot_request.failed_attempts < self.max_retries {
+                let backoff = self
+                    .retry_backoff
+                    .saturating_mul(2u32.saturating_pow(slot_request.failed_attempts));
+                slot_request.failed_attempts += 1;
+
+                tracing::warn!("{} for slot {}, retrying in {:?}", failure, slot, backoff);
+
+                let retry_tx = self.retry_tx.clone();
+                tokio::task::spawn(async move {
+                    tokio::time::sleep(backoff).await;
+                    let _ = retry_tx.send(slot);
+                });
+
+                return;
+            }
+        }
+
+        match &failure {
+            ClassifyFailure::SkippedSlot => tracing::debug!("Slot {} was skipped", slot),
+            failure => tracing::error!("{} for slot {}", failure, slot),
+        }
+
+        self.handle_result((slot, Err(failure)));
+    }
+
+    /// Blocks which failed to be indexed are not retried. Backfill slots stay outstanding,
+    /// so the checkpoint stays behind them and a restarted backfill indexes them again.
+    fn handle_index_failure(&mut self, slot: u64, failure: ClassifyFailure) {
+        tracing::error!("{} for slot {}", failure, slot);
+
+        if let Some(backfill) = &self.backfill {
+            if backfill.outstanding.contains(&slot) {
+                tracing::warn!(
+                    "Backfill {} checkpoint held before slot {}",
+                    backfill.checkpoint_name,
+                    slot
+                );
+            }
+        }
+    }
+
+    fn handle_retry(&mut self, slot: u64) {
+        if let Some(slot_request) = self.in_progress.get_mut(&slot) {
+            slot_request.dispatched = false;
+            self.retry_queue.push_back(slot);
+        }
+
+        self.dispatch_pending();
+    }
+
+    fn poll_latest_slot(&mut self) {
+        // Slot polling counts against the RPC rate limit
+        match self.semaphore.try_acquire() {
+            Ok(permit) => permit.forget(),
+            Err(_) => return,
+        }
+
+        let rpc_client = self.rpc_client.clone();
+        let latest_slot_tx = self.latest_slot_tx.clone();
+
+        tokio::task::spawn(async move {
+            match rpc_client
+                .get_slot_with_commitment(CommitmentConfig::confirmed())
+                .await
+            {
+                Ok(slot) => {
+                    let _ = latest_slot_tx.send(slot).await;
+                }
+                Err(err) => {
+                    tracing::error!("Failed to get latest slot: {:?}", err);
+                }
+            }
+        });
+    }
+
+    fn handle_latest_slot(&mut self, latest_slot: u64) {
+        let live = match &mut self.live {
+            Some(live) => live,
+            None => return,
+        };
+
+        // Live indexing starts at the first slot observed
+        let first_slot = match live.last_slot {
+            Some(last_slot) if last_slot >= latest_slot => return,
+            Some(last_slot) => last_slot + 1,
+            None => latest_slot,
+        };
+
+        tracing::trace!("Live indexing slots {} to {}", first_slot, latest_slot);
+
+        live.queue.extend(first_slot..=latest_slot);
+        live.last_slot = Some(latest_slot);
+
+        self.dispatch_pending();
+    }
+
+    async fn recv_sourced(source_rx: &mut Option<SourcedBlockReceiver>) -> Option<SourcedBlock> {
+        match source_rx {
+            Some(source_rx) => source_rx.recv().await,
+            None => std::future::pending().await,
+        }
+    }
+
+    fn accepts_sourced(&self) -> bool {
+        self.live
+            .as_ref()
+            .is_some_and(|live| live.sourced.len() < self.max_pending_blocks)
     }
 
-    fn try_request_block(&mut self) {
+    fn handle_sourced(&mut self, sourced: SourcedBlock) {
+        let live = match &mut self.live {
+            Some(live) => live,
+            None => return,
+        };
+
+        let slot = sourced.slot;
+
+        // Slots the block source missed, such as while it reconnected or failed to
+        // convert a block, are fetched over RPC instead
+        if let Some(last_slot) = live.last_slot {
+            if slot > last_slot + 1 {
+                tracing::warn!(
+                    "Block source skipped slots {}..{}, requesting them from RPC",
+                    last_slot + 1,
+                    slot
+                );
+                live.queue.extend(last_slot + 1..slot);
+            }
+        }
+        live.last_slot = Some(live.last_slot.map_or(slot, |last_slot| last_slot.max(slot)));
+
+        let slot_request = self.in_progress.entry(slot).or_default();
+        slot_request.lane.get_or_insert(IndexingLane::Live);
+
+        // Already fetched from RPC for another request
+        if slot_request.dispatched {
+            return;
+        }
+        slot_request.dispatched = true;
+
+        tracing::trace!("Received block for slot {} from block source", slot);
+        live.sourced.push_back(sourced);
+
+        self.dispatch_pending();
+    }
+
+    fn handle_indexed(&mut self, slot: u64, lane: IndexingLane) {
+        if lane != IndexingLane::Backfill {
+            return;
+        }
+
+        let backfill = match &mut self.backfill {
+            Some(backfill) => backfill,
+            None => return,
+        };
+
+        let checkpoint = match backfill.mark_indexed(slot) {
+            Some(checkpoint) => checkpoint,
+            None => return,
+        };
+
+        if backfill.is_exhausted() && backfill.outstanding.is_empty() {
+            tracing::info!("Finished backfill {}", backfill.checkpoint_name);
+        }
+
+        let _ = backfill.checkpoint_tx.send(checkpoint);
+    }
+
+    /// Writes backfill checkpoints as they advance. A single task writes them, so they
+    /// land in order, and checkpoints which advance during a write are coalesced.
+    async fn checkpoint_task(
+        store: Arc<dyn BlockStore>,
+        checkpoint_name: String,
+        mut checkpoint_rx: watch::Receiver<u64>,
+    ) {
+        while checkpoint_rx.changed().await.is_ok() {
+            let checkpoint = *checkpoint_rx.borrow_and_update();
+
+            if let Err(err) = store.write_checkpoint(&checkpoint_name, checkpoint).await {
+                tracing::error!("Failed to write backfill checkpoint: {:?}", err);
+            }
+        }
+    }
+
+    /// Writes backfill blocks in batches sharing one store session, then reports their
+    /// slots as indexed. Every slot of a batch which fails to write is reported as
+    /// failed, so the checkpoint stays behind them.
+    async fn backfill_write_task(
+        store: Arc<dyn BlockStore>,
+        mut write_rx: mpsc::UnboundedReceiver<(u64, BlockDocuments)>,
+        indexed_tx: mpsc::UnboundedSender<(u64, IndexingLane)>,
+        failure_tx: mpsc::UnboundedSender<(u64, ClassifyFailure)>,
+    ) {
+        let mut batch = Vec::with_capacity(BACKFILL_WRITE_BATCH);
+
+        while write_rx.recv_many(&mut batch, BACKFILL_WRITE_BATCH).await > 0 {
+            let (slots, blocks): (Vec<u64>, Vec<BlockDocuments>) = batch.drain(..).unzip();
+
+            match store.write_many_block_documents(blocks).await {
+                Ok(()) => {
+                    for slot in slots {
+                        let _ = indexed_tx.send((slot, IndexingLane::Backfill));
+                    }
+                }
+                Err(err) => {
+                    let failure = ClassifyFailure::IndexFailed(format!(
+                        "Failed to write block batch: {} ({:?} lane)",
+                        err,
+                        IndexingLane::Backfill
+                    ));
+                    for slot in slots {
+                        let _ = failure_tx.send((slot, failure.clone()));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Request blocks until the rate limit is reached or the classifiers are saturated,
+    /// then hand streamed blocks to the classifiers, which needs no RPC requests
+    fn dispatch_pending(&mut self) {
+        while self.semaphore.available_permits() > 0
+            && self.metrics.pending_blocks() < self.max_pending_blocks
+        {
+            let slot = match self.next_slot() {
+                Some(slot) => slot,
+                None => break,
+            };
+
+            self.try_request_block(slot);
+        }
+
+        while self.metrics.pending_blocks() < self.max_pending_blocks {
+            let sourced = match self.live.as_mut().and_then(|live| live.sourced.pop_front()) {
+                Some(sourced) => sourced,
+                None => break,
+            };
+
+            self.metrics.fetch_started();
+            self.metrics.fetch_finished(true);
+
+            tokio::task::spawn(Self::classify_task(
+                sourced,
+                self.classifier_tx.clone(),
+                self.metrics.clone(),
+                self.failure_tx.clone(),
+            ));
+        }
+    }
+
+    /// Next slot to request: user requests first, then retries, then the live tail, then
+    /// the backfill
+    fn next_slot(&mut self) -> Option<u64> {
+        for queue in [&mut self.pending_queue, &mut self.retry_queue] {
+            while let Some(slot) = queue.pop_front() {
+                if self.in_progress.get(&slot).is_some_and(|r| !r.dispatched) {
+                    return Some(slot);
+                }
+            }
+        }
+
+        if let Some(live) = &mut self.live {
+            while let Some(slot) = live.queue.pop_front() {
+                let slot_request = self.in_progress.entry(slot).or_default();
+                slot_request.lane.get_or_insert(IndexingLane::Live);
+
+                if !slot_request.dispatched {
+                    return Some(slot);
+                }
+            }
+        }
+
+        if let Some(backfill) = &mut self.backfill {
+            while !backfill.is_exhausted() {
+                let slot = backfill.next_slot;
+                backfill.next_slot += 1;
+                backfill.outstanding.insert(slot);
+
+                let slot_request = self.in_progress.entry(slot).or_default();
+                if slot_request.lane.is_none() {
+                    slot_request.lane = Some(IndexingLane::Backfill);
+                } else {
+                    // Already indexed by the live lane
+                    backfill.outstanding.remove(&slot);
+                }
+
+                if !slot_request.dispatched {
+                    return Some(slot);
+                }
+            }
+        }
+
+        None
+    }
+
+    fn try_request_block(&mut self, slot: u64) {
         let permit = match self.semaphore.try_acquire() {
             Ok(permit) => permit,
             Err(TryAcquireError::Closed) => unreachable!(),
             Err(TryAcquireError::NoPermits) => return,
         };
 
-        // Safety check, but shouldn't be possible in thread_loop
-        if self.pending_queue.is_empty() {
-            return;
-        }
+        self.in_progress.entry(slot).or_default().dispatched = true;
 
-        let slot = self.pending_queue.pop_front().unwrap();
         let rpc_client = self.rpc_client.clone();
+        self.metrics.fetch_started();
 
-        // TODO: If this task fails, retry/requeue on RPC failure
-        // and break on channel error
         tokio::task::spawn(Self::request_task(
             rpc_client,
             slot,
             self.classifier_tx.clone(),
+            self.metrics.clone(),
+            self.failure_tx.clone(),
         ));
 
         permit.forget();
     }
 
+    /// Fetches a block and forwards it to the classifiers, reporting failures back to the
+    /// requester
     async fn request_task(
         rpc_client: Arc<RpcClient>,
         slot: u64,
         classifier_tx: ClassifyBlockRequestSender,
+        metrics: Arc<ClassifierMetrics>,
+        failure_tx: mpsc::UnboundedSender<(u64, ClassifyFailure)>,
+    ) {
+        if let Err(err) = Self::fetch_block(rpc_client, slot, classifier_tx, metrics).await {
+            let _ = failure_tx.send((slot, err.into()));
+        }
+    }
+
+    async fn fetch_block(
+        rpc_client: Arc<RpcClient>,
+        slot: u64,
+        classifier_tx: ClassifyBlockRequestSender,
+        metrics: Arc<ClassifierMetrics>,
     ) -> Result<()> {
-        let block = rpc_client
+        let block = match rpc_client
             .get_block_with_config(
                 slot,
                 RpcBlockConfig {
                     max_supported_transaction_version: Some(0),
                     encoding: Some(UiTransactionEncoding::Base64),
                     transaction_details: Some(TransactionDetails::Full),
+                    commitment: Some(CommitmentConfig::confirmed()),
                     ..Default::default()
                 },
             )
-            .await?;
+            .await
+        {
+            Ok(block) => block,
+            Err(err) => {
+                metrics.fetch_finished(false);
+                return Err(err.into());
+            }
+        };
 
         tracing::trace!("Received block for slot {}", slot);
+        metrics.fetch_finished(true);
 
+        // The classifier channel is bounded, so this blocks while every worker is busy.
+        // The block is counted as queued before it is sent, as a worker may take it
+        // immediately.
         let populate_request = ClassifyBlockRequest { slot, block };
-        classifier_tx.send(populate_request)?;
+        if let Err(err) = tokio::task::block_in_place(|| classifier_tx.send(populate_request)) {
+            metrics.queue_failed();
+            return Err(err.into());
+        }
 
         Ok(())
     }
+
+    /// Forwards a streamed block to the classifiers
+    async fn classify_task(
+        sourced: SourcedBlock,
+        classifier_tx: ClassifyBlockRequestSender,
+        metrics: Arc<ClassifierMetrics>,
+        failure_tx: mpsc::UnboundedSender<(u64, ClassifyFailure)>,
+    ) {
+        let slot = sourced.slot;
+        let classify_request = ClassifyBlockRequest {
+            slot,
+            block: sourced.block,
+        };
+
+        if let Err(err) = tokio::task::block_in_place(|| classifier_tx.send(classify_request)) {
+            metrics.queue_failed();
+            let _ = failure_tx.send((slot, BlockRequesterError::from(err).into()));
+        }
+    }
+
+    /// Writes a classified block to the database, then reports the slot as indexed.
+    /// Blocks which failed to classify or write are reported as failures instead, and not
+    /// retried. Live blocks are also pushed to stream subscribers, while backfill blocks
+    /// are handed to the backfill writer.
+    #[allow(clippy::too_many_arguments)]
+    async fn write_task(
+        store: Arc<dyn BlockStore>,
+        block_stream: BlockStream,
+        slot: u64,
+        lane: IndexingLane,
+        tree: ClassifyResult,
+        backfill_write_tx: Option<mpsc::UnboundedSender<(u64, BlockDocuments)>>,
+        indexed_tx: mpsc::UnboundedSender<(u64, IndexingLane)>,
+        failure_tx: mpsc::UnboundedSender<(u64, ClassifyFailure)>,
+    ) {
+        let indexed = match tree {
+            Ok(tree) => {
+                let block_json = serialize_block(&tree, tree.root());
+
+                if lane == IndexingLane::Live && block_stream.has_subscribers() {
+                    block_stream.publish(StreamedBlock::new(slot, &tree, block_json.clone()));
+                }
+
+                // Read through by the API's block cache
+                if let Err(err) = store.write_block_json(slot, &block_json.to_string()).await {
+                    tracing::error!("Failed to write serialized block {}: {:?}", slot, err);
+                }
+
+                match build_block_documents(&tree, tree.root()) {
+                    // The backfill writer reports the slot once its batch is written
+                    Ok(block_documents) if lane == IndexingLane::Backfill => {
+                        match backfill_write_tx.map(|tx| tx.send((slot, block_documents))) {
+                            Some(Ok(())) => return,
+                            _ => Err("Backfill writer stopped".to_string()),
+                        }
+                    }
+                    Ok(block_documents) => store
+                        .write_block_documents(block_documents)
+                        .await
+                        .map_err(|err| format!("Failed to write block: {}", err)),
+                    Err(err) => Err(format!("Failed to build documents: {:?}", err)),
+                }
+            }
+            // Nothing to index
+            Err(ClassifyFailure::SkippedSlot) => Ok(()),
+            Err(failure) => Err(failure.to_string()),
+        };
+
+        match indexed {
+            Ok(()) => {
+                let _ = indexed_tx.send((slot, lane));
+            }
+            Err(err) => {
+                let failure = ClassifyFailure::IndexFailed(format!("{} ({:?} lane)", err, lane));
+                let _ = failure_tx.send((slot, failure));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use inspection::database::{
+        block_store::{open_block_store, BlockStoreConfig},
+        mongo_client::MongoDBStage,
+    };
+    use yellowstone_grpc_proto::geyser::{
+        subscribe_update::UpdateOneof, SubscribeUpdate, SubscribeUpdateBlock,
+    };
+
+    use super::*;
+    use crate::ingestion::{GeyserBlockSource, GeyserBlockSourceConfig, MockGeyserServer};
+
+    fn backfill_lane(start_slot: u64, end_slot: u64) -> (BackfillLane, watch::Receiver<u64>) {
+        let (checkpoint_tx, checkpoint_rx) = watch::channel(start_slot);
+        let (write_tx, _) = mpsc::unbounded_channel();
+
+        let lane = BackfillLane {
+            checkpoint_name: "backfill-test".to_string(),
+            next_slot: start_slot,
+            end_slot,
+            outstanding: BTreeSet::new(),
+            checkpoint: start_slot,
+            checkpoint_tx,
+            write_tx,
+        };
+
+        (lane, checkpoint_rx)
+    }
+
+    fn dispatch(lane: &mut BackfillLane, slots: u64) {
+        for _ in 0..slots {
+            lane.outstanding.insert(lane.next_slot);
+            lane.next_slot += 1;
+        }
+    }
+
+    #[test]
+    fn checkpoint_waits_for_earlier_slots() {
+        let (mut lane, _) = backfill_lane(10, 20);
+        dispatch(&mut lane, 3);
+
+        assert_eq!(lane.mark_indexed(11), None);
+        assert_eq!(lane.mark_indexed(12), None);
+        assert_eq!(lane.mark_indexed(10), Some(13));
+        assert_eq!(lane.checkpoint, 13);
+    }
+
+    #[test]
+    fn checkpoint_stays_behind_unindexed_slots() {
+        let (mut lane, _) = backfill_lane(10, 20);
+        dispatch(&mut lane, 4);
+
+        // Slot 11 failed, so it is never marked indexed
+        assert_eq!(lane.mark_indexed(10), Some(11));
+        assert_eq!(lane.mark_indexed(12), None);
+        assert_eq!(lane.mark_indexed(13), None);
+        assert_eq!(lane.current_checkpoint(), 11);
+    }
+
+    #[test]
+    fn checkpoint_never_moves_backwards() {
+        let (mut lane, _) = backfill_lane(10, 20);
+        dispatch(&mut lane, 2);
+
+        assert_eq!(lane.mark_indexed(10), Some(11));
+        assert_eq!(lane.mark_indexed(11), Some(12));
+
+        // Reported twice, such as by the live and backfill lanes
+        assert_eq!(lane.mark_indexed(10), None);
+        assert_eq!(lane.checkpoint, 12);
+    }
+
+    fn live_thread(
+        classifier_tx: ClassifyBlockRequestSender,
+        store: Arc<dyn BlockStore>,
+    ) -> BlockRequesterThread {
+        let (_, user_request_rx) = mpsc::channel(1);
+        let (_, classify_result_rx) = mpsc::channel(1);
+        let (latest_slot_tx, latest_slot_rx) = mpsc::channel(1);
+        let (indexed_tx, indexed_rx) = mpsc::unbounded_channel();
+        let (failure_tx, failure_rx) = mpsc::unbounded_channel();
+        let (retry_tx, retry_rx) = mpsc::unbounded_channel();
+
+        BlockRequesterThread {
+            requests_per_period: 0,
+            period: Duration::from_secs(1),
+            max_pending_blocks: 16,
+            max_retries: 0,
+            retry_backoff: Duration::from_secs(1),
+            user_request_rx,
+            classifier_tx,
+            classify_result_rx,
+            latest_slot_tx,
+            latest_slot_rx,
+            source_rx: None,
+            indexed_tx,
+            indexed_rx,
+            failure_tx,
+            failure_rx,
+            retry_tx,
+            retry_rx,
+            rpc_client: Arc::new(RpcClient::new("http://127.0.0.1:1".to_string())),
+            store,
+            metrics: Arc::new(ClassifierMetrics::new(1)),
+            block_stream: BlockStream::new(1),
+            // No RPC requests are made, so slots queued for RPC stay queued
+            semaphore: Semaphore::new(0),
+            in_progress: HashMap::new(),
+            pending_queue: VecDeque::new(),
+            retry_queue: VecDeque::new(),
+            live: Some(LiveLane {
+                poll_interval: Duration::from_secs(1),
+                last_slot: None,
+                queue: VecDeque::new(),
+                sourced: VecDeque::new(),
+            }),
+            backfill: None,
+        }
+    }
+
+    fn geyser_block(slot: u64) -> SubscribeUpdate {
+        SubscribeUpdate {
+            filters: vec!["blocks".to_string()],
+            update_oneof: Some(UpdateOneof::Block(SubscribeUpdateBlock {
+                slot,
+                parent_slot: slot - 1,
+                blockhash: format!("blockhash-{}", slot),
+                ..Default::default()
+            })),
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn unsent_blocks_are_not_counted_as_queued() {
+        let store = open_block_store(BlockStoreConfig {
+            url: "sqlite::memory:".to_string(),
+            stage: MongoDBStage::Beta,
+            dry_run: false,
+        })
+        .await
+        .unwrap();
+        let (classifier_tx, classifier_rx) = channel::unbounded();
+        drop(classifier_rx);
+        let mut thread = live_thread(classifier_tx, store);
+
+        let block = serde_json::from_value(serde_json::json!({
+            "previousBlockhash": "blockhash-9",
+            "blockhash": "blockhash-10",
+            "parentSlot": 9,
+            "blockTime": null,
+            "blockHeight": null,
+        }))
+        .unwrap();
+        thread
+            .live
+            .as_mut()
+            .unwrap()
+            .sourced
+            .push_back(SourcedBlock { slot: 10, block });
+        thread.dispatch_pending();
+
+        let (slot, _) = tokio::time::timeout(Duration::from_secs(10), thread.failure_rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(slot, 10);
+        assert_eq!(thread.metrics.pending_blocks(), 0);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn geyser_blocks_are_classified_and_gaps_requested() {
+        let addr = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+        let server = MockGeyserServer::new(
+            vec![geyser_block(10), geyser_block(11), geyser_block(14)],
+            Duration::from_millis(10),
+        );
+        tokio::spawn(server.serve(addr, async {
+            let _ = shutdown_rx.await;
+        }));
+
+        let source = GeyserBlockSource::new(GeyserBlockSourceConfig {
+            endpoint: format!("http://{}", addr),
+            x_token: None,
+            reconnect_backoff: Duration::from_millis(50),
+            buffer: 16,
+        });
+        let mut source_rx = source.subscribe();
+
+        let store = open_block_store(BlockStoreConfig {
+            url: "sqlite::memory:".to_string(),
+            stage: MongoDBStage::Beta,
+            dry_run: false,
+        })
+        .await
+        .unwrap();
+        let (classifier_tx, classifier_rx) = channel::unbounded();
+        let mut thread = live_thread(classifier_tx, store);
+
+        for _ in 0..3 {
+            let sourced = tokio::time::timeout(Duration::from_secs(10), source_rx.recv())
+                .await
+                .unwrap()
+                .unwrap();
+            thread.handle_sourced(sourced);
+        }
+
+        let mut classified = Vec::new();
+        while classified.len() < 3 {
+            match classifier_rx.try_recv() {
+                Ok(request) => classified.push(request.slot),
+                Err(_) => tokio::time::sleep(Duration::from_millis(10)).await,
+            }
+        }
+        classified.sort();
+        assert_eq!(classified, vec![10, 11, 14]);
+
+        let live = thread.live.as_ref().unwrap();
+        assert_eq!(live.last_slot, Some(14));
+        assert_eq!(live.queue, VecDeque::from([12, 13]));
+
+        let _ = shutdown_tx.send(());
+    }
 }
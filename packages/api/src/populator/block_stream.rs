@@ -0,0 +1,303 @@
+use std::{collections::HashSet, sync::Arc};
+
+use actions::{Action, ActionNodeId, ActionTree};
+use classifier_core::{
+    json::{ToJsonValue, SCHEMA_VERSION},
+    SandwichAttackTag, TransactionTag,
+};
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+use tokio::sync::broadcast;
+
+pub type StreamedBlockReceiver = broadcast::Receiver<Arc<StreamedBlock>>;
+
+/// Publishes blocks classified by the live indexing lane to stream subscribers.
+/// Subscribers which fall behind by more than the channel capacity skip blocks.
+#[derive(Clone)]
+pub struct BlockStream {
+    tx: broadcast::Sender<Arc<StreamedBlock>>,
+}
+
+impl BlockStream {
+    pub fn new(capacity: usize) -> Self {
+        let (tx, _) = broadcast::channel(capacity.max(1));
+        Self { tx }
+    }
+
+    pub fn subscribe(&self) -> StreamedBlockReceiver {
+        self.tx.subscribe()
+    }
+
+    pub fn has_subscribers(&self) -> bool {
+        self.tx.receiver_count() > 0
+    }
+
+    pub fn publish(&self, block: StreamedBlock) {
+        // Only fails when there are no subscribers
+        let _ = self.tx.send(Arc::new(block));
+    }
+}
+
+/// A classified block, with the fields stream filters match on extracted from each
+/// transaction
+pub struct StreamedBlock {
+    pub slot: u64,
+    pub block: serde_json::Value,
+    pub transactions: Vec<StreamedTransaction>,
+}
+
+pub struct StreamedTransaction {
+    pub signature: Signature,
+    pub program_ids: HashSet<Pubkey>,
+    pub accounts: HashSet<Pubkey>,
+    pub tags: Vec<StreamedTag>,
+}
+
+pub struct StreamedTag {
+    pub tag_type: &'static str,
+    pub profit: Option<i64>,
+    pub json: serde_json::Value,
+}
+
+impl StreamedBlock {
+    pub fn new(slot: u64, tree: &ActionTree, block: serde_json::Value) -> Self {
+        let transactions = tree
+            .descendants(tree.root())
+            .filter_map(|id| match tree.get(id).unwrap().get() {
+                Action::ClassifiableTransaction(txn) => Some(StreamedTransaction {
+                    signature: txn.signature,
+                    program_ids: program_ids(tree, id),
+                    accounts: txn.accounts().iter().map(|meta| meta.pubkey).collect(),
+                    tags: txn.tags.iter().map(StreamedTag::new).collect(),
+                }),
+                _ => None,
+            })
+            .collect();
+
+        Self {
+            slot,
+            block,
+            transactions,
+        }
+    }
+}
+
+/// Programs invoked by a transaction, including through CPIs, from its instructions and
+/// every instruction node of its subtree
+fn program_ids(tree: &ActionTree, txn_id: ActionNodeId) -> HashSet<Pubkey> {
+    let mut program_ids = HashSet::new();
+
+    for id in tree.descendants(txn_id) {
+        match tree.get(id).unwrap().get() {
+            // Instructions are flattened, so inner instructions follow their parent
+            Action::ClassifiableTransaction(txn) => program_ids.extend(
+                txn.instructions
+                    .iter()
+                    .filter_map(|ix| txn.get_pubkey(ix.program_id_index)),
+            ),
+            Action::ProgramInvocation(invocation) => {
+                program_ids.insert(invocation.program_id);
+            }
+            Action::AnchorInstruction(instruction) => {
+                program_ids.insert(instruction.program_id);
+            }
+            _ => {}
+        }
+    }
+
+    program_ids
+}
+
+impl StreamedTag {
+    fn new(tag: &TransactionTag) -> Self {
+        let profit = match tag {
+            TransactionTag::AtomicArbitrage(arb) => {
+                Some(arb.profit_amount.clamp(i64::MIN as i128, i64::MAX as i128) as i64)
+            }
+            TransactionTag::SandwichAttack(SandwichAttackTag::Backrun {
+                profit_amount, ..
+            }) => Some(*profit_amount),
+            TransactionTag::SandwichAttack(_) => None,
+        };
+
+        Self {
+            tag_type: tag.type_name(),
+            profit,
+            json: tag.to_json_value(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StreamMode {
+    /// Push the whole block when any transaction matches
+    #[default]
+    Block,
+
+    /// Push only the matching MEV tags
+    Tags,
+}
+
+/// Server side filters of a stream subscription. Empty filters match everything.
+#[derive(Debug, Clone, Default)]
+pub struct StreamFilter {
+    pub mode: StreamMode,
+    pub tag_types: Vec<String>,
+    pub program_ids: Vec<Pubkey>,
+    pub addresses: Vec<Pubkey>,
+    pub min_profit: Option<i64>,
+}
+
+impl StreamFilter {
+    fn filters_tags(&self) -> bool {
+        self.mode == StreamMode::Tags || !self.tag_types.is_empty() || self.min_profit.is_some()
+    }
+
+    fn matches_tag(&self, tag: &StreamedTag) -> bool {
+        let profitable = match self.min_profit {
+            Some(min_profit) => tag.profit.is_some_and(|profit| profit >= min_profit),
+            None => true,
+        };
+
+        profitable
+            && (self.tag_types.is_empty() || self.tag_types.iter().any(|t| t == tag.tag_type))
+    }
+
+    fn matches_transaction(&self, txn: &StreamedTransaction) -> bool {
+        (self.program_ids.is_empty()
+            || self
+                .program_ids
+                .iter()
+                .any(|id| txn.program_ids.contains(id)))
+            && (self.addresses.is_empty()
+                || self
+                    .addresses
+                    .iter()
+                    .any(|address| txn.accounts.contains(address)))
+    }
+
+    /// Message to push for a block, if anything in it matches
+    pub fn message(&self, block: &StreamedBlock) -> Option<serde_json::Value> {
+        let mut events = Vec::new();
+        let mut matched = false;
+
+        for txn in &block.transactions {
+            if !self.matches_transaction(txn) {
+                continue;
+            }
+
+            if !self.filters_tags() {
+                matched = true;
+                continue;
+            }
+
+            for tag in txn.tags.iter().filter(|tag| self.matches_tag(tag)) {
+                matched = true;
+                if self.mode == StreamMode::Tags {
+                    events.push(serde_json::json!({
+                        "signature": txn.signature.to_string(),
+                        "tag": tag.json,
+                    }));
+                }
+            }
+        }
+
+        if !matched {
+            return None;
+        }
+
+        Some(match self.mode {
+            StreamMode::Block => serde_json::json!({
+                "schemaVersion": SCHEMA_VERSION,
+                "slot": block.slot,
+                "block": block.block,
+            }),
+            StreamMode::Tags => serde_json::json!({
+                "schemaVersion": SCHEMA_VERSION,
+                "slot": block.slot,
+                "events": events,
+            }),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use actions::ProgramInvocation;
+    use classifier_core::ClassifiableTransaction;
+    use solana_sdk::{
+        instruction::CompiledInstruction,
+        message::{Message, MessageHeader, VersionedMessage},
+        signature::Signature,
+        transaction::VersionedTransaction,
+    };
+    use solana_transaction_status::UiTransactionStatusMeta;
+
+    use super::*;
+
+    /// Transaction whose only instruction makes a CPI
+    fn transaction(payer: Pubkey, program: Pubkey, cpi_program: Pubkey) -> ClassifiableTransaction {
+        let message = Message {
+            header: MessageHeader {
+                num_required_signatures: 1,
+                num_readonly_signed_accounts: 0,
+                num_readonly_unsigned_accounts: 2,
+            },
+            account_keys: vec![payer, program, cpi_program],
+            recent_blockhash: Default::default(),
+            instructions: vec![CompiledInstruction::new_from_raw_parts(1, vec![], vec![0])],
+        };
+
+        let meta: UiTransactionStatusMeta = serde_json::from_value(serde_json::json!({
+            "err": null,
+            "status": { "Ok": null },
+            "fee": 5000,
+            "preBalances": [1_000_000, 1, 1],
+            "postBalances": [995_000, 1, 1],
+            "innerInstructions": [{
+                "index": 0,
+                "instructions": [{
+                    "programIdIndex": 2,
+                    "accounts": [0],
+                    "data": "",
+                    "stackHeight": 2,
+                }],
+            }],
+            "logMessages": [],
+        }))
+        .unwrap();
+
+        ClassifiableTransaction::new(
+            VersionedTransaction {
+                signatures: vec![Signature::default()],
+                message: VersionedMessage::Legacy(message),
+            },
+            meta,
+        )
+    }
+
+    #[test]
+    fn program_ids_include_cpis() {
+        let (payer, program, cpi_program, invoked) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+
+        let mut tree = ActionTree::new(transaction(payer, program, cpi_program).into());
+        tree.insert_child(
+            tree.root(),
+            ProgramInvocation {
+                program_id: invoked,
+            }
+            .into(),
+        );
+
+        let block = StreamedBlock::new(1, &tree, serde_json::Value::Null);
+
+        assert_eq!(
+            block.transactions[0].program_ids,
+            HashSet::from([program, cpi_program, invoked])
+        );
+    }
+}
@@ -0,0 +1,130 @@
+use std::{
+    sync::atomic::{AtomicU64, AtomicUsize, Ordering},
+    time::{Duration, Instant},
+};
+
+use serde::Serialize;
+
+/// Counters shared by the BlockRequester and the classifier workers. The requester
+/// reads the pending counts to apply backpressure when the classifiers are saturated.
+pub struct ClassifierMetrics {
+    workers: usize,
+    started: Instant,
+
+    blocks_classified: AtomicU64,
+    blocks_failed: AtomicU64,
+    transactions_classified: AtomicU64,
+    classify_time_micros: AtomicU64,
+
+    busy_workers: AtomicUsize,
+    fetching_blocks: AtomicUsize,
+    queued_blocks: AtomicUsize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClassifierMetricsSnapshot {
+    pub workers: usize,
+    pub busy_workers: usize,
+    pub fetching_blocks: usize,
+    pub queued_blocks: usize,
+    pub blocks_classified: u64,
+    pub blocks_failed: u64,
+    pub transactions_classified: u64,
+    pub blocks_per_second: f64,
+    pub transactions_per_second: f64,
+    pub average_classify_millis: f64,
+    pub uptime_seconds: u64,
+}
+
+impl ClassifierMetrics {
+    pub fn new(workers: usize) -> Self {
+        Self {
+            workers,
+            started: Instant::now(),
+            blocks_classified: AtomicU64::new(0),
+            blocks_failed: AtomicU64::new(0),
+            transactions_classified: AtomicU64::new(0),
+            classify_time_micros: AtomicU64::new(0),
+            busy_workers: AtomicUsize::new(0),
+            fetching_blocks: AtomicUsize::new(0),
+            queued_blocks: AtomicUsize::new(0),
+        }
+    }
+
+    /// Blocks being fetched from RPC or waiting for a classifier worker
+    pub fn pending_blocks(&self) -> usize {
+        self.fetching_blocks.load(Ordering::Relaxed) + self.queued_blocks.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn fetch_started(&self) {
+        self.fetching_blocks.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// A fetched block is counted as queued before it leaves the fetching count, so
+    /// it is never missing from the pending count.
+    pub(crate) fn fetch_finished(&self, queued: bool) {
+        if queued {
+            self.queued_blocks.fetch_add(1, Ordering::Relaxed);
+        }
+        self.fetching_blocks.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    /// A queued block could not be sent to the classifiers
+    pub(crate) fn queue_failed(&self) {
+        self.queued_blocks.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn classify_started(&self) {
+        self.busy_workers.fetch_add(1, Ordering::Relaxed);
+        self.queued_blocks.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn classify_finished(&self, elapsed: Duration, transactions: Option<usize>) {
+        match transactions {
+            Some(transactions) => {
+                self.blocks_classified.fetch_add(1, Ordering::Relaxed);
+                self.transactions_classified
+                    .fetch_add(transactions as u64, Ordering::Relaxed);
+            }
+            None => {
+                self.blocks_failed.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        self.classify_time_micros
+            .fetch_add(elapsed.as_micros() as u64, Ordering::Relaxed);
+        self.busy_workers.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    pub fn snapshot(&self) -> ClassifierMetricsSnapshot {
+        let blocks_classified = self.blocks_classified.load(Ordering::Relaxed);
+        let blocks_failed = self.blocks_failed.load(Ordering::Relaxed);
+        let transactions_classified = self.transactions_classified.load(Ordering::Relaxed);
+        let classify_time_micros = self.classify_time_micros.load(Ordering::Relaxed);
+
+        let uptime = self.started.elapsed();
+        let uptime_seconds = uptime.as_secs_f64().max(f64::EPSILON);
+
+        let blocks_processed = blocks_classified + blocks_failed;
+        let average_classify_millis = if blocks_processed == 0 {
+            0.0
+        } else {
+            classify_time_micros as f64 / blocks_processed as f64 / 1000.0
+        };
+
+        ClassifierMetricsSnapshot {
+            workers: self.workers,
+            busy_workers: self.busy_workers.load(Ordering::Relaxed),
+            fetching_blocks: self.fetching_blocks.load(Ordering::Relaxed),
+            queued_blocks: self.queued_blocks.load(Ordering::Relaxed),
+            blocks_classified,
+            blocks_failed,
+            transactions_classified,
+            blocks_per_second: blocks_classified as f64 / uptime_seconds,
+            transactions_per_second: transactions_classified as f64 / uptime_seconds,
+            average_classify_millis,
+            uptime_seconds: uptime.as_secs(),
+        }
+    }
+}
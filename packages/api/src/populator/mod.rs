@@ -1,5 +1,11 @@
+mod block_cache;
 mod block_classifier;
 mod block_requester;
+mod block_stream;
+mod metrics;
 
+pub use block_cache::*;
 pub use block_classifier::*;
 pub use block_requester::*;
+pub use block_stream::*;
+pub use metrics::*;
@@ -2,11 +2,12 @@ use std::{sync::Arc, time::Duration};
 
 use actions::serialize_block;
 use axum::{extract::Query, http::StatusCode, response::IntoResponse, Extension, Json};
+use classifier_core::json::SCHEMA_VERSION;
 use futures::future;
 use serde::{Deserialize, Serialize};
 use tokio::{sync::oneshot, time::timeout};
 
-use crate::populator::FetchBlockRequest;
+use crate::populator::{ClassifyFailure, FetchBlockRequest};
 
 use super::AppState;
 
@@ -19,8 +20,17 @@ pub struct ClassifyQuery {
 
 #[derive(Serialize)]
 struct ClassifySuccess {
+    #[serde(rename = "schemaVersion")]
+    schema_version: u32,
     blocks: Vec<serde_json::Value>,
-    failures: Vec<u64>,
+    failures: Vec<SlotFailure>,
+}
+
+#[derive(Serialize)]
+struct SlotFailure {
+    slot: u64,
+    reason: &'static str,
+    message: String,
 }
 
 #[derive(Serialize)]
@@ -50,18 +60,27 @@ pub async fn classify(
 ) -> impl IntoResponse {
     let ClassifyQuery { start_slot, limit } = params;
 
+    // Cached slots are served directly, without requesting them from RPC
+    let cached_blocks =
+        future::join_all((start_slot..start_slot + limit).map(|slot| state.block_cache.get(slot)))
+            .await;
+
     let mut requests = Vec::with_capacity(limit as usize);
     let mut receivers = Vec::with_capacity(limit as usize);
 
-    // Create requests and receivers for each slot
-    for slot in start_slot..start_slot + limit {
+    // Create requests and receivers for each uncached slot
+    for (slot, cached_block) in (start_slot..).zip(&cached_blocks) {
+        if cached_block.is_some() {
+            continue;
+        }
+
         let (tx, rx) = oneshot::channel();
         requests.push(FetchBlockRequest { slot, response: tx });
-        receivers.push(rx);
+        receivers.push((slot, rx));
     }
 
     // Send requests to the central processing thread
-    if state.user_request_tx.send(requests).await.is_err() {
+    if !requests.is_empty() && state.user_request_tx.send(requests).await.is_err() {
         // 500 Internal Server Error
         return ClassifyResponse::Error(ClassifyError {
             message: "Internal server error".to_string(),
@@ -71,32 +90,46 @@ pub async fn classify(
     let timeout_duration = Duration::from_secs(30);
 
     // Collect results from the receivers, applying a timeout
+    let mut classified =
+        future::join_all(receivers.into_iter().map(|(slot, receiver)| async move {
+            match timeout(timeout_duration, receiver).await {
+                Ok(Ok(result)) => (slot, result),
+                Ok(Err(_)) => (slot, Err(ClassifyFailure::Cancelled)),
+                Err(_) => (slot, Err(ClassifyFailure::TimedOut)),
+            }
+        }))
+        .await
+        .into_iter();
+
     // Prepare vectors to hold successes and failures
     let mut blocks = Vec::with_capacity(limit as usize);
     let mut failures = Vec::with_capacity(limit as usize);
 
-    // Process the receivers and classify each slot as success or failure
-    let _ = future::join_all(receivers.into_iter().enumerate().map(|(i, receiver)| {
-        let slot = start_slot + i as u64;
-        async move {
-            match timeout(timeout_duration, receiver).await {
-                Ok(Ok(tree)) => Some((slot, tree)), // success
-                _ => Some((slot, None)),            // failure
-            }
+    // Merge cached and classified slots in slot order
+    for cached_block in cached_blocks {
+        if let Some(block_json) = cached_block {
+            blocks.push(block_json.as_ref().clone());
+            continue;
         }
-    }))
-    .await
-    .into_iter()
-    .filter_map(|result| result)
-    .for_each(|(slot, tree)| {
-        if let Some(tree) = tree {
-            let block_json = serialize_block(&tree, tree.root());
-            blocks.push(block_json);
-        } else {
-            failures.push(slot);
+
+        match classified.next().unwrap() {
+            (slot, Ok(tree)) => {
+                let block_json = serialize_block(&tree, tree.root());
+                state.block_cache.insert(slot, block_json.clone()).await;
+                blocks.push(block_json);
+            }
+            (slot, Err(failure)) => failures.push(SlotFailure {
+                slot,
+                reason: failure.reason(),
+                message: failure.to_string(),
+            }),
         }
-    });
+    }
 
     // Return the results as JSON
-    ClassifyResponse::Success(ClassifySuccess { blocks, failures })
+    ClassifyResponse::Success(ClassifySuccess {
+        schema_version: SCHEMA_VERSION,
+        blocks,
+        failures,
+    })
 }
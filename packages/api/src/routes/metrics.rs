@@ -0,0 +1,10 @@
+use std::sync::Arc;
+
+use axum::{response::IntoResponse, Extension, Json};
+
+use super::AppState;
+
+/// Throughput and saturation of the classification pipeline
+pub async fn metrics(Extension(state): Extension<Arc<AppState>>) -> impl IntoResponse {
+    Json(state.metrics.snapshot())
+}
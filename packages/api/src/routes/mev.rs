@@ -0,0 +1,233 @@
+use std::{str::FromStr, sync::Arc};
+
+use axum::{
+    extract::{Path, Query},
+    http::StatusCode,
+    response::IntoResponse,
+    Extension, Json,
+};
+use classifier_core::{document::Document, json::SCHEMA_VERSION};
+use inspection::database::mev_query::{
+    mev_event_json, MevEventQuery, MevEventSort, MevEventType, MAX_MEV_EVENTS_LIMIT,
+};
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+
+use super::AppState;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MevEventsQuery {
+    pub start_slot: Option<u64>,
+    pub end_slot: Option<u64>,
+
+    /// `arbitrage` or `sandwich`
+    #[serde(rename = "type")]
+    pub event_type: Option<String>,
+
+    /// Arbitrageur, sandwich attacker or sandwich victim
+    pub address: Option<String>,
+    pub mint: Option<String>,
+
+    /// `slot`, `-slot`, `profit` or `-profit`, descending when prefixed with `-`
+    pub sort: Option<String>,
+    pub offset: Option<u64>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Serialize)]
+struct MevEventsSuccess {
+    #[serde(rename = "schemaVersion")]
+    schema_version: u32,
+    events: Vec<serde_json::Value>,
+    offset: u64,
+    limit: i64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MevBlockSuccess {
+    schema_version: u32,
+    block: serde_json::Value,
+    block_metadata: Vec<serde_json::Value>,
+    events: Vec<serde_json::Value>,
+}
+
+#[derive(Serialize)]
+struct MevError {
+    message: String,
+}
+
+enum MevResponse<T> {
+    Success(T),
+    Error(StatusCode, MevError),
+}
+
+impl<T> MevResponse<T> {
+    fn error(status: StatusCode, message: impl ToString) -> Self {
+        MevResponse::Error(
+            status,
+            MevError {
+                message: message.to_string(),
+            },
+        )
+    }
+}
+
+impl<T: Serialize> IntoResponse for MevResponse<T> {
+    fn into_response(self) -> axum::response::Response {
+        match self {
+            MevResponse::Success(success) => (StatusCode::OK, Json(success)).into_response(),
+            MevResponse::Error(status, error) => (status, Json(error)).into_response(),
+        }
+    }
+}
+
+impl TryFrom<MevEventsQuery> for MevEventQuery {
+    type Error = String;
+
+    fn try_from(params: MevEventsQuery) -> Result<Self, Self::Error> {
+        let event_type = match params.event_type.as_deref() {
+            None => None,
+            Some("arbitrage") => Some(MevEventType::Arbitrage),
+            Some("sandwich") => Some(MevEventType::Sandwich),
+            Some(event_type) => return Err(format!("Invalid event type: {}", event_type)),
+        };
+
+        let sort = match params.sort.as_deref() {
+            None => MevEventSort::default(),
+            Some("-slot") => MevEventSort::SlotDescending,
+            Some("slot") => MevEventSort::SlotAscending,
+            Some("-profit") => MevEventSort::ProfitDescending,
+            Some("profit") => MevEventSort::ProfitAscending,
+            Some(sort) => return Err(format!("Invalid sort: {}", sort)),
+        };
+
+        let parse_pubkey = |value: Option<String>| {
+            value
+                .map(|value| {
+                    Pubkey::from_str(&value).map_err(|_| format!("Invalid pubkey: {}", value))
+                })
+                .transpose()
+        };
+
+        Ok(MevEventQuery {
+            start_slot: params.start_slot,
+            end_slot: params.end_slot,
+            event_type,
+            address: parse_pubkey(params.address)?,
+            mint: parse_pubkey(params.mint)?,
+            sort,
+            offset: params.offset.unwrap_or(0),
+            limit: params.limit.unwrap_or(MAX_MEV_EVENTS_LIMIT),
+        })
+    }
+}
+
+/// Transactions tagged with MEV, filtered by slot range, type, address and mint
+pub async fn mev_events(
+    Query(params): Query<MevEventsQuery>,
+    Extension(state): Extension<Arc<AppState>>,
+) -> impl IntoResponse {
+    let query = match MevEventQuery::try_from(params) {
+        Ok(query) => query,
+        Err(message) => return MevResponse::error(StatusCode::BAD_REQUEST, message),
+    };
+
+    match state.store.find_mev_events(&query).await {
+        Ok(events) => MevResponse::Success(MevEventsSuccess {
+            schema_version: SCHEMA_VERSION,
+            events: events.iter().map(mev_event_json).collect(),
+            offset: query.offset,
+            limit: query.limit(),
+        }),
+        Err(err) => {
+            tracing::error!("Failed to query MEV events: {:?}", err);
+            MevResponse::error(StatusCode::INTERNAL_SERVER_ERROR, "Internal server error")
+        }
+    }
+}
+
+/// Indexed block, its metadata and the transactions tagged with MEV in it
+pub async fn mev_block(
+    Path(slot): Path<u64>,
+    Extension(state): Extension<Arc<AppState>>,
+) -> impl IntoResponse {
+    let (block, block_metadata) = match state.store.find_block(slot).await {
+        Ok(Some(block)) => block,
+        Ok(None) => return MevResponse::error(StatusCode::NOT_FOUND, "Block not indexed"),
+        Err(err) => {
+            tracing::error!("Failed to query block {}: {:?}", slot, err);
+            return MevResponse::error(StatusCode::INTERNAL_SERVER_ERROR, "Internal server error");
+        }
+    };
+
+    let query = MevEventQuery {
+        start_slot: Some(slot),
+        end_slot: Some(slot),
+        sort: MevEventSort::SlotAscending,
+        limit: MAX_MEV_EVENTS_LIMIT,
+        ..Default::default()
+    };
+
+    match state.store.find_mev_events(&query).await {
+        Ok(events) => MevResponse::Success(MevBlockSuccess {
+            schema_version: SCHEMA_VERSION,
+            block: block.to_json(),
+            block_metadata: block_metadata.iter().map(Document::to_json).collect(),
+            events: events.iter().map(mev_event_json).collect(),
+        }),
+        Err(err) => {
+            tracing::error!("Failed to query MEV events in block {}: {:?}", slot, err);
+            MevResponse::error(StatusCode::INTERNAL_SERVER_ERROR, "Internal server error")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn query(params: serde_json::Value) -> Result<MevEventQuery, String> {
+        serde_json::from_value::<MevEventsQuery>(params)
+            .unwrap()
+            .try_into()
+    }
+
+    #[test]
+    fn parses_query_parameters() {
+        let address = Pubkey::new_unique();
+        let query = query(serde_json::json!({
+            "startSlot": 10,
+            "type": "sandwich",
+            "address": address.to_string(),
+            "sort": "-profit",
+            "limit": 5,
+        }))
+        .unwrap();
+
+        assert_eq!(query.start_slot, Some(10));
+        assert_eq!(query.end_slot, None);
+        assert_eq!(query.event_type, Some(MevEventType::Sandwich));
+        assert_eq!(query.address, Some(address));
+        assert_eq!(query.mint, None);
+        assert_eq!(query.sort, MevEventSort::ProfitDescending);
+        assert_eq!((query.offset, query.limit), (0, 5));
+    }
+
+    #[test]
+    fn defaults_to_most_recent_events() {
+        let query = query(serde_json::json!({})).unwrap();
+
+        assert_eq!(query.event_type, None);
+        assert_eq!(query.sort, MevEventSort::SlotDescending);
+        assert_eq!(query.limit, MAX_MEV_EVENTS_LIMIT);
+    }
+
+    #[test]
+    fn rejects_invalid_query_parameters() {
+        assert!(query(serde_json::json!({ "type": "liquidation" })).is_err());
+        assert!(query(serde_json::json!({ "sort": "fee" })).is_err());
+        assert!(query(serde_json::json!({ "mint": "not a pubkey" })).is_err());
+    }
+}
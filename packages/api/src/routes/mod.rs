@@ -1,10 +1,36 @@
 mod classify;
+mod metrics;
+mod mev;
+mod openapi;
+mod stats;
+mod stream;
+mod transaction;
 
 pub use classify::*;
+pub use metrics::*;
+pub use mev::*;
+pub use openapi::*;
+pub use stats::*;
+pub use stream::*;
+pub use transaction::*;
 
-use crate::populator::FetchBlockSender;
+use std::sync::Arc;
+
+use classifier_core::AddressLookupTableSource;
+use classifier_handler::ClassifierRegistry;
+use inspection::database::block_store::BlockStore;
+use solana_client::nonblocking::rpc_client::RpcClient;
+
+use crate::populator::{BlockCache, BlockStream, ClassifierMetrics, FetchBlockSender};
 
 #[derive(Clone)]
 pub struct AppState {
     pub user_request_tx: FetchBlockSender,
+    pub metrics: Arc<ClassifierMetrics>,
+    pub block_cache: Arc<BlockCache>,
+    pub rpc_client: Arc<RpcClient>,
+    pub classifier_registry: Arc<ClassifierRegistry>,
+    pub lookup_tables: Arc<dyn AddressLookupTableSource + Send + Sync>,
+    pub store: Arc<dyn BlockStore>,
+    pub block_stream: BlockStream,
 }
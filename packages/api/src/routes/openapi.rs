@@ -0,0 +1,293 @@
+use axum::{response::IntoResponse, Json};
+use classifier_core::{
+    json::{JsonSchema, SCHEMA_VERSION},
+    TransactionTag,
+};
+use serde_json::json;
+use solana_sdk::signature::Signature;
+
+/// OpenAPI document describing every route, with the action tree schema as a component
+pub async fn openapi() -> impl IntoResponse {
+    Json(openapi_document())
+}
+
+fn schema_ref(name: &str) -> serde_json::Value {
+    json!({ "$ref": format!("#/components/schemas/{}", name) })
+}
+
+fn query_parameter(name: &str, schema: serde_json::Value, required: bool) -> serde_json::Value {
+    json!({ "name": name, "in": "query", "required": required, "schema": schema })
+}
+
+fn path_parameter(name: &str, schema: serde_json::Value) -> serde_json::Value {
+    json!({ "name": name, "in": "path", "required": true, "schema": schema })
+}
+
+fn json_response(description: &str, schema: serde_json::Value) -> serde_json::Value {
+    json!({
+        "description": description,
+        "content": { "application/json": { "schema": schema } },
+    })
+}
+
+/// Response envelope holding `schemaVersion` alongside `properties`
+fn envelope(properties: serde_json::Value) -> serde_json::Value {
+    let mut schema = json!({
+        "type": "object",
+        "properties": { "schemaVersion": schema_ref("SchemaVersion") },
+        "required": ["schemaVersion"],
+    });
+
+    for (key, property) in properties.as_object().unwrap() {
+        schema["properties"][key] = property.clone();
+        schema["required"].as_array_mut().unwrap().push(json!(key));
+    }
+
+    schema
+}
+
+fn error_response(description: &str) -> serde_json::Value {
+    json_response(description, schema_ref("Error"))
+}
+
+pub fn openapi_document() -> serde_json::Value {
+    let stored_document = json!({
+        "type": "object",
+        "description": "Document as stored in the block store",
+    });
+    let mev_event = json!({
+        "type": "object",
+        "description": "Transaction document as stored in the block store",
+        "properties": {
+            "tags": { "type": "array", "items": schema_ref("TransactionTag") },
+        },
+    });
+
+    json!({
+        "openapi": "3.1.0",
+        "info": {
+            "title": "MEV classifier",
+            "version": format!("{}.0.0", SCHEMA_VERSION),
+            "description": "Classified Solana blocks, transactions and MEV. Responses carry \
+                `schemaVersion`, which changes when a field is removed, renamed or changes type.",
+        },
+        "paths": {
+            "/classify": {
+                "get": {
+                    "summary": "Classify a range of blocks",
+                    "parameters": [
+                        query_parameter("startSlot", json!({ "type": "integer" }), true),
+                        query_parameter("limit", json!({ "type": "integer", "minimum": 1 }), true),
+                    ],
+                    "responses": {
+                        "200": json_response("Classified blocks and the slots which failed", envelope(json!({
+                            "blocks": { "type": "array", "items": schema_ref("ActionNode") },
+                            "failures": { "type": "array", "items": schema_ref("SlotFailure") },
+                        }))),
+                        "500": error_response("Classification failed"),
+                    },
+                },
+            },
+            "/metrics": {
+                "get": {
+                    "summary": "Throughput and saturation of the classification pipeline",
+                    "responses": {
+                        "200": json_response("Pipeline metrics", schema_ref("Metrics")),
+                    },
+                },
+            },
+            "/mev/events": {
+                "get": {
+                    "summary": "Arbitrage and sandwich events",
+                    "parameters": [
+                        query_parameter("startSlot", json!({ "type": "integer" }), false),
+                        query_parameter("endSlot", json!({ "type": "integer" }), false),
+                        query_parameter("type", json!({ "enum": ["arbitrage", "sandwich"] }), false),
+                        query_parameter("address", json!({ "type": "string" }), false),
+                        query_parameter("mint", json!({ "type": "string" }), false),
+                        query_parameter(
+                            "sort",
+                            json!({ "enum": ["slot", "-slot", "profit", "-profit"] }),
+                            false,
+                        ),
+                        query_parameter("offset", json!({ "type": "integer" }), false),
+                        query_parameter("limit", json!({ "type": "integer", "minimum": 1 }), false),
+                    ],
+                    "responses": {
+                        "200": json_response("Matching events", envelope(json!({
+                            "events": { "type": "array", "items": mev_event },
+                            "offset": { "type": "integer" },
+                            "limit": { "type": "integer" },
+                        }))),
+                        "400": error_response("Invalid query"),
+                        "500": error_response("Store query failed"),
+                    },
+                },
+            },
+            "/mev/blocks/{slot}": {
+                "get": {
+                    "summary": "Stored block with its metadata and MEV events",
+                    "parameters": [path_parameter("slot", json!({ "type": "integer" }))],
+                    "responses": {
+                        "200": json_response("Stored block", envelope(json!({
+                            "block": stored_document,
+                            "blockMetadata": { "type": "array", "items": stored_document },
+                            "events": { "type": "array", "items": mev_event },
+                        }))),
+                        "404": error_response("Block not indexed"),
+                        "500": error_response("Store query failed"),
+                    },
+                },
+            },
+            "/stats/{granularity}": {
+                "get": {
+                    "summary": "MEV rollups per slot, hour or day, most recent first",
+                    "parameters": [
+                        path_parameter("granularity", json!({ "enum": ["slot", "hour", "day"] })),
+                        query_parameter("start", json!({ "type": "integer" }), false),
+                        query_parameter("end", json!({ "type": "integer" }), false),
+                        query_parameter("limit", json!({ "type": "integer", "minimum": 1 }), false),
+                        query_parameter("top", json!({ "type": "integer" }), false),
+                    ],
+                    "responses": {
+                        "200": json_response("Rollups", envelope(json!({
+                            "granularity": { "enum": ["slot", "hour", "day"] },
+                            "rollups": { "type": "array", "items": stored_document },
+                        }))),
+                        "400": error_response("Invalid granularity"),
+                        "500": error_response("Store query failed"),
+                    },
+                },
+            },
+            "/stream": {
+                "get": {
+                    "summary": "Live classified blocks or tags over WebSocket or server-sent events",
+                    "parameters": [
+                        query_parameter("mode", json!({ "enum": ["block", "tags"] }), false),
+                        query_parameter(
+                            "types",
+                            json!({
+                                "type": "string",
+                                "description": "Comma separated tag types, such as \
+                                    `atomicArbitrage,sandwichBackrun`",
+                            }),
+                            false,
+                        ),
+                        query_parameter("programs", json!({ "type": "string" }), false),
+                        query_parameter("addresses", json!({ "type": "string" }), false),
+                        query_parameter("minProfit", json!({ "type": "integer" }), false),
+                    ],
+                    "responses": {
+                        "101": { "description": "Switched to a WebSocket" },
+                        "200": {
+                            "description": "Server-sent events, each holding a JSON message",
+                            "content": { "text/event-stream": { "schema": { "type": "string" } } },
+                        },
+                        "400": error_response("Invalid filter"),
+                    },
+                },
+            },
+            "/transaction": {
+                "post": {
+                    "summary": "Classify a raw transaction",
+                    "requestBody": {
+                        "required": true,
+                        "content": { "application/json": { "schema": {
+                            "type": "object",
+                            "properties": {
+                                "transaction": {
+                                    "type": "string",
+                                    "description": "Base64 encoded transaction",
+                                },
+                                "meta": { "type": "object" },
+                                "slot": { "type": "integer" },
+                                "blockTime": { "type": "integer" },
+                            },
+                            "required": ["transaction", "meta"],
+                        } } },
+                    },
+                    "responses": {
+                        "200": json_response("Classified transaction", schema_ref("TransactionResponse")),
+                        "422": error_response("Transaction could not be classified"),
+                        "500": error_response("Classification failed"),
+                    },
+                },
+            },
+            "/transaction/{signature}": {
+                "get": {
+                    "summary": "Fetch and classify a transaction",
+                    "parameters": [path_parameter("signature", Signature::json_schema())],
+                    "responses": {
+                        "200": json_response("Classified transaction", schema_ref("TransactionResponse")),
+                        "400": error_response("Invalid signature"),
+                        "422": error_response("Transaction could not be classified"),
+                        "500": error_response("Classification failed"),
+                        "502": error_response("Transaction could not be fetched from RPC"),
+                    },
+                },
+            },
+            "/openapi.json": {
+                "get": {
+                    "summary": "This document",
+                    "responses": {
+                        "200": json_response("OpenAPI document", json!({ "type": "object" })),
+                    },
+                },
+            },
+        },
+        "components": {
+            "schemas": {
+                "SchemaVersion": {
+                    "const": SCHEMA_VERSION,
+                    "description": "Version of the output schema",
+                },
+                "ActionNode": actions::action_node_schema("#/components/schemas/ActionNode"),
+                "TransactionTag": TransactionTag::json_schema(),
+                "TransactionResponse": envelope(json!({
+                    "slot": { "type": ["integer", "null"] },
+                    "blockTime": { "type": ["integer", "null"] },
+                    "transaction": schema_ref("ActionNode"),
+                })),
+                "SlotFailure": {
+                    "type": "object",
+                    "properties": {
+                        "slot": { "type": "integer" },
+                        "reason": {
+                            "enum": [
+                                "skippedSlot",
+                                "fetchFailed",
+                                "classificationFailed",
+                                "timedOut",
+                                "cancelled",
+                                "indexFailed",
+                            ],
+                        },
+                        "message": { "type": "string" },
+                    },
+                    "required": ["slot", "reason", "message"],
+                },
+                "Metrics": {
+                    "type": "object",
+                    "properties": {
+                        "workers": { "type": "integer" },
+                        "busyWorkers": { "type": "integer" },
+                        "fetchingBlocks": { "type": "integer" },
+                        "queuedBlocks": { "type": "integer" },
+                        "blocksClassified": { "type": "integer" },
+                        "blocksFailed": { "type": "integer" },
+                        "transactionsClassified": { "type": "integer" },
+                        "blocksPerSecond": { "type": "number" },
+                        "transactionsPerSecond": { "type": "number" },
+                        "averageClassifyMillis": { "type": "number" },
+                        "uptimeSeconds": { "type": "integer" },
+                    },
+                },
+                "Error": {
+                    "type": "object",
+                    "properties": { "message": { "type": "string" } },
+                    "required": ["message"],
+                },
+            },
+        },
+    })
+}
@@ -0,0 +1,105 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, Query},
+    http::StatusCode,
+    response::IntoResponse,
+    Extension, Json,
+};
+use classifier_core::json::SCHEMA_VERSION;
+use inspection::database::mev_stats::{rollup_to_json, StatsGranularity};
+use serde::{Deserialize, Serialize};
+
+use super::AppState;
+
+const MAX_STATS_LIMIT: i64 = 1000;
+
+#[derive(Deserialize)]
+pub struct StatsQuery {
+    /// First bucket to include. Slots for slot rollups, otherwise unix timestamps.
+    pub start: Option<i64>,
+
+    /// Last bucket to include, inclusive
+    pub end: Option<i64>,
+    pub limit: Option<i64>,
+
+    /// Number of top searchers and sandwiched mints included in each rollup
+    pub top: Option<usize>,
+}
+
+#[derive(Serialize)]
+struct StatsSuccess {
+    #[serde(rename = "schemaVersion")]
+    schema_version: u32,
+    granularity: &'static str,
+    rollups: Vec<serde_json::Value>,
+}
+
+#[derive(Serialize)]
+struct StatsError {
+    message: String,
+}
+
+enum StatsResponse {
+    Success(StatsSuccess),
+    Error(StatusCode, StatsError),
+}
+
+impl StatsResponse {
+    fn error(status: StatusCode, message: impl ToString) -> Self {
+        StatsResponse::Error(
+            status,
+            StatsError {
+                message: message.to_string(),
+            },
+        )
+    }
+}
+
+impl IntoResponse for StatsResponse {
+    fn into_response(self) -> axum::response::Response {
+        match self {
+            StatsResponse::Success(success) => (StatusCode::OK, Json(success)).into_response(),
+            StatsResponse::Error(status, error) => (status, Json(error)).into_response(),
+        }
+    }
+}
+
+/// MEV rollups per slot, hour or day, most recent first
+pub async fn stats(
+    Path(granularity): Path<String>,
+    Query(params): Query<StatsQuery>,
+    Extension(state): Extension<Arc<AppState>>,
+) -> impl IntoResponse {
+    let granularity = match StatsGranularity::parse(&granularity) {
+        Some(granularity) => granularity,
+        None => {
+            return StatsResponse::error(
+                StatusCode::BAD_REQUEST,
+                format!("Invalid granularity: {}", granularity),
+            )
+        }
+    };
+
+    let limit = params.limit.unwrap_or(100).clamp(1, MAX_STATS_LIMIT);
+    let top = params.top.unwrap_or(10);
+
+    match state
+        .store
+        .find_mev_stats(granularity, params.start, params.end, limit)
+        .await
+    {
+        Ok(rollups) => StatsResponse::Success(StatsSuccess {
+            schema_version: SCHEMA_VERSION,
+            granularity: granularity.as_str(),
+            rollups: rollups
+                .iter()
+                .map(|rollup| rollup_to_json(rollup, top))
+                .collect(),
+        }),
+        Err(err) => {
+            tracing::error!("Failed to query MEV stats: {:?}", err);
+            StatsResponse::error(StatusCode::INTERNAL_SERVER_ERROR, "Internal server error")
+        }
+    }
+}
@@ -0,0 +1,165 @@
+use std::{convert::Infallible, str::FromStr, sync::Arc};
+
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        Query,
+    },
+    http::StatusCode,
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
+    Extension, Json,
+};
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::populator::{StreamFilter, StreamMode, StreamedBlockReceiver};
+
+use super::AppState;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamQuery {
+    /// `block` pushes whole blocks, `tags` pushes only the matching MEV tags
+    pub mode: Option<String>,
+
+    /// Comma separated tag types, e.g. `atomicArbitrage,sandwichBackrun`
+    pub types: Option<String>,
+
+    /// Comma separated program ids, matching transactions which invoke any of them
+    pub programs: Option<String>,
+
+    /// Comma separated addresses, matching transactions which load any of them
+    pub addresses: Option<String>,
+
+    /// Minimum profit of matching tags, in raw token amounts
+    pub min_profit: Option<i64>,
+}
+
+#[derive(Serialize)]
+struct StreamError {
+    message: String,
+}
+
+impl TryFrom<StreamQuery> for StreamFilter {
+    type Error = String;
+
+    fn try_from(params: StreamQuery) -> Result<Self, Self::Error> {
+        let mode = match params.mode.as_deref() {
+            None | Some("block") => StreamMode::Block,
+            Some("tags") => StreamMode::Tags,
+            Some(mode) => return Err(format!("Invalid mode: {}", mode)),
+        };
+
+        Ok(StreamFilter {
+            mode,
+            tag_types: split_list(params.types.as_deref())
+                .map(String::from)
+                .collect(),
+            program_ids: parse_pubkeys(params.programs.as_deref())?,
+            addresses: parse_pubkeys(params.addresses.as_deref())?,
+            min_profit: params.min_profit,
+        })
+    }
+}
+
+fn split_list(list: Option<&str>) -> impl Iterator<Item = &str> {
+    list.unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+}
+
+fn parse_pubkeys(list: Option<&str>) -> Result<Vec<Pubkey>, String> {
+    split_list(list)
+        .map(|pubkey| Pubkey::from_str(pubkey).map_err(|_| format!("Invalid pubkey: {}", pubkey)))
+        .collect()
+}
+
+/// Push blocks classified by the live indexer as they are produced. Served over a
+/// WebSocket when the request is an upgrade, otherwise as server-sent events.
+pub async fn stream(
+    ws: Option<WebSocketUpgrade>,
+    Query(params): Query<StreamQuery>,
+    Extension(state): Extension<Arc<AppState>>,
+) -> Response {
+    let filter = match StreamFilter::try_from(params) {
+        Ok(filter) => filter,
+        Err(message) => {
+            return (StatusCode::BAD_REQUEST, Json(StreamError { message })).into_response();
+        }
+    };
+
+    let blocks = state.block_stream.subscribe();
+
+    match ws {
+        Some(ws) => ws.on_upgrade(move |socket| stream_socket(socket, blocks, filter)),
+        None => stream_events(blocks, filter).into_response(),
+    }
+}
+
+/// Next message matching the filter, or None once the stream has closed
+async fn next_message(
+    blocks: &mut StreamedBlockReceiver,
+    filter: &StreamFilter,
+) -> Option<serde_json::Value> {
+    loop {
+        match blocks.recv().await {
+            Ok(block) => {
+                if let Some(message) = filter.message(&block) {
+                    return Some(message);
+                }
+            }
+            Err(RecvError::Lagged(skipped)) => {
+                tracing::warn!("Stream subscriber lagged, skipped {} blocks", skipped);
+            }
+            Err(RecvError::Closed) => return None,
+        }
+    }
+}
+
+async fn stream_socket(
+    mut socket: WebSocket,
+    mut blocks: StreamedBlockReceiver,
+    filter: StreamFilter,
+) {
+    loop {
+        tokio::select! {
+            message = next_message(&mut blocks, &filter) => {
+                let message = match message {
+                    Some(message) => message,
+                    None => break,
+                };
+
+                if socket.send(Message::Text(message.to_string())).await.is_err() {
+                    break;
+                }
+            }
+
+            // Subscribers only send control frames, stop once they disconnect
+            received = socket.recv() => {
+                match received {
+                    Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                    Some(Ok(_)) => {}
+                }
+            }
+        }
+    }
+}
+
+fn stream_events(
+    blocks: StreamedBlockReceiver,
+    filter: StreamFilter,
+) -> Sse<impl futures::Stream<Item = Result<Event, Infallible>>> {
+    let events = futures::stream::unfold((blocks, filter), |(mut blocks, filter)| async move {
+        let message = next_message(&mut blocks, &filter).await?;
+        let event = Event::default().data(message.to_string());
+
+        Some((Ok(event), (blocks, filter)))
+    });
+
+    Sse::new(events).keep_alive(KeepAlive::default())
+}
@@ -0,0 +1,152 @@
+use std::{str::FromStr, sync::Arc};
+
+use actions::serialize_transaction;
+use axum::{extract::Path, http::StatusCode, response::IntoResponse, Extension, Json};
+use classifier_core::json::SCHEMA_VERSION;
+use classifier_handler::{classify_encoded_transaction, ClassifyEncodedTransactionError};
+use inspection::label_transaction_tree;
+use serde::{Deserialize, Serialize};
+use solana_client::rpc_config::RpcTransactionConfig;
+use solana_sdk::{commitment_config::CommitmentConfig, signature::Signature};
+use solana_transaction_status::{
+    EncodedTransaction, EncodedTransactionWithStatusMeta, TransactionBinaryEncoding,
+    UiTransactionEncoding, UiTransactionStatusMeta,
+};
+
+use super::AppState;
+
+/// Transaction and meta for classifying transactions the RPC does not have. Lookup tables
+/// missing from the meta's loaded addresses are resolved from their current state.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RawTransactionBody {
+    /// Base64 encoded transaction
+    pub transaction: String,
+    pub meta: UiTransactionStatusMeta,
+    pub slot: Option<u64>,
+    pub block_time: Option<i64>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TransactionSuccess {
+    schema_version: u32,
+    slot: Option<u64>,
+    block_time: Option<i64>,
+    transaction: serde_json::Value,
+}
+
+#[derive(Serialize)]
+struct TransactionError {
+    message: String,
+}
+
+enum TransactionResponse {
+    Success(TransactionSuccess),
+    Error(StatusCode, TransactionError),
+}
+
+impl TransactionResponse {
+    fn error(status: StatusCode, message: impl ToString) -> Self {
+        TransactionResponse::Error(
+            status,
+            TransactionError {
+                message: message.to_string(),
+            },
+        )
+    }
+}
+
+impl IntoResponse for TransactionResponse {
+    fn into_response(self) -> axum::response::Response {
+        match self {
+            TransactionResponse::Success(success) => {
+                (StatusCode::OK, Json(success)).into_response()
+            }
+            TransactionResponse::Error(status, error) => (status, Json(error)).into_response(),
+        }
+    }
+}
+
+/// Classify a transaction fetched from RPC by its signature
+pub async fn transaction(
+    Path(signature): Path<String>,
+    Extension(state): Extension<Arc<AppState>>,
+) -> impl IntoResponse {
+    let signature = match Signature::from_str(&signature) {
+        Ok(signature) => signature,
+        Err(_) => return TransactionResponse::error(StatusCode::BAD_REQUEST, "Invalid signature"),
+    };
+
+    let txn = match state
+        .rpc_client
+        .get_transaction_with_config(
+            &signature,
+            RpcTransactionConfig {
+                encoding: Some(UiTransactionEncoding::Base64),
+                commitment: Some(CommitmentConfig::confirmed()),
+                max_supported_transaction_version: Some(0),
+            },
+        )
+        .await
+    {
+        Ok(txn) => txn,
+        Err(err) => {
+            return TransactionResponse::error(
+                StatusCode::BAD_GATEWAY,
+                format!("Failed to get transaction: {}", err),
+            );
+        }
+    };
+
+    classify(state, txn.transaction, Some(txn.slot), txn.block_time).await
+}
+
+/// Classify a transaction provided in the request body
+pub async fn raw_transaction(
+    Extension(state): Extension<Arc<AppState>>,
+    Json(body): Json<RawTransactionBody>,
+) -> impl IntoResponse {
+    let txn = EncodedTransactionWithStatusMeta {
+        transaction: EncodedTransaction::Binary(
+            body.transaction,
+            TransactionBinaryEncoding::Base64,
+        ),
+        meta: Some(body.meta),
+        version: None,
+    };
+
+    classify(state, txn, body.slot, body.block_time).await
+}
+
+async fn classify(
+    state: Arc<AppState>,
+    txn: EncodedTransactionWithStatusMeta,
+    slot: Option<u64>,
+    block_time: Option<i64>,
+) -> TransactionResponse {
+    let classifier_registry = state.classifier_registry.clone();
+    let lookup_tables = state.lookup_tables.clone();
+
+    let result = tokio::task::spawn_blocking(move || {
+        let mut tree =
+            classify_encoded_transaction(txn, Some(lookup_tables.as_ref()), &classifier_registry)?;
+        label_transaction_tree(&mut tree);
+
+        Ok::<_, ClassifyEncodedTransactionError>(serialize_transaction(&tree, tree.root()))
+    })
+    .await;
+
+    match result {
+        Ok(Ok(transaction)) => TransactionResponse::Success(TransactionSuccess {
+            schema_version: SCHEMA_VERSION,
+            slot,
+            block_time,
+            transaction,
+        }),
+        Ok(Err(err)) => TransactionResponse::error(StatusCode::UNPROCESSABLE_ENTITY, err),
+        Err(_) => {
+            TransactionResponse::error(StatusCode::INTERNAL_SERVER_ERROR, "Internal server error")
+        }
+    }
+}
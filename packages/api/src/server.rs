@@ -1,19 +1,36 @@
 use std::{
     net::{IpAddr, Ipv4Addr, SocketAddr},
+    path::PathBuf,
     sync::Arc,
 };
 
-use axum::{routing::get, Extension, Router};
-use inspection::database::mongo_client::{
-    MongoDBClient, MongoDBClientConfig, MongoDBClientError, MongoDBStage,
+use axum::{
+    routing::{get, post},
+    Extension, Router,
+};
+use classifier_handler::{ClassifierConfig, ClassifierRegistry, DynamicAnchorError};
+use inspection::{
+    database::{
+        block_store::{open_block_store, BlockStore, BlockStoreConfig, BlockStoreError},
+        mongo_client::MongoDBStage,
+    },
+    lookup_tables::RpcLookupTableSource,
+    validators::{ValidatorRegistry, ValidatorRegistryError},
 };
 use solana_client::nonblocking::rpc_client::RpcClient;
 use thiserror::Error;
 use tower_http::cors::{AllowOrigin, Any, CorsLayer};
 
 use crate::{
-    populator::{BlockClassifier, BlockRequester, BlockRequesterConfig},
-    routes::{classify, AppState},
+    ingestion::{BlockSource, GeyserBlockSource, GeyserBlockSourceConfig},
+    populator::{
+        BackfillConfig, BlockCache, BlockCacheConfig, BlockClassifier, BlockClassifierConfig,
+        BlockRequester, BlockRequesterConfig, BlockStream, ClassifierMetrics, LiveIndexingConfig,
+    },
+    routes::{
+        classify, metrics, mev_block, mev_events, openapi, raw_transaction, stats, stream,
+        transaction, AppState,
+    },
 };
 
 pub enum TimeMachineStage {
@@ -30,8 +47,17 @@ impl Into<MongoDBStage> for TimeMachineStage {
 
 #[derive(Debug, Error)]
 pub enum TimeMachineError {
-    #[error("Failed to create MongoDB client: {0}")]
-    MongoDBError(#[from] MongoDBClientError),
+    #[error("Failed to open block store: {0}")]
+    StoreError(#[from] BlockStoreError),
+
+    #[error("Failed to load validator registry: {0}")]
+    ValidatorRegistryError(#[from] ValidatorRegistryError),
+
+    #[error("Failed to load IDLs: {0}")]
+    IdlError(#[from] DynamicAnchorError),
+
+    #[error("Failed to create block cache directory: {0}")]
+    CacheDirError(#[source] std::io::Error),
 
     #[error("Failed to bind to address: {0}")]
     BindError(#[source] std::io::Error),
@@ -46,53 +72,171 @@ pub struct TimeMachineServerConfig {
     pub rpc_url: String,
     pub port: u16,
     pub rpc_requests_per_second: usize,
-    pub mongo_uri: String,
+    /// MongoDB, PostgreSQL or SQLite URL of the block store
+    pub store_url: String,
+
+    /// Validate block writes against the store without persisting them
+    pub dry_run: bool,
+
     pub stage: TimeMachineStage,
+    pub leader_schedule_path: Option<PathBuf>,
+    pub validator_metadata_path: Option<PathBuf>,
+    pub idl_dir: Option<PathBuf>,
+    pub classifier_config: ClassifierConfig,
+    pub classifier_workers: usize,
+    pub parallel_transactions: bool,
+    pub max_pending_blocks: usize,
+
+    /// Index blocks into the database as they are confirmed
+    pub live_indexing: bool,
+
+    /// Yellowstone gRPC endpoint streaming live blocks, used instead of polling RPC
+    pub geyser_endpoint: Option<String>,
+    pub geyser_x_token: Option<String>,
+
+    /// Range of slots, inclusive, to index into the database
+    pub backfill_slots: Option<(u64, u64)>,
+
+    /// Maximum number of classified blocks cached in memory
+    pub cache_max_entries: usize,
+
+    /// Maximum size in bytes of the serialized blocks cached in memory
+    pub cache_max_bytes: usize,
+
+    /// Directory where classified blocks are persisted
+    pub cache_dir: Option<PathBuf>,
+
+    /// Maximum size in bytes of the blocks persisted to `cache_dir`
+    pub cache_dir_max_bytes: u64,
 }
 
 pub struct TimeMachineServer {
     addr: SocketAddr,
     rpc_client: Arc<RpcClient>,
-    _mongo_client: MongoDBClient,
+    store: Arc<dyn BlockStore>,
+    validator_registry: Arc<ValidatorRegistry>,
+    classifier_registry: Arc<ClassifierRegistry>,
+    lookup_tables: Arc<RpcLookupTableSource>,
     rpc_requests_per_second: usize,
+    classifier_workers: usize,
+    parallel_transactions: bool,
+    max_pending_blocks: usize,
+    live_indexing: bool,
+    block_source: Option<Arc<dyn BlockSource>>,
+    backfill_slots: Option<(u64, u64)>,
+    block_cache: Arc<BlockCache>,
 }
 
 impl TimeMachineServer {
     pub async fn new(config: TimeMachineServerConfig) -> Result<Self> {
         let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), config.port);
 
+        let lookup_tables = Arc::new(RpcLookupTableSource::new(config.rpc_url.clone()));
         let rpc_client = Arc::new(RpcClient::new(config.rpc_url));
-        let _mongo_client = MongoDBClient::new(MongoDBClientConfig {
+        let store = open_block_store(BlockStoreConfig {
+            url: config.store_url,
             stage: config.stage.into(),
-            uri: config.mongo_uri,
+            dry_run: config.dry_run,
         })
         .await?;
 
+        let validator_registry = Arc::new(ValidatorRegistry::load(
+            config.leader_schedule_path.as_deref(),
+            config.validator_metadata_path.as_deref(),
+        )?);
+
+        let classifier_registry = Arc::new(ClassifierRegistry::load(
+            config.idl_dir.as_deref(),
+            &config.classifier_config,
+        )?);
+
+        if let Some(cache_dir) = &config.cache_dir {
+            std::fs::create_dir_all(cache_dir).map_err(TimeMachineError::CacheDirError)?;
+        }
+
+        let block_cache = Arc::new(BlockCache::new(
+            BlockCacheConfig {
+                max_entries: config.cache_max_entries,
+                max_bytes: config.cache_max_bytes,
+                disk_dir: config.cache_dir,
+                disk_max_bytes: config.cache_dir_max_bytes,
+            },
+            Some(store.clone()),
+        ));
+
+        let block_source = config.geyser_endpoint.map(|endpoint| {
+            Arc::new(GeyserBlockSource::new(GeyserBlockSourceConfig {
+                endpoint,
+                x_token: config.geyser_x_token,
+                reconnect_backoff: std::time::Duration::from_secs(1),
+                buffer: config.max_pending_blocks.max(1),
+            })) as Arc<dyn BlockSource>
+        });
+
         Ok(Self {
             addr,
             rpc_client,
-            _mongo_client,
+            store,
+            validator_registry,
+            classifier_registry,
+            lookup_tables,
             rpc_requests_per_second: config.rpc_requests_per_second,
+            classifier_workers: config.classifier_workers.max(1),
+            parallel_transactions: config.parallel_transactions,
+            max_pending_blocks: config.max_pending_blocks,
+            live_indexing: config.live_indexing,
+            block_source,
+            backfill_slots: config.backfill_slots,
+            block_cache,
         })
     }
 
     pub async fn serve(&self) -> Result<()> {
-        // Setup up processing pipeline (one thread coordinates async work, one thread classifies blocks)
+        // Setup up processing pipeline (one thread coordinates async work, a pool of threads classifies blocks)
+        let classifier_metrics = Arc::new(ClassifierMetrics::new(self.classifier_workers));
         let (user_request_tx, user_request_rx) = tokio::sync::mpsc::channel(10_000);
-        let (classifier_tx, classifier_rx) = crossbeam::channel::unbounded();
+        let (classifier_tx, classifier_rx) = crossbeam::channel::bounded(self.classifier_workers);
         let (classify_result_tx, classify_result_rx) = tokio::sync::mpsc::channel(10_000);
+        let block_stream = BlockStream::new(256);
         let block_requester = BlockRequester::new(
             user_request_rx,
             classifier_tx,
             classify_result_rx,
             self.rpc_client.clone(),
+            self.store.clone(),
+            classifier_metrics.clone(),
+            block_stream.clone(),
             BlockRequesterConfig {
                 requests_per_period: self.rpc_requests_per_second,
                 period: std::time::Duration::from_secs(1),
+                max_pending_blocks: self.max_pending_blocks,
+                max_retries: 3,
+                retry_backoff: std::time::Duration::from_millis(500),
+                live: self.live_indexing.then(|| LiveIndexingConfig {
+                    poll_interval: std::time::Duration::from_millis(400),
+                    source: self.block_source.clone(),
+                }),
+                backfill: self
+                    .backfill_slots
+                    .map(|(start_slot, end_slot)| BackfillConfig {
+                        start_slot,
+                        end_slot,
+                    }),
             },
         );
 
-        let block_populator = BlockClassifier::new(classifier_rx, classify_result_tx);
+        let block_populator = BlockClassifier::new(
+            classifier_rx,
+            classify_result_tx,
+            self.validator_registry.clone(),
+            self.classifier_registry.clone(),
+            self.lookup_tables.clone(),
+            classifier_metrics.clone(),
+            BlockClassifierConfig {
+                workers: self.classifier_workers,
+                parallel_transactions: self.parallel_transactions,
+            },
+        );
 
         let cors = CorsLayer::new()
             .allow_origin(AllowOrigin::any()) // Allow frontend
@@ -100,9 +244,26 @@ impl TimeMachineServer {
             .allow_headers(Any);
 
         // Setup routes
-        let classify_state = Arc::new(AppState { user_request_tx });
+        let classify_state = Arc::new(AppState {
+            user_request_tx,
+            metrics: classifier_metrics,
+            block_cache: self.block_cache.clone(),
+            rpc_client: self.rpc_client.clone(),
+            classifier_registry: self.classifier_registry.clone(),
+            lookup_tables: self.lookup_tables.clone(),
+            store: self.store.clone(),
+            block_stream,
+        });
         let app = Router::new()
             .route("/classify", get(classify))
+            .route("/metrics", get(metrics))
+            .route("/mev/events", get(mev_events))
+            .route("/mev/blocks/:slot", get(mev_block))
+            .route("/openapi.json", get(openapi))
+            .route("/stats/:granularity", get(stats))
+            .route("/stream", get(stream))
+            .route("/transaction", post(raw_transaction))
+            .route("/transaction/:signature", get(transaction))
             .layer(Extension(classify_state))
             .layer(cors);
 
@@ -0,0 +1,433 @@
+use solana_sdk::{hash::Hash, pubkey::Pubkey, signature::Signature};
+
+/// Builds a [`Document`] from `"key": value` pairs, where values convert into [`Value`]
+#[macro_export]
+macro_rules! document {
+    () => {
+        $crate::document::Document::new()
+    };
+    ($($key:literal: $value:expr),+ $(,)?) => {{
+        let mut document = $crate::document::Document::new();
+        $(document.insert($key, $value);)+
+        document
+    }};
+}
+
+/// Field value of a stored document. Storage backends decide how pubkeys, signatures
+/// and amounts wider than an i64 are encoded.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    String(String),
+    Pubkey(Pubkey),
+    Signature(Signature),
+
+    /// Lamport amount, which may not fit in an i64
+    U64(u64),
+    U128(u128),
+    Array(Vec<Value>),
+    Document(Document),
+}
+
+impl Value {
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Value::Int(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn as_document(&self) -> Option<&Document> {
+        match self {
+            Value::Document(document) => Some(document),
+            _ => None,
+        }
+    }
+
+    /// JSON representation, with pubkeys and signatures in base58 and 128 bit
+    /// integers as decimal strings
+    pub fn to_json(&self) -> serde_json::Value {
+        match self {
+            Value::Null => serde_json::Value::Null,
+            Value::Bool(value) => serde_json::Value::from(*value),
+            Value::Int(value) => serde_json::Value::from(*value),
+            Value::Float(value) => serde_json::Value::from(*value),
+            Value::String(value) => serde_json::Value::from(value.as_str()),
+            Value::Pubkey(pubkey) => serde_json::Value::from(pubkey.to_string()),
+            Value::Signature(signature) => serde_json::Value::from(signature.to_string()),
+            Value::U64(value) => serde_json::Value::from(*value),
+            Value::U128(value) => serde_json::Value::from(value.to_string()),
+            Value::Array(values) => values.iter().map(Value::to_json).collect(),
+            Value::Document(document) => document.to_json(),
+        }
+    }
+
+    /// Reads back a value stored as JSON. Pubkeys, signatures and 128 bit integers
+    /// are not distinguishable from strings, so they stay strings.
+    pub fn from_json(value: serde_json::Value) -> Self {
+        match value {
+            serde_json::Value::Null => Value::Null,
+            serde_json::Value::Bool(value) => Value::Bool(value),
+            serde_json::Value::Number(number) => match (number.as_i64(), number.as_u64()) {
+                (Some(value), _) => Value::Int(value),
+                (None, Some(value)) => Value::U64(value),
+                (None, None) => Value::Float(number.as_f64().unwrap_or_default()),
+            },
+            serde_json::Value::String(value) => Value::String(value),
+            serde_json::Value::Array(values) => {
+                Value::Array(values.into_iter().map(Value::from_json).collect())
+            }
+            serde_json::Value::Object(fields) => Value::Document(Document(
+                fields
+                    .into_iter()
+                    .map(|(key, value)| (key, Value::from_json(value)))
+                    .collect(),
+            )),
+        }
+    }
+}
+
+/// Ordered set of fields, the storage agnostic form of blocks, transactions, actions
+/// and rollups
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Document(Vec<(String, Value)>);
+
+impl Document {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets a field, replacing any previous value in place
+    pub fn insert(&mut self, key: impl Into<String>, value: impl Into<Value>) {
+        let key = key.into();
+        let value = value.into();
+
+        match self.0.iter_mut().find(|(existing, _)| *existing == key) {
+            Some((_, existing)) => *existing = value,
+            None => self.0.push((key, value)),
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.0
+            .iter()
+            .find(|(existing, _)| existing == key)
+            .map(|(_, value)| value)
+    }
+
+    pub fn get_i64(&self, key: &str) -> Option<i64> {
+        self.get(key).and_then(Value::as_i64)
+    }
+
+    pub fn get_str(&self, key: &str) -> Option<&str> {
+        self.get(key).and_then(Value::as_str)
+    }
+
+    pub fn get_document(&self, key: &str) -> Option<&Document> {
+        self.get(key).and_then(Value::as_document)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &Value)> {
+        self.0.iter().map(|(key, value)| (key, value))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::Value::Object(
+            self.0
+                .iter()
+                .map(|(key, value)| (key.clone(), value.to_json()))
+                .collect(),
+        )
+    }
+
+    pub fn from_json(value: serde_json::Value) -> Option<Self> {
+        match Value::from_json(value) {
+            Value::Document(document) => Some(document),
+            _ => None,
+        }
+    }
+}
+
+impl IntoIterator for Document {
+    type Item = (String, Value);
+    type IntoIter = std::vec::IntoIter<(String, Value)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl FromIterator<(String, Value)> for Document {
+    fn from_iter<I: IntoIterator<Item = (String, Value)>>(iter: I) -> Self {
+        let mut document = Document::new();
+        for (key, value) in iter {
+            document.insert(key, value);
+        }
+
+        document
+    }
+}
+
+impl Extend<(String, Value)> for Document {
+    fn extend<I: IntoIterator<Item = (String, Value)>>(&mut self, iter: I) {
+        for (key, value) in iter {
+            self.insert(key, value);
+        }
+    }
+}
+
+macro_rules! impl_from_int {
+    ($($int:ty),+) => {
+        $(
+            impl From<$int> for Value {
+                fn from(value: $int) -> Self {
+                    Value::Int(value as i64)
+                }
+            }
+        )+
+    };
+}
+
+impl_from_int!(i8, i16, i32, i64, u8, u16, u32);
+
+impl From<bool> for Value {
+    fn from(value: bool) -> Self {
+        Value::Bool(value)
+    }
+}
+
+impl From<f64> for Value {
+    fn from(value: f64) -> Self {
+        Value::Float(value)
+    }
+}
+
+impl From<u64> for Value {
+    fn from(value: u64) -> Self {
+        Value::U64(value)
+    }
+}
+
+impl From<u128> for Value {
+    fn from(value: u128) -> Self {
+        Value::U128(value)
+    }
+}
+
+impl From<String> for Value {
+    fn from(value: String) -> Self {
+        Value::String(value)
+    }
+}
+
+impl From<&String> for Value {
+    fn from(value: &String) -> Self {
+        Value::String(value.clone())
+    }
+}
+
+impl From<&str> for Value {
+    fn from(value: &str) -> Self {
+        Value::String(value.to_string())
+    }
+}
+
+impl From<Pubkey> for Value {
+    fn from(value: Pubkey) -> Self {
+        Value::Pubkey(value)
+    }
+}
+
+impl From<&Pubkey> for Value {
+    fn from(value: &Pubkey) -> Self {
+        Value::Pubkey(*value)
+    }
+}
+
+impl From<Signature> for Value {
+    fn from(value: Signature) -> Self {
+        Value::Signature(value)
+    }
+}
+
+impl From<Document> for Value {
+    fn from(value: Document) -> Self {
+        Value::Document(value)
+    }
+}
+
+impl<T: Into<Value>> From<Option<T>> for Value {
+    fn from(value: Option<T>) -> Self {
+        match value {
+            Some(value) => value.into(),
+            None => Value::Null,
+        }
+    }
+}
+
+impl<T: Into<Value>> From<Vec<T>> for Value {
+    fn from(values: Vec<T>) -> Self {
+        Value::Array(values.into_iter().map(Into::into).collect())
+    }
+}
+
+/// Conversion into the stored form of a value. Implemented for every action by the
+/// `#[action]` and `#[action_enum]` macros, so that stored documents are a faithful
+/// copy of the classified tree.
+pub trait ToDocumentValue {
+    fn to_document_value(&self) -> Value;
+}
+
+macro_rules! impl_to_document_value {
+    ($($ty:ty),+) => {
+        $(
+            impl ToDocumentValue for $ty {
+                fn to_document_value(&self) -> Value {
+                    Value::from(*self)
+                }
+            }
+        )+
+    };
+}
+
+impl_to_document_value!(bool, i8, i16, i32, i64, u8, u16, u32, u64, u128, f64, Pubkey, Signature);
+
+/// Stored as a decimal string, as signed 128 bit integers have no native form
+impl ToDocumentValue for i128 {
+    fn to_document_value(&self) -> Value {
+        Value::String(self.to_string())
+    }
+}
+
+impl ToDocumentValue for f32 {
+    fn to_document_value(&self) -> Value {
+        Value::Float(*self as f64)
+    }
+}
+
+impl ToDocumentValue for String {
+    fn to_document_value(&self) -> Value {
+        Value::from(self)
+    }
+}
+
+impl ToDocumentValue for Hash {
+    fn to_document_value(&self) -> Value {
+        Value::String(self.to_string())
+    }
+}
+
+impl ToDocumentValue for serde_json::Value {
+    fn to_document_value(&self) -> Value {
+        Value::from_json(self.clone())
+    }
+}
+
+impl ToDocumentValue for Document {
+    fn to_document_value(&self) -> Value {
+        Value::Document(self.clone())
+    }
+}
+
+impl<T: ToDocumentValue> ToDocumentValue for Option<T> {
+    fn to_document_value(&self) -> Value {
+        match self {
+            Some(value) => value.to_document_value(),
+            None => Value::Null,
+        }
+    }
+}
+
+impl<T: ToDocumentValue> ToDocumentValue for Vec<T> {
+    fn to_document_value(&self) -> Value {
+        Value::Array(self.iter().map(T::to_document_value).collect())
+    }
+}
+
+impl<T: ToDocumentValue, const N: usize> ToDocumentValue for [T; N] {
+    fn to_document_value(&self) -> Value {
+        Value::Array(self.iter().map(T::to_document_value).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn insert_replaces_fields_in_place() {
+        let mut document = document! { "a": 1i64, "b": 2i64 };
+        document.insert("a", 3i64);
+
+        assert_eq!(document.to_json(), serde_json::json!({ "a": 3, "b": 2 }));
+        assert_eq!(
+            document
+                .iter()
+                .map(|(key, _)| key.as_str())
+                .collect::<Vec<_>>(),
+            vec!["a", "b"]
+        );
+    }
+
+    #[test]
+    fn values_convert_to_their_stored_form() {
+        let pubkey = Pubkey::new_unique();
+        let document = document! {
+            "pubkey": pubkey,
+            "amount": u64::MAX,
+            "wide": u128::MAX,
+            "missing": Option::<Pubkey>::None,
+            "list": vec![1i64, 2i64],
+        };
+
+        assert_eq!(document.get("pubkey"), Some(&Value::Pubkey(pubkey)));
+        assert_eq!(document.get("amount"), Some(&Value::U64(u64::MAX)));
+        assert_eq!(document.get("missing"), Some(&Value::Null));
+        assert_eq!(
+            document.to_json(),
+            serde_json::json!({
+                "pubkey": pubkey.to_string(),
+                "amount": u64::MAX,
+                "wide": u128::MAX.to_string(),
+                "missing": null,
+                "list": [1, 2],
+            })
+        );
+    }
+
+    #[test]
+    fn json_round_trip_keeps_numbers_and_nesting() {
+        let document = document! {
+            "count": -1i64,
+            "amount": u64::MAX,
+            "nested": document! { "name": "swap", "ok": true },
+        };
+
+        let read = Document::from_json(document.to_json()).unwrap();
+
+        // Field order is not kept by JSON objects
+        assert_eq!(read.to_json(), document.to_json());
+        assert_eq!(read.get("amount"), Some(&Value::U64(u64::MAX)));
+        assert_eq!(read.get_i64("count"), Some(-1));
+        assert_eq!(
+            read.get_document("nested")
+                .and_then(|nested| nested.get_str("name")),
+            Some("swap")
+        );
+        assert!(Document::from_json(serde_json::json!([1])).is_none());
+    }
+}
@@ -0,0 +1,302 @@
+use solana_sdk::{hash::Hash, pubkey::Pubkey, signature::Signature};
+
+/// Version of the JSON output schema, returned as `schemaVersion` in API responses.
+/// Bumped when a field is removed, renamed or changes type, or a `type` value changes.
+/// Fields and action types may be added without a bump.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Largest integer a JSON number holds without loss in JavaScript clients
+pub const MAX_SAFE_INTEGER: u64 = (1 << 53) - 1;
+
+/// Conversion into the JSON returned by the API. Implemented for every action by the
+/// `#[action]` and `#[action_enum]` macros, with camelCase keys, pubkeys and
+/// signatures in base58 and integers wider than [`MAX_SAFE_INTEGER`] as decimal
+/// strings.
+pub trait ToJsonValue {
+    fn to_json_value(&self) -> serde_json::Value;
+}
+
+/// JSON Schema of the values produced by [`ToJsonValue`]. Implemented alongside it by
+/// the `#[action]` and `#[action_enum]` macros.
+pub trait JsonSchema {
+    fn json_schema() -> serde_json::Value;
+}
+
+/// Integer as a JSON number when it fits in [`MAX_SAFE_INTEGER`], otherwise as a
+/// decimal string
+pub fn safe_integer(value: i128) -> serde_json::Value {
+    if value.unsigned_abs() <= MAX_SAFE_INTEGER as u128 {
+        serde_json::Value::from(value as i64)
+    } else {
+        serde_json::Value::from(value.to_string())
+    }
+}
+
+macro_rules! impl_to_json_value {
+    ($($ty:ty),+) => {
+        $(
+            impl ToJsonValue for $ty {
+                fn to_json_value(&self) -> serde_json::Value {
+                    serde_json::Value::from(*self)
+                }
+            }
+        )+
+    };
+}
+
+impl_to_json_value!(bool, i8, i16, i32, u8, u16, u32, f32, f64);
+
+macro_rules! impl_to_json_value_wide {
+    ($($ty:ty),+) => {
+        $(
+            impl ToJsonValue for $ty {
+                fn to_json_value(&self) -> serde_json::Value {
+                    safe_integer(*self as i128)
+                }
+            }
+        )+
+    };
+}
+
+impl_to_json_value_wide!(i64, u64, i128);
+
+impl ToJsonValue for u128 {
+    fn to_json_value(&self) -> serde_json::Value {
+        match i128::try_from(*self) {
+            Ok(value) => safe_integer(value),
+            Err(_) => serde_json::Value::from(self.to_string()),
+        }
+    }
+}
+
+impl ToJsonValue for String {
+    fn to_json_value(&self) -> serde_json::Value {
+        serde_json::Value::from(self.as_str())
+    }
+}
+
+impl ToJsonValue for Pubkey {
+    fn to_json_value(&self) -> serde_json::Value {
+        serde_json::Value::from(self.to_string())
+    }
+}
+
+impl ToJsonValue for Signature {
+    fn to_json_value(&self) -> serde_json::Value {
+        serde_json::Value::from(self.to_string())
+    }
+}
+
+impl ToJsonValue for Hash {
+    fn to_json_value(&self) -> serde_json::Value {
+        serde_json::Value::from(self.to_string())
+    }
+}
+
+impl ToJsonValue for serde_json::Value {
+    fn to_json_value(&self) -> serde_json::Value {
+        self.clone()
+    }
+}
+
+impl<T: ToJsonValue> ToJsonValue for Option<T> {
+    fn to_json_value(&self) -> serde_json::Value {
+        match self {
+            Some(value) => value.to_json_value(),
+            None => serde_json::Value::Null,
+        }
+    }
+}
+
+impl<T: ToJsonValue> ToJsonValue for Vec<T> {
+    fn to_json_value(&self) -> serde_json::Value {
+        serde_json::Value::Array(self.iter().map(ToJsonValue::to_json_value).collect())
+    }
+}
+
+impl<T: ToJsonValue, const N: usize> ToJsonValue for [T; N] {
+    fn to_json_value(&self) -> serde_json::Value {
+        serde_json::Value::Array(self.iter().map(ToJsonValue::to_json_value).collect())
+    }
+}
+
+/// Schema of [`safe_integer`] values
+pub fn safe_integer_schema() -> serde_json::Value {
+    serde_json::json!({
+        "oneOf": [
+            { "type": "integer" },
+            { "type": "string", "pattern": "^-?[0-9]+$" },
+        ],
+    })
+}
+
+/// Schema of a base58 string, such as a pubkey or signature
+pub fn base58_schema(description: &str) -> serde_json::Value {
+    serde_json::json!({
+        "type": "string",
+        "pattern": "^[1-9A-HJ-NP-Za-km-z]+$",
+        "description": description,
+    })
+}
+
+/// Schema of an object holding `properties`, all of which are always present
+pub fn object_schema(properties: Vec<(&str, serde_json::Value)>) -> serde_json::Value {
+    let required = properties
+        .iter()
+        .map(|(key, _)| serde_json::Value::from(*key))
+        .collect::<Vec<_>>();
+
+    serde_json::json!({
+        "type": "object",
+        "properties": properties
+            .into_iter()
+            .map(|(key, schema)| (key.to_string(), schema))
+            .collect::<serde_json::Map<_, _>>(),
+        "required": required,
+    })
+}
+
+/// Schema of an enum serialized with the variant name under `tag`, followed by the
+/// fields of the variant's payload. Payloads which are not objects are stored under
+/// `value`, and payload fields replace the tag when they share its key.
+pub fn tagged_union_schema(
+    tag: &str,
+    variants: Vec<(&str, Option<serde_json::Value>)>,
+) -> serde_json::Value {
+    let variants = variants
+        .into_iter()
+        .map(|(variant, payload)| tagged_variant_schema(tag, variant, payload))
+        .collect::<Vec<_>>();
+
+    serde_json::json!({ "oneOf": variants })
+}
+
+fn tagged_variant_schema(
+    tag: &str,
+    variant: &str,
+    payload: Option<serde_json::Value>,
+) -> serde_json::Value {
+    let tag_schema = serde_json::json!({ "const": variant });
+
+    let payload = match payload {
+        Some(payload) => payload,
+        None => return object_schema(vec![(tag, tag_schema)]),
+    };
+
+    // Payload is itself a tagged union, extended into the object per variant
+    if let Some(payload_variants) = payload.get("oneOf").and_then(|v| v.as_array()) {
+        let variants = payload_variants
+            .iter()
+            .map(|payload| tagged_variant_schema(tag, variant, Some(payload.clone())))
+            .collect::<Vec<_>>();
+
+        return serde_json::json!({ "oneOf": variants });
+    }
+
+    if payload.get("type").and_then(|v| v.as_str()) != Some("object") {
+        return object_schema(vec![(tag, tag_schema), ("value", payload)]);
+    }
+
+    let mut schema = object_schema(vec![(tag, tag_schema)]);
+    if let Some(description) = payload.get("description") {
+        schema["description"] = description.clone();
+    }
+
+    if let Some(properties) = payload.get("properties").and_then(|v| v.as_object()) {
+        let schema_properties = schema["properties"].as_object_mut().unwrap();
+        for (key, property) in properties {
+            schema_properties.insert(key.clone(), property.clone());
+        }
+    }
+
+    if let Some(required) = payload.get("required").and_then(|v| v.as_array()) {
+        let schema_required = schema["required"].as_array_mut().unwrap();
+        for key in required {
+            if !schema_required.contains(key) {
+                schema_required.push(key.clone());
+            }
+        }
+    }
+
+    schema
+}
+
+macro_rules! impl_json_schema {
+    ($schema:tt => $($ty:ty),+) => {
+        $(
+            impl JsonSchema for $ty {
+                fn json_schema() -> serde_json::Value {
+                    serde_json::json!($schema)
+                }
+            }
+        )+
+    };
+}
+
+impl_json_schema!({ "type": "boolean" } => bool);
+impl_json_schema!({ "type": "integer" } => i8, i16, i32, u8, u16, u32);
+impl_json_schema!({ "type": "number" } => f32, f64);
+impl_json_schema!({ "type": "string" } => String);
+
+// Any JSON value
+impl_json_schema!({} => serde_json::Value);
+
+macro_rules! impl_json_schema_wide {
+    ($($ty:ty),+) => {
+        $(
+            impl JsonSchema for $ty {
+                fn json_schema() -> serde_json::Value {
+                    safe_integer_schema()
+                }
+            }
+        )+
+    };
+}
+
+impl_json_schema_wide!(i64, u64, i128, u128);
+
+impl JsonSchema for Pubkey {
+    fn json_schema() -> serde_json::Value {
+        base58_schema("Base58 public key")
+    }
+}
+
+impl JsonSchema for Signature {
+    fn json_schema() -> serde_json::Value {
+        base58_schema("Base58 transaction signature")
+    }
+}
+
+impl JsonSchema for Hash {
+    fn json_schema() -> serde_json::Value {
+        base58_schema("Base58 hash")
+    }
+}
+
+impl<T: JsonSchema> JsonSchema for Option<T> {
+    fn json_schema() -> serde_json::Value {
+        serde_json::json!({
+            "anyOf": [T::json_schema(), { "type": "null" }],
+        })
+    }
+}
+
+impl<T: JsonSchema> JsonSchema for Vec<T> {
+    fn json_schema() -> serde_json::Value {
+        serde_json::json!({
+            "type": "array",
+            "items": T::json_schema(),
+        })
+    }
+}
+
+impl<T: JsonSchema, const N: usize> JsonSchema for [T; N] {
+    fn json_schema() -> serde_json::Value {
+        serde_json::json!({
+            "type": "array",
+            "items": T::json_schema(),
+            "minItems": N,
+            "maxItems": N,
+        })
+    }
+}
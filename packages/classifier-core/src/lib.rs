@@ -1,3 +1,5 @@
+pub mod document;
+pub mod json;
 mod transaction;
 
 pub use transaction::*;
@@ -0,0 +1,323 @@
+use std::collections::HashMap;
+
+use solana_sdk::{
+    message::{v0::LoadedAddresses, v0::MessageAddressTableLookup, VersionedMessage},
+    pubkey::Pubkey,
+};
+
+/// Where an account key of a transaction was loaded from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccountSource {
+    /// Included in the message's static account keys
+    Static,
+
+    /// Loaded from an address lookup table
+    LookupTable { table: Pubkey, index: u8 },
+}
+
+/// A resolved account key of a transaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AccountMeta {
+    pub pubkey: Pubkey,
+    pub is_signer: bool,
+
+    /// Whether the message requested a write lock for this account. Reserved accounts
+    /// and invoked programs are demoted to readonly.
+    pub is_writable: bool,
+
+    pub source: AccountSource,
+}
+
+/// Account state used to resolve address lookup tables when a transaction's meta does
+/// not include its loaded addresses.
+///
+/// Lookup tables are append only, so addresses fetched after the transaction landed
+/// resolve to the same accounts unless the table has since been closed.
+pub trait AddressLookupTableSource {
+    fn get_lookup_table_addresses(&self, table: &Pubkey) -> Option<Vec<Pubkey>>;
+}
+
+impl AddressLookupTableSource for HashMap<Pubkey, Vec<Pubkey>> {
+    fn get_lookup_table_addresses(&self, table: &Pubkey) -> Option<Vec<Pubkey>> {
+        self.get(table).cloned()
+    }
+}
+
+/// Resolve every account key of a message, in the order instructions index them:
+/// static keys, then writable and readonly lookup table addresses.
+///
+/// Lookup table addresses are taken from `loaded_addresses` when present and otherwise
+/// resolved through `lookup_tables`. If neither can provide them, only the static keys
+/// are returned.
+pub fn resolve_accounts(
+    message: &VersionedMessage,
+    loaded_addresses: Option<LoadedAddresses>,
+    lookup_tables: Option<&dyn AddressLookupTableSource>,
+) -> Vec<AccountMeta> {
+    let static_keys = message.static_account_keys();
+    let lookups = message.address_table_lookups().unwrap_or_default();
+
+    let mut accounts = Vec::with_capacity(static_keys.len());
+
+    for (idx, pubkey) in static_keys.iter().enumerate() {
+        accounts.push(AccountMeta {
+            pubkey: *pubkey,
+            is_signer: message.is_signer(idx),
+            is_writable: message.is_maybe_writable(idx),
+            source: AccountSource::Static,
+        });
+    }
+
+    if lookups.is_empty() {
+        return accounts;
+    }
+
+    let loaded_addresses = match loaded_addresses
+        .or_else(|| lookup_tables.and_then(|source| load_addresses(lookups, source)))
+    {
+        Some(loaded_addresses) => loaded_addresses,
+        None => {
+            tracing::trace!("Could not resolve address lookup tables for message");
+            return accounts;
+        }
+    };
+
+    let writable_sources = lookups
+        .iter()
+        .flat_map(|lookup| lookup_sources(lookup, &lookup.writable_indexes));
+    let readonly_sources = lookups
+        .iter()
+        .flat_map(|lookup| lookup_sources(lookup, &lookup.readonly_indexes));
+
+    for (pubkey, source) in loaded_addresses.writable.iter().zip(writable_sources) {
+        accounts.push(AccountMeta {
+            pubkey: *pubkey,
+            is_signer: false,
+            is_writable: true,
+            source,
+        });
+    }
+
+    for (pubkey, source) in loaded_addresses.readonly.iter().zip(readonly_sources) {
+        accounts.push(AccountMeta {
+            pubkey: *pubkey,
+            is_signer: false,
+            is_writable: false,
+            source,
+        });
+    }
+
+    accounts
+}
+
+fn lookup_sources<'a>(
+    lookup: &'a MessageAddressTableLookup,
+    indexes: &'a [u8],
+) -> impl Iterator<Item = AccountSource> + 'a {
+    indexes.iter().map(|index| AccountSource::LookupTable {
+        table: lookup.account_key,
+        index: *index,
+    })
+}
+
+/// Load the addresses referenced by a message's lookups. Returns None if any table or
+/// index cannot be resolved, since partially loaded addresses would shift every index
+/// after the missing one.
+fn load_addresses(
+    lookups: &[MessageAddressTableLookup],
+    source: &dyn AddressLookupTableSource,
+) -> Option<LoadedAddresses> {
+    let tables = lookups
+        .iter()
+        .map(|lookup| source.get_lookup_table_addresses(&lookup.account_key))
+        .collect::<Option<Vec<_>>>()?;
+
+    let mut loaded_addresses = LoadedAddresses::default();
+
+    for (lookup, addresses) in lookups.iter().zip(&tables) {
+        for index in &lookup.writable_indexes {
+            loaded_addresses
+                .writable
+                .push(*addresses.get(*index as usize)?);
+        }
+    }
+
+    for (lookup, addresses) in lookups.iter().zip(&tables) {
+        for index in &lookup.readonly_indexes {
+            loaded_addresses
+                .readonly
+                .push(*addresses.get(*index as usize)?);
+        }
+    }
+
+    Some(loaded_addresses)
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_sdk::{
+        hash::Hash,
+        message::{v0, MessageHeader},
+    };
+
+    use super::*;
+
+    /// Message signed by a fee payer, with a readonly static program and one lookup of a
+    /// writable and a readonly address from `table`
+    fn v0_message(table: Pubkey) -> VersionedMessage {
+        VersionedMessage::V0(v0::Message {
+            header: MessageHeader {
+                num_required_signatures: 1,
+                num_readonly_signed_accounts: 0,
+                num_readonly_unsigned_accounts: 1,
+            },
+            account_keys: vec![Pubkey::new_unique(), Pubkey::new_unique()],
+            recent_blockhash: Hash::default(),
+            instructions: vec![],
+            address_table_lookups: vec![MessageAddressTableLookup {
+                account_key: table,
+                writable_indexes: vec![2],
+                readonly_indexes: vec![0],
+            }],
+        })
+    }
+
+    fn lookup_table() -> Vec<Pubkey> {
+        (0..3).map(|_| Pubkey::new_unique()).collect()
+    }
+
+    #[test]
+    fn resolves_static_keys() {
+        let message = v0_message(Pubkey::new_unique());
+        let keys = message.static_account_keys().to_vec();
+
+        let accounts = resolve_accounts(&message, None, None);
+
+        assert_eq!(
+            accounts,
+            vec![
+                AccountMeta {
+                    pubkey: keys[0],
+                    is_signer: true,
+                    is_writable: true,
+                    source: AccountSource::Static,
+                },
+                AccountMeta {
+                    pubkey: keys[1],
+                    is_signer: false,
+                    is_writable: false,
+                    source: AccountSource::Static,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn resolves_lookup_tables_through_source() {
+        let table = Pubkey::new_unique();
+        let addresses = lookup_table();
+        let message = v0_message(table);
+        let source = HashMap::from([(table, addresses.clone())]);
+
+        let accounts = resolve_accounts(&message, None, Some(&source));
+
+        assert_eq!(accounts.len(), 4);
+        assert_eq!(
+            accounts[2],
+            AccountMeta {
+                pubkey: addresses[2],
+                is_signer: false,
+                is_writable: true,
+                source: AccountSource::LookupTable { table, index: 2 },
+            }
+        );
+        assert_eq!(
+            accounts[3],
+            AccountMeta {
+                pubkey: addresses[0],
+                is_signer: false,
+                is_writable: false,
+                source: AccountSource::LookupTable { table, index: 0 },
+            }
+        );
+    }
+
+    #[test]
+    fn prefers_loaded_addresses() {
+        let table = Pubkey::new_unique();
+        let message = v0_message(table);
+        let loaded_addresses = LoadedAddresses {
+            writable: vec![Pubkey::new_unique()],
+            readonly: vec![Pubkey::new_unique()],
+        };
+        let source = HashMap::from([(table, lookup_table())]);
+
+        let accounts = resolve_accounts(&message, Some(loaded_addresses.clone()), Some(&source));
+
+        assert_eq!(accounts[2].pubkey, loaded_addresses.writable[0]);
+        assert_eq!(accounts[3].pubkey, loaded_addresses.readonly[0]);
+        assert_eq!(
+            accounts[3].source,
+            AccountSource::LookupTable { table, index: 0 }
+        );
+    }
+
+    #[test]
+    fn unresolved_lookup_tables_leave_static_keys() {
+        let message = v0_message(Pubkey::new_unique());
+        let source = HashMap::from([(Pubkey::new_unique(), lookup_table())]);
+
+        let accounts = resolve_accounts(&message, None, Some(&source));
+
+        assert_eq!(accounts.len(), 2);
+        assert!(accounts
+            .iter()
+            .all(|account| account.source == AccountSource::Static));
+    }
+
+    #[test]
+    fn load_addresses_orders_writable_before_readonly() {
+        let (first, second) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (first_addresses, second_addresses) = (lookup_table(), lookup_table());
+        let lookups = [
+            MessageAddressTableLookup {
+                account_key: first,
+                writable_indexes: vec![1],
+                readonly_indexes: vec![0, 2],
+            },
+            MessageAddressTableLookup {
+                account_key: second,
+                writable_indexes: vec![0],
+                readonly_indexes: vec![1],
+            },
+        ];
+        let source = HashMap::from([
+            (first, first_addresses.clone()),
+            (second, second_addresses.clone()),
+        ]);
+
+        let loaded_addresses = load_addresses(&lookups, &source).unwrap();
+
+        assert_eq!(
+            loaded_addresses.writable,
+            vec![first_addresses[1], second_addresses[0]]
+        );
+        assert_eq!(
+            loaded_addresses.readonly,
+            vec![first_addresses[0], first_addresses[2], second_addresses[1]]
+        );
+    }
+
+    #[test]
+    fn load_addresses_fails_on_missing_index() {
+        let table = Pubkey::new_unique();
+        let lookups = [MessageAddressTableLookup {
+            account_key: table,
+            writable_indexes: vec![3],
+            readonly_indexes: vec![],
+        }];
+        let source = HashMap::from([(table, lookup_table())]);
+
+        assert_eq!(load_addresses(&lookups, &source), None);
+    }
+}
@@ -1,16 +1,76 @@
-use std::{collections::HashMap, str::FromStr};
+use std::{
+    collections::{HashMap, HashSet},
+    str::FromStr,
+};
 
 use solana_sdk::{
     message::v0::LoadedAddresses,
+    pubkey,
     pubkey::Pubkey,
     signature::Signature,
+    system_instruction::SystemInstruction,
+    system_program,
     transaction::{TransactionError, VersionedTransaction},
 };
 use solana_transaction_status::{
     UiInnerInstructions, UiLoadedAddresses, UiTransactionStatusMeta, UiTransactionTokenBalance,
 };
 
-use super::{instruction::ClassifiableInstruction, TransactionTag};
+use crate::{
+    document::{Document, ToDocumentValue, Value},
+    json::{object_schema, safe_integer, safe_integer_schema, JsonSchema, ToJsonValue},
+};
+
+use super::{
+    accounts::{resolve_accounts, AccountMeta, AddressLookupTableSource},
+    instruction::ClassifiableInstruction,
+    logs::parse_logged_invocations,
+    TransactionTag,
+};
+
+/// Lamports charged per transaction signature
+pub const LAMPORTS_PER_SIGNATURE: u64 = 5000;
+
+/// Compute unit limit given to each instruction when no limit is requested
+pub const DEFAULT_INSTRUCTION_COMPUTE_UNIT_LIMIT: u32 = 200_000;
+
+/// Compute unit limit given to each builtin program instruction when no limit is requested
+pub const MAX_BUILTIN_ALLOCATION_COMPUTE_UNIT_LIMIT: u32 = 3_000;
+
+/// Maximum compute unit limit a transaction can request
+pub const MAX_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+
+const MICRO_LAMPORTS_PER_LAMPORT: u128 = 1_000_000;
+
+/// Programs the runtime treats as builtins when working out the default compute unit limit
+pub const BUILTIN_PROGRAM_IDS: [Pubkey; 12] = [
+    system_program::ID,
+    solana_sdk::vote::program::ID,
+    solana_sdk::stake::program::ID,
+    solana_sdk::config::program::ID,
+    solana_sdk::bpf_loader_deprecated::ID,
+    solana_sdk::bpf_loader::ID,
+    solana_sdk::bpf_loader_upgradeable::ID,
+    solana_sdk::loader_v4::ID,
+    solana_sdk::compute_budget::ID,
+    solana_sdk::address_lookup_table::program::ID,
+    pubkey!("ZkTokenProof1111111111111111111111111111111"),
+    pubkey!("ZkE1Gama1Proof11111111111111111111111111111"),
+];
+
+/// Precompiles whose signatures are charged like transaction signatures. The first byte
+/// of their instruction data is the number of signatures verified.
+pub const PRECOMPILE_PROGRAM_IDS: [Pubkey; 3] = [
+    solana_sdk::ed25519_program::ID,
+    solana_sdk::secp256k1_program::ID,
+    pubkey!("Secp256r1SigVerify1111111111111111111111111"),
+];
+
+/// Mint of wrapped SOL. Token amounts of this mint are denominated in lamports.
+pub const NATIVE_MINT: Pubkey = pubkey!("So11111111111111111111111111111111111111112");
+
+/// Token-2022 program, whose mints may withhold a fee from every transfer
+pub const TOKEN_2022_PROGRAM_ID: Pubkey = pubkey!("TokenzQdBNbLqP5VEhdkAs6EPFLC1PHnBqCXEpPxuEb");
 
 #[derive(Debug, Clone)]
 pub struct ClassifiableTransaction {
@@ -19,11 +79,26 @@ pub struct ClassifiableTransaction {
     pub instructions: Vec<ClassifiableInstruction>,
     pub pre_token_balances: Option<Vec<UiTransactionTokenBalance>>,
     pub post_token_balances: Option<Vec<UiTransactionTokenBalance>>,
+    pub pre_balances: Vec<u64>,
+    pub post_balances: Vec<u64>,
     pub fee: u64,
+    pub num_signatures: u8,
     pub created_tokens: Option<HashMap<Pubkey, Pubkey>>,
 
+    /// Name of the classifier which handled each instruction, by instruction index. Set
+    /// once the transaction is classified, unclassified instructions are None.
+    pub instruction_classifiers: Vec<Option<String>>,
+
+    /// Nonce account advanced by a durable nonce transaction
+    pub nonce_account: Option<Pubkey>,
+
+    // Compute budget
+    pub compute_units_consumed: Option<u64>,
+    pub compute_unit_limit: Option<u32>,
+    pub compute_unit_price: Option<u64>,
+
     pub static_keys: Vec<Pubkey>,
-    loaded_addresses: Option<LoadedAddresses>,
+    accounts: Vec<AccountMeta>,
 
     // Label metadata
     pub tags: Vec<TransactionTag>,
@@ -37,8 +112,99 @@ impl PartialEq for ClassifiableTransaction {
 
 impl Eq for ClassifiableTransaction {}
 
+impl ToDocumentValue for ClassifiableTransaction {
+    fn to_document_value(&self) -> Value {
+        let mut document = Document::new();
+        document.insert("signature", self.signature);
+        document.insert("success", self.status.is_ok());
+        document.insert(
+            "error",
+            self.status.as_ref().err().map(|err| err.to_string()),
+        );
+        document.insert("fee", self.fee);
+        document.insert("num_signatures", self.num_signatures);
+        document.insert("compute_units_consumed", self.compute_units_consumed);
+        document.insert("compute_unit_limit", self.compute_unit_limit);
+        document.insert("compute_unit_price", self.compute_unit_price);
+        document.insert("nonce_account", self.nonce_account);
+        document.insert(
+            "instruction_classifiers",
+            self.instruction_classifiers.to_document_value(),
+        );
+
+        Value::Document(document)
+    }
+}
+
+impl ToJsonValue for ClassifiableTransaction {
+    fn to_json_value(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "transaction",
+            "signature": self.signature.to_json_value(),
+            "failed": self.status.is_err(),
+            "nonceAccount": self.nonce_account.to_json_value(),
+            "computeUnitsConsumed": self.compute_units_consumed.to_json_value(),
+            "computeUnitLimit": self.compute_unit_limit,
+            "computeUnitPrice": self.compute_unit_price.to_json_value(),
+            "solBalanceChanges": self
+                .sol_balance_changes()
+                .into_iter()
+                .map(|(account, change)| serde_json::json!({
+                    "account": account.to_json_value(),
+                    "change": safe_integer(change),
+                }))
+                .collect::<Vec<_>>(),
+            "instructionClassifiers": self.instruction_classifiers,
+            "tags": self.tags.to_json_value(),
+        })
+    }
+}
+
+impl JsonSchema for ClassifiableTransaction {
+    fn json_schema() -> serde_json::Value {
+        object_schema(vec![
+            ("type", serde_json::json!({ "const": "transaction" })),
+            ("signature", Signature::json_schema()),
+            ("failed", bool::json_schema()),
+            ("nonceAccount", Option::<Pubkey>::json_schema()),
+            ("computeUnitsConsumed", Option::<u64>::json_schema()),
+            ("computeUnitLimit", Option::<u32>::json_schema()),
+            ("computeUnitPrice", Option::<u64>::json_schema()),
+            (
+                "solBalanceChanges",
+                serde_json::json!({
+                    "type": "array",
+                    "items": object_schema(vec![
+                        ("account", Pubkey::json_schema()),
+                        ("change", safe_integer_schema()),
+                    ]),
+                }),
+            ),
+            (
+                "instructionClassifiers",
+                serde_json::json!({
+                    "type": "array",
+                    "items": Option::<String>::json_schema(),
+                    "description": "Classifier which handled each instruction, by instruction index",
+                }),
+            ),
+            ("tags", Vec::<TransactionTag>::json_schema()),
+        ])
+    }
+}
+
 impl ClassifiableTransaction {
     pub fn new(txn: VersionedTransaction, meta: UiTransactionStatusMeta) -> Self {
+        Self::new_with_lookup_tables(txn, meta, None)
+    }
+
+    /// Create a classifiable transaction, resolving any address lookup tables missing
+    /// from the meta's loaded addresses through `lookup_tables`.
+    pub fn new_with_lookup_tables(
+        txn: VersionedTransaction,
+        meta: UiTransactionStatusMeta,
+        lookup_tables: Option<&dyn AddressLookupTableSource>,
+    ) -> Self {
         let instructions = txn.message.instructions().to_owned();
         let mut inner_instructions =
             Option::<Vec<UiInnerInstructions>>::from(meta.inner_instructions);
@@ -79,22 +245,217 @@ impl ClassifiableTransaction {
             None
         };
 
+        let accounts = resolve_accounts(&txn.message, loaded_addresses, lookup_tables);
         let static_keys = txn.message.static_account_keys().into();
 
         let signature = txn.signatures.first().unwrap().clone();
+        let num_signatures = txn.message.header().num_required_signatures;
 
-        Self {
+        let mut classifiable = Self {
             signature,
             status: meta.status,
             instructions: classifiable_instructions,
             static_keys,
-            loaded_addresses,
+            accounts,
             pre_token_balances: meta.pre_token_balances.into(),
             post_token_balances: meta.post_token_balances.into(),
+            pre_balances: meta.pre_balances,
+            post_balances: meta.post_balances,
             fee: meta.fee,
+            num_signatures,
             created_tokens: None,
+            instruction_classifiers: vec![],
+            nonce_account: None,
+            compute_units_consumed: meta.compute_units_consumed.into(),
+            compute_unit_limit: None,
+            compute_unit_price: None,
             tags: vec![],
+        };
+
+        classifiable.nonce_account = classifiable.find_nonce_account();
+
+        if let Some(log_messages) = Option::<Vec<String>>::from(meta.log_messages) {
+            classifiable.assign_instruction_compute_units(&log_messages);
+        }
+
+        classifiable
+    }
+
+    /// Match program invocations in the logs to instructions and record the compute
+    /// units each one consumed. Stops at the first invocation that does not line up
+    /// with the instruction list (e.g. inner instructions failed to decode).
+    fn assign_instruction_compute_units(&mut self, log_messages: &[String]) {
+        let invocations = parse_logged_invocations(log_messages);
+
+        for (idx, invocation) in invocations.into_iter().enumerate() {
+            let ix = match self.instructions.get(idx) {
+                Some(ix) => ix,
+                None => break,
+            };
+
+            let program_id = self.get_pubkey(ix.program_id_index);
+            if ix.stack_height != invocation.stack_height
+                || program_id.map(|id| id.to_string()).as_ref() != Some(&invocation.program_id)
+            {
+                tracing::trace!(
+                    "Logged invocations do not match instructions, signature {:?}",
+                    self.signature
+                );
+                break;
+            }
+
+            self.instructions[idx].compute_units_consumed = invocation.compute_units_consumed;
+        }
+    }
+
+    /// Durable nonce transactions must advance their nonce account in the first instruction
+    fn find_nonce_account(&self) -> Option<Pubkey> {
+        let ix = self.instructions.first()?;
+
+        if self.get_pubkey(ix.program_id_index)? != system_program::ID {
+            return None;
+        }
+
+        match bincode::deserialize(&ix.data).ok()? {
+            SystemInstruction::AdvanceNonceAccount => self
+                .get_instruction_account(ix, 0)
+                .map(|account| account.pubkey),
+            _ => None,
+        }
+    }
+
+    pub fn uses_durable_nonce(&self) -> bool {
+        self.nonce_account.is_some()
+    }
+
+    /// Signatures verified by precompile instructions
+    pub fn num_precompile_signatures(&self) -> u64 {
+        self.instructions
+            .iter()
+            .filter(|ix| ix.stack_height == 1)
+            .filter(|ix| {
+                self.get_pubkey(ix.program_id_index)
+                    .is_some_and(|program_id| PRECOMPILE_PROGRAM_IDS.contains(&program_id))
+            })
+            .map(|ix| ix.data.first().copied().unwrap_or_default() as u64)
+            .sum()
+    }
+
+    /// Base fee paid for this transaction's signatures, including those verified by
+    /// precompiles
+    pub fn base_fee(&self) -> u64 {
+        (self.num_signatures as u64 + self.num_precompile_signatures()) * LAMPORTS_PER_SIGNATURE
+    }
+
+    /// Portion of the fee paid above the base fee
+    pub fn priority_fee(&self) -> u64 {
+        self.fee.saturating_sub(self.base_fee())
+    }
+
+    /// Compute unit limit the runtime applied to this transaction. Without an explicit
+    /// request, each top level instruction is given the builtin limit if it invokes a
+    /// builtin program, otherwise the default instruction limit.
+    pub fn effective_compute_unit_limit(&self) -> u32 {
+        if let Some(limit) = self.compute_unit_limit {
+            return limit.min(MAX_COMPUTE_UNIT_LIMIT);
+        }
+
+        self.instructions
+            .iter()
+            .filter(|ix| ix.stack_height == 1)
+            .map(|ix| match self.get_pubkey(ix.program_id_index) {
+                Some(program_id) if BUILTIN_PROGRAM_IDS.contains(&program_id) => {
+                    MAX_BUILTIN_ALLOCATION_COMPUTE_UNIT_LIMIT
+                }
+                _ => DEFAULT_INSTRUCTION_COMPUTE_UNIT_LIMIT,
+            })
+            .fold(0u32, |limit, ix_limit| limit.saturating_add(ix_limit))
+            .min(MAX_COMPUTE_UNIT_LIMIT)
+    }
+
+    /// Priority fee implied by the requested compute unit price and limit
+    pub fn compute_budget_priority_fee(&self) -> u64 {
+        let price = self.compute_unit_price.unwrap_or_default() as u128;
+        let limit = self.effective_compute_unit_limit() as u128;
+
+        let fee = (price * limit + MICRO_LAMPORTS_PER_LAMPORT - 1) / MICRO_LAMPORTS_PER_LAMPORT;
+        fee.min(u64::MAX as u128) as u64
+    }
+
+    /// Change in lamports of the account at the given index, including fees paid
+    pub fn native_balance_change(&self, index: usize) -> Option<i128> {
+        let pre = *self.pre_balances.get(index)?;
+        let post = *self.post_balances.get(index)?;
+
+        Some(post as i128 - pre as i128)
+    }
+
+    /// Non-zero lamport balance changes of every account, in account order
+    pub fn native_balance_changes(&self) -> Vec<(Pubkey, i128)> {
+        (0..self.accounts.len())
+            .filter_map(|index| {
+                let change = self.native_balance_change(index)?;
+                (change != 0).then_some((self.accounts[index].pubkey, change))
+            })
+            .collect()
+    }
+
+    /// Non-zero SOL balance changes with wrapped SOL folded into native SOL.
+    ///
+    /// Wrapped SOL token accounts are not reported themselves. Instead, changes in their
+    /// token amount are credited to their owner, so wrapping, unwrapping and closing a
+    /// wrapped account only show up as the rent paid or reclaimed.
+    pub fn sol_balance_changes(&self) -> Vec<(Pubkey, i128)> {
+        let pre_wrapped = wrapped_sol_balances(self.pre_token_balances.as_deref());
+        let post_wrapped = wrapped_sol_balances(self.post_token_balances.as_deref());
+
+        let mut changes: Vec<(Pubkey, i128)> = Vec::new();
+
+        for (index, account) in self.accounts.iter().enumerate() {
+            let is_wrapped = pre_wrapped.contains_key(&index) || post_wrapped.contains_key(&index);
+            if is_wrapped {
+                continue;
+            }
+
+            if let Some(change) = self.native_balance_change(index) {
+                add_balance_change(&mut changes, account.pubkey, change);
+            }
+        }
+
+        let wrapped_indexes: HashSet<usize> = pre_wrapped
+            .keys()
+            .chain(post_wrapped.keys())
+            .copied()
+            .collect();
+
+        for index in wrapped_indexes {
+            let pre = pre_wrapped.get(&index);
+            let post = post_wrapped.get(&index);
+
+            let owner = match post.or(pre).and_then(|(owner, _)| *owner) {
+                Some(owner) => owner,
+                None => continue,
+            };
+
+            let pre_amount = pre.map_or(0, |(_, amount)| *amount);
+            let post_amount = post.map_or(0, |(_, amount)| *amount);
+            add_balance_change(
+                &mut changes,
+                owner,
+                post_amount as i128 - pre_amount as i128,
+            );
         }
+
+        changes.retain(|(_, change)| *change != 0);
+        changes
+    }
+
+    /// SOL balance change of a single account, see [`Self::sol_balance_changes`]
+    pub fn sol_balance_change(&self, pubkey: &Pubkey) -> i128 {
+        self.sol_balance_changes()
+            .into_iter()
+            .find(|(key, _)| key == pubkey)
+            .map_or(0, |(_, change)| change)
     }
 
     /// If inner instructions exist for the given index, remove them from the list and return them
@@ -132,48 +493,34 @@ impl ClassifiableTransaction {
         None
     }
 
-    pub fn get_pubkey(&self, index: u8) -> Option<Pubkey> {
-        let mut index = index as usize;
-
-        if index < self.static_keys.len() {
-            return Some(self.static_keys[index]);
-        }
-
-        if let Some(loaded_addresses) = &self.loaded_addresses {
-            index -= self.static_keys.len();
-
-            if index < loaded_addresses.writable.len() {
-                return Some(loaded_addresses.writable[index]);
-            }
-
-            index -= loaded_addresses.writable.len();
-
-            if index < loaded_addresses.readonly.len() {
-                return Some(loaded_addresses.readonly[index]);
-            }
-        }
-
-        None
+    /// All resolved account keys, in the order instruction account indexes refer to them
+    pub fn accounts(&self) -> &[AccountMeta] {
+        &self.accounts
     }
 
-    pub fn get_index_for_pubkey(&self, pubkey: &Pubkey) -> Option<u8> {
-        if let Some(index) = self.static_keys.iter().position(|k| k == pubkey) {
-            return Some(index as u8);
-        }
+    pub fn get_account(&self, index: u8) -> Option<&AccountMeta> {
+        self.accounts.get(index as usize)
+    }
 
-        if let Some(loaded_addresses) = &self.loaded_addresses {
-            if let Some(index) = loaded_addresses.writable.iter().position(|k| k == pubkey) {
-                return Some((index + self.static_keys.len()) as u8);
-            }
+    /// Resolve the account at `position` in an instruction's account list
+    pub fn get_instruction_account(
+        &self,
+        ix: &ClassifiableInstruction,
+        position: usize,
+    ) -> Option<&AccountMeta> {
+        ix.accounts
+            .get(position)
+            .and_then(|index| self.get_account(*index))
+    }
 
-            if let Some(index) = loaded_addresses.readonly.iter().position(|k| k == pubkey) {
-                return Some(
-                    (index + self.static_keys.len() + loaded_addresses.writable.len()) as u8,
-                );
-            }
-        }
+    pub fn get_pubkey(&self, index: u8) -> Option<Pubkey> {
+        self.get_account(index).map(|account| account.pubkey)
+    }
 
-        None
+    pub fn get_index_for_pubkey(&self, pubkey: &Pubkey) -> Option<usize> {
+        self.accounts
+            .iter()
+            .position(|account| &account.pubkey == pubkey)
     }
 
     pub fn get_pre_token_balance(
@@ -186,7 +533,7 @@ impl ClassifiableTransaction {
 
         if let Some(pre_balances) = &self.pre_token_balances {
             for balance in pre_balances {
-                if balance.account_index == index {
+                if balance.account_index as usize == index {
                     return Ok(balance.clone());
                 }
             }
@@ -215,7 +562,7 @@ impl ClassifiableTransaction {
 
         if let Some(post_balances) = &self.post_token_balances {
             for balance in post_balances {
-                if balance.account_index == index {
+                if balance.account_index as usize == index {
                     return Ok(balance.clone());
                 }
             }
@@ -234,6 +581,52 @@ impl ClassifiableTransaction {
         ))
     }
 
+    /// Transfer fee withheld from a transfer of `amount` into `destination`, for
+    /// instructions which do not state their fee. Only Token-2022 accounts withhold
+    /// fees, and the fee is inferred from how much less than `amount` the destination's
+    /// balance grew. Zero when any other Token-2022 instruction uses the destination,
+    /// such as a pool vault which also pays out, since its balance change then includes
+    /// more than this transfer.
+    pub fn inferred_transfer_fee(&self, destination: &Pubkey, amount: u64) -> u64 {
+        let post = match self.get_post_token_balance(destination) {
+            Ok(post) => post,
+            Err(_) => return 0,
+        };
+
+        let program_id = Option::<String>::from(post.program_id.clone());
+        if program_id != Some(TOKEN_2022_PROGRAM_ID.to_string()) {
+            return 0;
+        }
+
+        if self.token_2022_instructions_using(destination) > 1 {
+            return 0;
+        }
+
+        let pre_amount = self
+            .get_pre_token_balance(destination)
+            .ok()
+            .and_then(|pre| pre.ui_token_amount.amount.parse::<u64>().ok())
+            .unwrap_or_default();
+
+        match post.ui_token_amount.amount.parse::<u64>() {
+            Ok(post_amount) => withheld_fee(amount, pre_amount, post_amount),
+            Err(_) => 0,
+        }
+    }
+
+    /// Number of Token-2022 instructions, top level or inner, which take `account`
+    fn token_2022_instructions_using(&self, account: &Pubkey) -> usize {
+        self.instructions
+            .iter()
+            .filter(|ix| self.get_pubkey(ix.program_id_index) == Some(TOKEN_2022_PROGRAM_ID))
+            .filter(|ix| {
+                ix.accounts
+                    .iter()
+                    .any(|index| self.get_pubkey(*index).as_ref() == Some(account))
+            })
+            .count()
+    }
+
     pub fn get_mint_for_token_account(&self, pubkey: &Pubkey) -> Result<Pubkey, anyhow::Error> {
         if let Some(created_tokens) = &self.created_tokens {
             if let Some(mint) = created_tokens.get(pubkey) {
@@ -246,3 +639,316 @@ impl ClassifiableTransaction {
         Ok(Pubkey::from_str(&balance.mint).unwrap())
     }
 }
+
+/// Portion of `amount` which did not reach an account whose balance went from `pre` to
+/// `post`. Zero when the balance grew by at least `amount` or shrank, as the change was
+/// not caused by this transfer alone.
+fn withheld_fee(amount: u64, pre: u64, post: u64) -> u64 {
+    let received = post as i128 - pre as i128;
+    if received < 0 || received >= amount as i128 {
+        return 0;
+    }
+
+    amount - received as u64
+}
+
+/// Owner and amount of each wrapped SOL token account, keyed by account index
+fn wrapped_sol_balances(
+    balances: Option<&[UiTransactionTokenBalance]>,
+) -> HashMap<usize, (Option<Pubkey>, u64)> {
+    balances
+        .unwrap_or_default()
+        .iter()
+        .filter(|balance| balance.mint == NATIVE_MINT.to_string())
+        .filter_map(|balance| {
+            let amount = balance.ui_token_amount.amount.parse::<u64>().ok()?;
+            let owner = Option::<String>::from(balance.owner.clone())
+                .and_then(|owner| Pubkey::from_str(&owner).ok());
+
+            Some((balance.account_index as usize, (owner, amount)))
+        })
+        .collect()
+}
+
+fn add_balance_change(changes: &mut Vec<(Pubkey, i128)>, pubkey: Pubkey, change: i128) {
+    match changes.iter_mut().find(|(key, _)| *key == pubkey) {
+        Some((_, total)) => *total += change,
+        None => changes.push((pubkey, change)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_sdk::{
+        compute_budget::ComputeBudgetInstruction,
+        instruction::Instruction,
+        message::{Message, VersionedMessage},
+        system_instruction,
+    };
+
+    use super::*;
+
+    fn transaction(instructions: &[Instruction], fee: u64) -> ClassifiableTransaction {
+        let payer = Pubkey::new_unique();
+        let meta: UiTransactionStatusMeta = serde_json::from_value(serde_json::json!({
+            "err": null,
+            "status": { "Ok": null },
+            "fee": fee,
+            "preBalances": [],
+            "postBalances": [],
+        }))
+        .unwrap();
+
+        ClassifiableTransaction::new(
+            VersionedTransaction {
+                signatures: vec![Signature::new_unique()],
+                message: VersionedMessage::Legacy(Message::new(instructions, Some(&payer))),
+            },
+            meta,
+        )
+    }
+
+    fn program_instruction(program_id: Pubkey, data: &[u8]) -> Instruction {
+        Instruction::new_with_bytes(program_id, data, vec![])
+    }
+
+    #[test]
+    fn base_fee_includes_precompile_signatures() {
+        let txn = transaction(
+            &[
+                program_instruction(solana_sdk::ed25519_program::ID, &[2, 0]),
+                program_instruction(solana_sdk::secp256k1_program::ID, &[1]),
+            ],
+            20_000,
+        );
+
+        assert_eq!(txn.num_precompile_signatures(), 3);
+        assert_eq!(txn.base_fee(), 4 * LAMPORTS_PER_SIGNATURE);
+        assert_eq!(txn.priority_fee(), 0);
+    }
+
+    #[test]
+    fn default_compute_unit_limit_counts_builtins_separately() {
+        let payer = Pubkey::new_unique();
+        let txn = transaction(
+            &[
+                ComputeBudgetInstruction::set_compute_unit_price(1),
+                system_instruction::transfer(&payer, &Pubkey::new_unique(), 1),
+                program_instruction(Pubkey::new_unique(), &[]),
+            ],
+            5000,
+        );
+
+        assert_eq!(
+            txn.effective_compute_unit_limit(),
+            2 * MAX_BUILTIN_ALLOCATION_COMPUTE_UNIT_LIMIT + DEFAULT_INSTRUCTION_COMPUTE_UNIT_LIMIT
+        );
+    }
+
+    #[test]
+    fn requested_compute_unit_limit_is_capped() {
+        let mut txn = transaction(&[program_instruction(Pubkey::new_unique(), &[])], 5000);
+
+        txn.compute_unit_limit = Some(10_000);
+        assert_eq!(txn.effective_compute_unit_limit(), 10_000);
+
+        txn.compute_unit_limit = Some(u32::MAX);
+        assert_eq!(txn.effective_compute_unit_limit(), MAX_COMPUTE_UNIT_LIMIT);
+    }
+
+    #[test]
+    fn compute_budget_priority_fee_rounds_up() {
+        let mut txn = transaction(&[program_instruction(Pubkey::new_unique(), &[])], 5000);
+        txn.compute_unit_limit = Some(300_000);
+
+        txn.compute_unit_price = Some(1);
+        assert_eq!(txn.compute_budget_priority_fee(), 1);
+
+        txn.compute_unit_price = Some(10_000);
+        assert_eq!(txn.compute_budget_priority_fee(), 3000);
+
+        txn.compute_unit_price = None;
+        assert_eq!(txn.compute_budget_priority_fee(), 0);
+    }
+
+    /// Token-2022 transfers between accounts, with the destination of the first transfer
+    /// holding `pre` tokens before and `post` after
+    fn token_2022_transaction(
+        transfers: &[(Pubkey, Pubkey)],
+        pre: u64,
+        post: u64,
+    ) -> ClassifiableTransaction {
+        let payer = Pubkey::new_unique();
+        let instructions = transfers
+            .iter()
+            .map(|(source, destination)| {
+                Instruction::new_with_bytes(
+                    TOKEN_2022_PROGRAM_ID,
+                    &[],
+                    vec![
+                        solana_sdk::instruction::AccountMeta::new(*source, false),
+                        solana_sdk::instruction::AccountMeta::new(*destination, false),
+                        solana_sdk::instruction::AccountMeta::new_readonly(payer, true),
+                    ],
+                )
+            })
+            .collect::<Vec<_>>();
+        let message = Message::new(&instructions, Some(&payer));
+
+        let destination = transfers[0].1;
+        let account_index = message
+            .account_keys
+            .iter()
+            .position(|key| *key == destination)
+            .unwrap();
+        let balance = |amount: u64| {
+            serde_json::json!([{
+                "accountIndex": account_index,
+                "mint": Pubkey::new_unique().to_string(),
+                "uiTokenAmount": {
+                    "amount": amount.to_string(),
+                    "decimals": 0,
+                    "uiAmount": amount as f64,
+                    "uiAmountString": amount.to_string(),
+                },
+                "owner": payer.to_string(),
+                "programId": TOKEN_2022_PROGRAM_ID.to_string(),
+            }])
+        };
+
+        let meta: UiTransactionStatusMeta = serde_json::from_value(serde_json::json!({
+            "err": null,
+            "status": { "Ok": null },
+            "fee": 5000,
+            "preBalances": [],
+            "postBalances": [],
+            "preTokenBalances": balance(pre),
+            "postTokenBalances": balance(post),
+        }))
+        .unwrap();
+
+        ClassifiableTransaction::new(
+            VersionedTransaction {
+                signatures: vec![Signature::new_unique()],
+                message: VersionedMessage::Legacy(message),
+            },
+            meta,
+        )
+    }
+
+    #[test]
+    fn transfer_fee_is_inferred_from_destination_balance() {
+        let (source, destination) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let txn = token_2022_transaction(&[(source, destination)], 500, 1_490);
+
+        assert_eq!(txn.inferred_transfer_fee(&destination, 1_000), 10);
+    }
+
+    #[test]
+    fn transfer_fee_is_not_inferred_when_destination_also_sends() {
+        // A pool vault receives 1000 less a 10 fee and pays out 300 in the same swap
+        let (user, vault) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let txn = token_2022_transaction(&[(user, vault), (vault, user)], 500, 1_190);
+
+        assert_eq!(txn.inferred_transfer_fee(&vault, 1_000), 0);
+    }
+
+    #[test]
+    fn durable_nonce_is_advanced_by_first_instruction() {
+        let (nonce_account, authority) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let advance = system_instruction::advance_nonce_account(&nonce_account, &authority);
+        let transfer = system_instruction::transfer(&authority, &Pubkey::new_unique(), 1);
+
+        let txn = transaction(&[advance.clone(), transfer.clone()], 5000);
+        assert_eq!(txn.nonce_account, Some(nonce_account));
+        assert!(txn.uses_durable_nonce());
+
+        let txn = transaction(&[transfer, advance], 5000);
+        assert_eq!(txn.nonce_account, None);
+        assert!(!txn.uses_durable_nonce());
+    }
+
+    fn wrapped_sol_balance(index: u8, owner: &Pubkey, amount: u64) -> serde_json::Value {
+        serde_json::json!({
+            "accountIndex": index,
+            "mint": NATIVE_MINT.to_string(),
+            "owner": owner.to_string(),
+            "uiTokenAmount": {
+                "amount": amount.to_string(),
+                "decimals": 9,
+                "uiAmount": null,
+                "uiAmountString": "0",
+            },
+        })
+    }
+
+    /// Payer wrapping 1 SOL into a new token account holding 0.002 SOL of rent
+    fn wrapping_transaction() -> (ClassifiableTransaction, Pubkey, Pubkey) {
+        let (payer, token_account) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let meta: UiTransactionStatusMeta = serde_json::from_value(serde_json::json!({
+            "err": null,
+            "status": { "Ok": null },
+            "fee": 5_000,
+            "preBalances": [10_000_000_000u64, 0, 1],
+            "postBalances": [8_997_995_000u64, 1_002_000_000, 1],
+            "preTokenBalances": [],
+            "postTokenBalances": [wrapped_sol_balance(1, &payer, 1_000_000_000)],
+        }))
+        .unwrap();
+
+        let txn = ClassifiableTransaction::new(
+            VersionedTransaction {
+                signatures: vec![Signature::new_unique()],
+                message: VersionedMessage::Legacy(Message::new(
+                    &[system_instruction::transfer(
+                        &payer,
+                        &token_account,
+                        1_002_000_000,
+                    )],
+                    Some(&payer),
+                )),
+            },
+            meta,
+        );
+
+        (txn, payer, token_account)
+    }
+
+    #[test]
+    fn native_balance_changes_are_raw_lamport_changes() {
+        let (txn, payer, token_account) = wrapping_transaction();
+
+        assert_eq!(
+            txn.native_balance_changes(),
+            vec![(payer, -1_002_005_000), (token_account, 1_002_000_000)]
+        );
+    }
+
+    #[test]
+    fn sol_balance_changes_fold_wrapped_sol_into_owner() {
+        let (txn, payer, token_account) = wrapping_transaction();
+
+        // Only the fee and the token account's rent leave the payer
+        assert_eq!(txn.sol_balance_changes(), vec![(payer, -2_005_000)]);
+        assert_eq!(txn.sol_balance_change(&payer), -2_005_000);
+        assert_eq!(txn.sol_balance_change(&token_account), 0);
+    }
+
+    #[test]
+    fn withheld_fee_is_zero_for_full_receipt() {
+        assert_eq!(withheld_fee(1_000, 500, 1_500), 0);
+    }
+
+    #[test]
+    fn withheld_fee_is_the_amount_not_received() {
+        assert_eq!(withheld_fee(1_000, 500, 1_490), 10);
+        assert_eq!(withheld_fee(1_000, 0, 0), 1_000);
+    }
+
+    #[test]
+    fn withheld_fee_ignores_shrinking_balances() {
+        // The destination also sent tokens in the transaction, so the delta says nothing
+        // about the fee
+        assert_eq!(withheld_fee(1_000, 5_000, 4_000), 0);
+    }
+}
@@ -22,6 +22,10 @@ pub struct ClassifiableInstruction {
     pub accounts: Vec<u8>,
     pub data: Vec<u8>,
     pub stack_height: u32,
+
+    /// Compute units consumed by this instruction, including any CPIs it made.
+    /// Parsed from the transaction logs, so only present for programs that log consumption.
+    pub compute_units_consumed: Option<u64>,
 }
 
 impl ClassifiableInstruction {
@@ -31,6 +35,7 @@ impl ClassifiableInstruction {
             accounts: ix.accounts,
             data: ix.data,
             stack_height,
+            compute_units_consumed: None,
         }
     }
 
@@ -54,6 +59,7 @@ impl ClassifiableInstruction {
             accounts: ix.accounts,
             data,
             stack_height,
+            compute_units_consumed: None,
         })
     }
 }
@@ -0,0 +1,164 @@
+/// A single program invocation recovered from a transaction's log messages.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoggedInvocation {
+    pub program_id: String,
+    pub stack_height: u32,
+    pub compute_units_consumed: Option<u64>,
+}
+
+/// Parse program invocations from transaction log messages, in execution order.
+///
+/// Invocations are returned in the same order as a flattened instruction list
+/// (top level instruction followed by its inner instructions). Builtin programs
+/// do not log compute unit consumption, so their entries will have `None`.
+/// Parsing stops if the runtime truncated the logs.
+pub fn parse_logged_invocations(log_messages: &[String]) -> Vec<LoggedInvocation> {
+    let mut invocations: Vec<LoggedInvocation> = Vec::new();
+    let mut stack: Vec<usize> = Vec::new();
+
+    for log in log_messages {
+        if log == "Log truncated" {
+            break;
+        }
+
+        let rest = match log.strip_prefix("Program ") {
+            Some(rest) => rest,
+            None => continue,
+        };
+
+        // "Program log:", "Program data:" and "Program return:" lines are written by
+        // programs, and may contain anything
+        let (program_id, rest) = match rest.split_once(' ') {
+            Some((program_id, _)) if program_id.ends_with(':') => continue,
+            Some(split) => split,
+            None => continue,
+        };
+
+        // "Program <id> invoke [<height>]"
+        if let Some(height) = rest
+            .strip_prefix("invoke [")
+            .and_then(|rest| rest.strip_suffix(']'))
+        {
+            let stack_height = match height.parse() {
+                Ok(height) => height,
+                Err(_) => continue,
+            };
+
+            stack.push(invocations.len());
+            invocations.push(LoggedInvocation {
+                program_id: program_id.to_string(),
+                stack_height,
+                compute_units_consumed: None,
+            });
+            continue;
+        }
+
+        // "Program <id> consumed <units> of <remaining> compute units"
+        if let Some(consumed) = rest.strip_prefix("consumed ") {
+            let units = consumed
+                .split_once(' ')
+                .and_then(|(units, _)| units.parse::<u64>().ok());
+
+            if let Some(&current) = stack.last() {
+                if invocations[current].program_id == program_id {
+                    invocations[current].compute_units_consumed = units;
+                }
+            }
+            continue;
+        }
+
+        // "Program <id> success" or "Program <id> failed: <reason>"
+        if rest == "success" || rest.starts_with("failed") {
+            stack.pop();
+        }
+    }
+
+    invocations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn logs(lines: &[&str]) -> Vec<String> {
+        lines.iter().map(|line| line.to_string()).collect()
+    }
+
+    #[test]
+    fn parses_nested_invocations_in_execution_order() {
+        let invocations = parse_logged_invocations(&logs(&[
+            "Program ComputeBudget111111111111111111111111111111 invoke [1]",
+            "Program ComputeBudget111111111111111111111111111111 success",
+            "Program JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4 invoke [1]",
+            "Program log: Instruction: Route",
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA invoke [2]",
+            "Program log: Instruction: Transfer",
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA consumed 4645 of 180000 compute units",
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA success",
+            "Program JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4 consumed 30000 of 199850 compute units",
+            "Program JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4 success",
+        ]));
+
+        assert_eq!(
+            invocations,
+            vec![
+                LoggedInvocation {
+                    program_id: "ComputeBudget111111111111111111111111111111".to_string(),
+                    stack_height: 1,
+                    compute_units_consumed: None,
+                },
+                LoggedInvocation {
+                    program_id: "JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4".to_string(),
+                    stack_height: 1,
+                    compute_units_consumed: Some(30000),
+                },
+                LoggedInvocation {
+                    program_id: "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA".to_string(),
+                    stack_height: 2,
+                    compute_units_consumed: Some(4645),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn failed_invocation_is_popped() {
+        let invocations = parse_logged_invocations(&logs(&[
+            "Program A111 invoke [1]",
+            "Program B111 invoke [2]",
+            "Program B111 consumed 100 of 1000 compute units",
+            "Program B111 failed: custom program error: 0x1",
+            "Program A111 consumed 500 of 1400 compute units",
+            "Program A111 failed: custom program error: 0x1",
+        ]));
+
+        assert_eq!(invocations.len(), 2);
+        assert_eq!(invocations[0].compute_units_consumed, Some(500));
+        assert_eq!(invocations[1].compute_units_consumed, Some(100));
+    }
+
+    #[test]
+    fn stops_at_truncated_logs() {
+        let invocations = parse_logged_invocations(&logs(&[
+            "Program A111 invoke [1]",
+            "Log truncated",
+            "Program B111 invoke [2]",
+        ]));
+
+        assert_eq!(invocations.len(), 1);
+        assert_eq!(invocations[0].program_id, "A111");
+        assert_eq!(invocations[0].compute_units_consumed, None);
+    }
+
+    #[test]
+    fn ignores_malformed_lines() {
+        let invocations = parse_logged_invocations(&logs(&[
+            "Program A111 invoke [one]",
+            "Program log: invoke [1]",
+            "Program data: AAAA",
+            "Program",
+        ]));
+
+        assert!(invocations.is_empty());
+    }
+}
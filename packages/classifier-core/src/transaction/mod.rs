@@ -1,7 +1,11 @@
+mod accounts;
 mod classifiable;
 mod instruction;
+mod logs;
 mod tags;
 
+pub use accounts::*;
 pub use classifiable::*;
 pub use instruction::*;
+pub use logs::*;
 pub use tags::*;
@@ -1,5 +1,7 @@
 use solana_sdk::pubkey::Pubkey;
 
+use crate::json::{base58_schema, object_schema, JsonSchema, ToJsonValue};
+
 #[derive(Debug, Clone)]
 pub enum TransactionTag {
     AtomicArbitrage(AtomicArbitrageTag),
@@ -7,14 +9,52 @@ pub enum TransactionTag {
 }
 
 impl TransactionTag {
-    pub fn to_json(&self) -> serde_json::Value {
+    pub const ATOMIC_ARBITRAGE: &'static str = "atomicArbitrage";
+    pub const SANDWICH_FRONTRUN: &'static str = "sandwichFrontrun";
+    pub const SANDWICH_VICTIM: &'static str = "sandwichVictim";
+    pub const SANDWICH_BACKRUN: &'static str = "sandwichBackrun";
+
+    /// `type` of the tag's JSON
+    pub fn type_name(&self) -> &'static str {
         match self {
-            TransactionTag::AtomicArbitrage(tag) => tag.to_json(),
-            TransactionTag::SandwichAttack(tag) => tag.to_json(),
+            TransactionTag::AtomicArbitrage(_) => Self::ATOMIC_ARBITRAGE,
+            TransactionTag::SandwichAttack(tag) => tag.type_name(),
         }
     }
 }
 
+impl ToJsonValue for TransactionTag {
+    fn to_json_value(&self) -> serde_json::Value {
+        match self {
+            TransactionTag::AtomicArbitrage(tag) => tag.to_json_value(),
+            TransactionTag::SandwichAttack(tag) => tag.to_json_value(),
+        }
+    }
+}
+
+impl JsonSchema for TransactionTag {
+    fn json_schema() -> serde_json::Value {
+        let mut variants = vec![AtomicArbitrageTag::json_schema()];
+        variants.extend(
+            SandwichAttackTag::json_schema()["oneOf"]
+                .as_array()
+                .cloned()
+                .unwrap_or_default(),
+        );
+
+        serde_json::json!({ "oneOf": variants })
+    }
+}
+
+/// Schema of a profit in raw token amounts. Profits may exceed the range of an i64, so
+/// they are always decimal strings, as in stored documents.
+fn profit_amount_schema() -> serde_json::Value {
+    serde_json::json!({
+        "type": "string",
+        "pattern": "^-?[0-9]+$",
+    })
+}
+
 #[derive(Debug, Clone)]
 pub struct AtomicArbitrageTag {
     pub mint: Pubkey,
@@ -22,17 +62,31 @@ pub struct AtomicArbitrageTag {
     pub address: Pubkey,
 }
 
-impl AtomicArbitrageTag {
-    pub fn to_json(&self) -> serde_json::Value {
+impl ToJsonValue for AtomicArbitrageTag {
+    fn to_json_value(&self) -> serde_json::Value {
         serde_json::json!({
-            "type": "atomicArbitrage",
-            "mint": self.mint.to_string(),
-            "profitAmount": self.profit_amount,
-            "address": self.address.to_string(),
+            "type": TransactionTag::ATOMIC_ARBITRAGE,
+            "mint": self.mint.to_json_value(),
+            "profitAmount": self.profit_amount.to_string(),
+            "address": self.address.to_json_value(),
         })
     }
 }
 
+impl JsonSchema for AtomicArbitrageTag {
+    fn json_schema() -> serde_json::Value {
+        object_schema(vec![
+            (
+                "type",
+                serde_json::json!({ "const": TransactionTag::ATOMIC_ARBITRAGE }),
+            ),
+            ("mint", base58_schema("Arbitraged mint")),
+            ("profitAmount", profit_amount_schema()),
+            ("address", base58_schema("Arbitrageur")),
+        ])
+    }
+}
+
 // Todo: add signatures, timestamps
 #[derive(Debug, Clone)]
 pub enum SandwichAttackTag {
@@ -55,7 +109,18 @@ pub enum SandwichAttackTag {
 }
 
 impl SandwichAttackTag {
-    pub fn to_json(&self) -> serde_json::Value {
+    /// `type` of the tag's JSON
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            SandwichAttackTag::Frontrun { .. } => TransactionTag::SANDWICH_FRONTRUN,
+            SandwichAttackTag::Victim { .. } => TransactionTag::SANDWICH_VICTIM,
+            SandwichAttackTag::Backrun { .. } => TransactionTag::SANDWICH_BACKRUN,
+        }
+    }
+}
+
+impl ToJsonValue for SandwichAttackTag {
+    fn to_json_value(&self) -> serde_json::Value {
         match self {
             SandwichAttackTag::Frontrun {
                 token_bought,
@@ -63,10 +128,10 @@ impl SandwichAttackTag {
                 attacker_pubkey,
             } => {
                 serde_json::json!({
-                    "type": "sandwich_frontrun",
-                    "tokenBought": token_bought.to_string(),
-                    "amount": amount,
-                    "attackerPubkey": attacker_pubkey.to_string(),
+                    "type": self.type_name(),
+                    "tokenBought": token_bought.to_json_value(),
+                    "amount": amount.to_json_value(),
+                    "attackerPubkey": attacker_pubkey.to_json_value(),
                 })
             }
             SandwichAttackTag::Victim {
@@ -75,10 +140,10 @@ impl SandwichAttackTag {
                 victim_pubkey,
             } => {
                 serde_json::json!({
-                    "type": "sandwich_victim",
-                    "tokenBought": token_bought.to_string(),
-                    "amount": amount,
-                    "victimPubkey": victim_pubkey.to_string(),
+                    "type": self.type_name(),
+                    "tokenBought": token_bought.to_json_value(),
+                    "amount": amount.to_json_value(),
+                    "victimPubkey": victim_pubkey.to_json_value(),
                 })
             }
             SandwichAttackTag::Backrun {
@@ -88,13 +153,86 @@ impl SandwichAttackTag {
                 profit_amount,
             } => {
                 serde_json::json!({
-                    "type": "sandwich_backrun",
-                    "tokenSold": token_sold.to_string(),
-                    "amount": amount,
-                    "attackerPubkey": attacker_pubkey.to_string(),
-                    "profitAmount": profit_amount,
+                    "type": self.type_name(),
+                    "tokenSold": token_sold.to_json_value(),
+                    "amount": amount.to_json_value(),
+                    "attackerPubkey": attacker_pubkey.to_json_value(),
+                    "profitAmount": profit_amount.to_string(),
                 })
             }
         }
     }
 }
+
+impl JsonSchema for SandwichAttackTag {
+    fn json_schema() -> serde_json::Value {
+        serde_json::json!({
+            "oneOf": [
+                object_schema(vec![
+                    ("type", serde_json::json!({ "const": TransactionTag::SANDWICH_FRONTRUN })),
+                    ("tokenBought", Pubkey::json_schema()),
+                    ("amount", u64::json_schema()),
+                    ("attackerPubkey", Pubkey::json_schema()),
+                ]),
+                object_schema(vec![
+                    ("type", serde_json::json!({ "const": TransactionTag::SANDWICH_VICTIM })),
+                    ("tokenBought", Pubkey::json_schema()),
+                    ("amount", u64::json_schema()),
+                    ("victimPubkey", Pubkey::json_schema()),
+                ]),
+                object_schema(vec![
+                    ("type", serde_json::json!({ "const": TransactionTag::SANDWICH_BACKRUN })),
+                    ("tokenSold", Pubkey::json_schema()),
+                    ("amount", u64::json_schema()),
+                    ("attackerPubkey", Pubkey::json_schema()),
+                    ("profitAmount", profit_amount_schema()),
+                ]),
+            ],
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_type_is_type_name() {
+        let pubkey = Pubkey::new_unique();
+        let tags = [
+            TransactionTag::AtomicArbitrage(AtomicArbitrageTag {
+                mint: pubkey,
+                profit_amount: 1,
+                address: pubkey,
+            }),
+            TransactionTag::SandwichAttack(SandwichAttackTag::Frontrun {
+                token_bought: pubkey,
+                amount: 1,
+                attacker_pubkey: pubkey,
+            }),
+            TransactionTag::SandwichAttack(SandwichAttackTag::Victim {
+                token_bought: pubkey,
+                amount: 1,
+                victim_pubkey: pubkey,
+            }),
+            TransactionTag::SandwichAttack(SandwichAttackTag::Backrun {
+                token_sold: pubkey,
+                amount: 1,
+                attacker_pubkey: pubkey,
+                profit_amount: 1,
+            }),
+        ];
+
+        let schema_types = TransactionTag::json_schema()["oneOf"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|variant| variant["properties"]["type"]["const"].clone())
+            .collect::<Vec<_>>();
+
+        for (tag, schema_type) in tags.iter().zip(schema_types) {
+            assert_eq!(tag.to_json_value()["type"], tag.type_name());
+            assert_eq!(schema_type, tag.type_name());
+        }
+    }
+}
@@ -1,7 +1,10 @@
 use clap::{Parser, Subcommand};
 use tokio::runtime::Runtime;
 
-use crate::processor::{self, inspect::InspectArgs, serve::ServeArgs};
+use crate::processor::{
+    self, export::ExportArgs, inspect::InspectArgs, inspect_tx::InspectTxArgs, schema::SchemaArgs,
+    serve::ServeArgs,
+};
 
 #[derive(Parser, Debug)]
 pub struct CLI {
@@ -15,16 +18,31 @@ pub enum Command {
         #[clap(flatten)]
         args: InspectArgs,
     },
+    InspectTx {
+        #[clap(flatten)]
+        args: InspectTxArgs,
+    },
     Serve {
         #[clap(flatten)]
         args: ServeArgs,
     },
+    Export {
+        #[clap(flatten)]
+        args: ExportArgs,
+    },
+    Schema {
+        #[clap(flatten)]
+        args: SchemaArgs,
+    },
 }
 
 pub fn entry(cli: CLI) {
     match cli.command {
         Command::Inspect { args } => processor::inspect::entry(args),
+        Command::InspectTx { args } => processor::inspect_tx::entry(args),
         Command::Serve { args } => entry_async(processor::serve::entry(args)),
+        Command::Export { args } => processor::export::entry(args),
+        Command::Schema { args } => processor::schema::entry(args),
     }
 }
 
@@ -0,0 +1,167 @@
+use std::path::PathBuf;
+
+use clap::{Args, ValueEnum};
+use classifier_handler::{ClassifierConfig, ClassifierRegistry};
+use inspection::export::{
+    export_slot_range, ExportConfig, ExportFormat, ExportSources, TableExporter,
+};
+use inspection::lookup_tables::RpcLookupTableSource;
+use inspection::validators::ValidatorRegistry;
+use solana_client::rpc_client::RpcClient;
+
+#[derive(Args, Debug)]
+pub struct ExportArgs {
+    #[clap(long, help = "First slot to export.")]
+    start_slot: u64,
+
+    #[clap(long, help = "Last slot to export, inclusive.")]
+    end_slot: u64,
+
+    #[clap(long, help = "Directory to write the table files to.")]
+    out_dir: PathBuf,
+
+    #[clap(long, help = "File format of the tables.", default_value = "parquet")]
+    format: ExportFormatArg,
+
+    #[clap(
+        long,
+        help = "Slots covered by each file. Partitions start at multiples of this size.",
+        default_value = "1000"
+    )]
+    partition_slots: u64,
+
+    #[clap(
+        long,
+        help = "RPC URL to use for fetching data.",
+        default_value = "https://api.mainnet-beta.solana.com"
+    )]
+    rpc_url: String,
+
+    #[clap(
+        long,
+        help = "Leader schedule file (output of `solana leader-schedule`) used when a block has no fee reward."
+    )]
+    leader_schedule: Option<PathBuf>,
+
+    #[clap(long, help = "JSON file with validator names and vote accounts.")]
+    validator_metadata: Option<PathBuf>,
+
+    #[clap(
+        long,
+        help = "Directory of Anchor IDLs used to decode programs without a classifier."
+    )]
+    idl_dir: Option<PathBuf>,
+
+    #[clap(
+        long = "enable-classifier",
+        value_delimiter = ',',
+        help = "Only use these classifiers, by name or program id."
+    )]
+    enabled_classifiers: Option<Vec<String>>,
+
+    #[clap(
+        long = "disable-classifier",
+        value_delimiter = ',',
+        help = "Never use these classifiers, by name or program id."
+    )]
+    disabled_classifiers: Vec<String>,
+
+    #[clap(
+        long,
+        help = "Classify each block's transactions in parallel.",
+        default_value = "false"
+    )]
+    parallel_transactions: bool,
+}
+
+#[derive(ValueEnum, Debug, Clone)]
+pub enum ExportFormatArg {
+    Parquet,
+    Arrow,
+}
+
+impl From<ExportFormatArg> for ExportFormat {
+    fn from(format: ExportFormatArg) -> Self {
+        match format {
+            ExportFormatArg::Parquet => ExportFormat::Parquet,
+            ExportFormatArg::Arrow => ExportFormat::ArrowIpc,
+        }
+    }
+}
+
+pub fn entry(args: ExportArgs) {
+    if args.end_slot < args.start_slot {
+        eprintln!("End slot must not be before start slot");
+        return;
+    }
+
+    let validator_registry = match ValidatorRegistry::load(
+        args.leader_schedule.as_deref(),
+        args.validator_metadata.as_deref(),
+    ) {
+        Ok(registry) => registry,
+        Err(err) => {
+            eprintln!("Failed to load validator registry: {:?}", err);
+            return;
+        }
+    };
+
+    let classifier_registry = match ClassifierRegistry::load(
+        args.idl_dir.as_deref(),
+        &ClassifierConfig {
+            enabled: args.enabled_classifiers,
+            disabled: args.disabled_classifiers,
+        },
+    ) {
+        Ok(registry) => registry,
+        Err(err) => {
+            eprintln!("Failed to load classifiers: {:?}", err);
+            return;
+        }
+    };
+
+    let rpc_client = RpcClient::new(args.rpc_url.clone());
+    let lookup_tables = RpcLookupTableSource::new(args.rpc_url);
+
+    let exporter = TableExporter::new(ExportConfig {
+        out_dir: args.out_dir,
+        format: args.format.into(),
+        partition_slots: args.partition_slots,
+    });
+
+    println!("Exporting slots {} to {}", args.start_slot, args.end_slot);
+
+    let summary = match export_slot_range(
+        &ExportSources {
+            rpc_client: &rpc_client,
+            lookup_tables: Some(&lookup_tables),
+            classifier_registry: &classifier_registry,
+            validator_registry: &validator_registry,
+            parallel_transactions: args.parallel_transactions,
+        },
+        args.start_slot,
+        args.end_slot,
+        exporter,
+    ) {
+        Ok(summary) => summary,
+        Err(err) => {
+            eprintln!("Failed to export slots: {:?}", err);
+            return;
+        }
+    };
+
+    println!(
+        "Exported {} blocks to {} files ({} skipped slots)",
+        summary.blocks,
+        summary.files.len(),
+        summary.skipped_slots
+    );
+
+    if !summary.failed_slots.is_empty() {
+        eprintln!(
+            "Failed to fetch or classify {} slots: {:?}",
+            summary.failed_slots.len(),
+            summary.failed_slots
+        );
+    }
+}
@@ -1,13 +1,16 @@
 use clap::Args;
-use classifier_handler::classify_block;
-use inspection::database::mongo_client::{MongoDBClient, MongoDBClientConfig, MongoDBStage};
+use classifier_handler::{classify_block, ClassifierConfig, ClassifierRegistry};
+use inspection::database::block_store::{open_block_store, BlockStoreConfig};
+use inspection::database::mongo_client::MongoDBStage;
 use inspection::filtering::{post_process, PostProcessConfig};
+use inspection::lookup_tables::RpcLookupTableSource;
+use inspection::validators::ValidatorRegistry;
 use inspection::{database, label_tree};
 use solana_client::{rpc_client::RpcClient, rpc_config::RpcBlockConfig};
 use solana_transaction_status::{TransactionDetails, UiTransactionEncoding};
 use std::fs::{self, File};
 use std::io::Write;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::time::{SystemTime, UNIX_EPOCH};
 
 #[derive(Args, Debug)]
@@ -28,12 +31,84 @@ pub struct InspectArgs {
     )]
     rpc_url: String,
 
-    #[clap(long, help = "MongoDB URI to use for writing data.")]
-    mongo_uri: Option<String>,
+    #[clap(
+        long,
+        alias = "mongo-uri",
+        help = "MongoDB, PostgreSQL or SQLite URL to use for writing data."
+    )]
+    store_url: Option<String>,
+
+    #[clap(
+        long,
+        help = "Validate the block documents against the store without writing them.",
+        default_value = "false"
+    )]
+    dry_run: bool,
+
+    #[clap(
+        long,
+        help = "Leader schedule file (output of `solana leader-schedule`) used when the block has no fee reward."
+    )]
+    leader_schedule: Option<PathBuf>,
+
+    #[clap(long, help = "JSON file with validator names and vote accounts.")]
+    validator_metadata: Option<PathBuf>,
+
+    #[clap(
+        long,
+        help = "Directory of Anchor IDLs used to decode programs without a classifier."
+    )]
+    idl_dir: Option<PathBuf>,
+
+    #[clap(
+        long = "enable-classifier",
+        value_delimiter = ',',
+        help = "Only use these classifiers, by name or program id."
+    )]
+    enabled_classifiers: Option<Vec<String>>,
+
+    #[clap(
+        long = "disable-classifier",
+        value_delimiter = ',',
+        help = "Never use these classifiers, by name or program id."
+    )]
+    disabled_classifiers: Vec<String>,
+
+    #[clap(
+        long,
+        help = "Classify the block's transactions in parallel.",
+        default_value = "false"
+    )]
+    parallel_transactions: bool,
 }
 
 pub fn entry(args: InspectArgs) {
-    let rpc_client = RpcClient::new(args.rpc_url);
+    let validator_registry = match ValidatorRegistry::load(
+        args.leader_schedule.as_deref(),
+        args.validator_metadata.as_deref(),
+    ) {
+        Ok(registry) => registry,
+        Err(err) => {
+            eprintln!("Failed to load validator registry: {:?}", err);
+            return;
+        }
+    };
+
+    let classifier_registry = match ClassifierRegistry::load(
+        args.idl_dir.as_deref(),
+        &ClassifierConfig {
+            enabled: args.enabled_classifiers,
+            disabled: args.disabled_classifiers,
+        },
+    ) {
+        Ok(registry) => registry,
+        Err(err) => {
+            eprintln!("Failed to load classifiers: {:?}", err);
+            return;
+        }
+    };
+
+    let rpc_client = RpcClient::new(args.rpc_url.clone());
 
     let block = match rpc_client.get_block_with_config(
         args.slot,
@@ -59,7 +134,17 @@ pub fn entry(args: InspectArgs) {
         );
     }
 
-    let mut tree = match classify_block(args.slot, block, args.filter_transaction) {
+    // Only consulted for transactions whose meta is missing loaded addresses
+    let lookup_tables = RpcLookupTableSource::new(args.rpc_url);
+
+    let mut tree = match classify_block(
+        args.slot,
+        block,
+        args.filter_transaction,
+        Some(&lookup_tables),
+        &classifier_registry,
+        args.parallel_transactions,
+    ) {
         Ok(tree) => tree,
         Err(err) => {
             eprintln!("Failed to classify block: {:?}", err);
@@ -71,6 +156,7 @@ pub fn entry(args: InspectArgs) {
 
     let block_id = tree.root();
 
+    validator_registry.enrich_block(&mut tree);
     label_tree(&mut tree);
 
     post_process(
@@ -91,24 +177,26 @@ pub fn entry(args: InspectArgs) {
     };
 
     // Write block to beta DB (Should not be writing to prod with this CLI tool)
-    if let Some(mongo_uri) = args.mongo_uri {
+    if let Some(store_url) = args.store_url {
+        let dry_run = args.dry_run;
         let rt = tokio::runtime::Runtime::new().unwrap();
 
         rt.block_on(async {
-            let client = match MongoDBClient::new(MongoDBClientConfig {
-                uri: mongo_uri,
+            let store = match open_block_store(BlockStoreConfig {
+                url: store_url,
                 stage: MongoDBStage::Beta,
+                dry_run,
             })
             .await
             {
-                Ok(client) => client,
+                Ok(store) => store,
                 Err(err) => {
-                    eprintln!("Failed to create MongoDB client: {:?}", err);
+                    eprintln!("Failed to open block store: {:?}", err);
                     return;
                 }
             };
 
-            match client.write_block_documents(block_documents).await {
+            match store.write_block_documents(block_documents).await {
                 Ok(_) => {}
                 Err(err) => {
                     eprintln!("Failed to write block documents: {:?}", err);
@@ -0,0 +1,120 @@
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use actions::serialize_transaction;
+use clap::Args;
+use classifier_handler::{classify_encoded_transaction, ClassifierConfig, ClassifierRegistry};
+use inspection::label_transaction_tree;
+use inspection::lookup_tables::RpcLookupTableSource;
+use solana_client::{rpc_client::RpcClient, rpc_config::RpcTransactionConfig};
+use solana_sdk::signature::Signature;
+use solana_transaction_status::UiTransactionEncoding;
+
+#[derive(Args, Debug)]
+pub struct InspectTxArgs {
+    #[clap(help = "Signature of the transaction to inspect.")]
+    signature: String,
+
+    #[clap(
+        long,
+        help = "RPC URL to use for fetching data.",
+        default_value = "https://api.mainnet-beta.solana.com"
+    )]
+    rpc_url: String,
+
+    #[clap(
+        long,
+        help = "Print the action tree instead of JSON.",
+        default_value = "false"
+    )]
+    print_tree: bool,
+
+    #[clap(
+        long,
+        help = "Directory of Anchor IDLs used to decode programs without a classifier."
+    )]
+    idl_dir: Option<PathBuf>,
+
+    #[clap(
+        long = "enable-classifier",
+        value_delimiter = ',',
+        help = "Only use these classifiers, by name or program id."
+    )]
+    enabled_classifiers: Option<Vec<String>>,
+
+    #[clap(
+        long = "disable-classifier",
+        value_delimiter = ',',
+        help = "Never use these classifiers, by name or program id."
+    )]
+    disabled_classifiers: Vec<String>,
+}
+
+pub fn entry(args: InspectTxArgs) {
+    let signature = match Signature::from_str(&args.signature) {
+        Ok(signature) => signature,
+        Err(err) => {
+            eprintln!("Invalid signature: {:?}", err);
+            return;
+        }
+    };
+
+    let classifier_registry = match ClassifierRegistry::load(
+        args.idl_dir.as_deref(),
+        &ClassifierConfig {
+            enabled: args.enabled_classifiers,
+            disabled: args.disabled_classifiers,
+        },
+    ) {
+        Ok(registry) => registry,
+        Err(err) => {
+            eprintln!("Failed to load classifiers: {:?}", err);
+            return;
+        }
+    };
+
+    let rpc_client = RpcClient::new(args.rpc_url.clone());
+
+    let txn = match rpc_client.get_transaction_with_config(
+        &signature,
+        RpcTransactionConfig {
+            encoding: Some(UiTransactionEncoding::Base64),
+            commitment: None,
+            max_supported_transaction_version: Some(0),
+        },
+    ) {
+        Ok(txn) => txn,
+        Err(err) => {
+            eprintln!("Failed to get transaction: {:?}", err);
+            return;
+        }
+    };
+
+    eprintln!(
+        "Inspecting transaction {} from slot {}",
+        signature, txn.slot
+    );
+
+    // Only consulted if the meta is missing loaded addresses
+    let lookup_tables = RpcLookupTableSource::new(args.rpc_url);
+
+    let mut tree = match classify_encoded_transaction(
+        txn.transaction,
+        Some(&lookup_tables),
+        &classifier_registry,
+    ) {
+        Ok(tree) => tree,
+        Err(err) => {
+            eprintln!("Failed to classify transaction: {:?}", err);
+            return;
+        }
+    };
+
+    label_transaction_tree(&mut tree);
+
+    if args.print_tree {
+        println!("{}", tree);
+    } else {
+        println!("{:#}", serialize_transaction(&tree, tree.root()));
+    }
+}
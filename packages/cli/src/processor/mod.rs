@@ -1,2 +1,5 @@
+pub mod export;
 pub mod inspect;
+pub mod inspect_tx;
+pub mod schema;
 pub mod serve;
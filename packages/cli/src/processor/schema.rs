@@ -0,0 +1,18 @@
+use std::{fs, path::PathBuf};
+
+use clap::Args;
+
+#[derive(Args, Debug)]
+pub struct SchemaArgs {
+    #[clap(long, help = "Write the schema to this file instead of stdout.")]
+    out: Option<PathBuf>,
+}
+
+pub fn entry(args: SchemaArgs) {
+    let schema = format!("{:#}", actions::output_schema());
+
+    match args.out {
+        Some(path) => fs::write(&path, schema).expect("Failed to write schema"),
+        None => println!("{}", schema),
+    }
+}
@@ -1,4 +1,7 @@
+use std::path::PathBuf;
+
 use clap::{Args, ValueEnum};
+use classifier_handler::ClassifierConfig;
 use time_machine_api::{TimeMachineServer, TimeMachineServerConfig, TimeMachineStage};
 
 #[derive(Args, Debug)]
@@ -30,8 +33,155 @@ pub struct ServeArgs {
     )]
     pub stage: TimeMachineStageArg,
 
-    #[clap(long, env = "MONGO_URI", help = "MongoDB URI to use for storing data.")]
-    pub mongo_uri: String,
+    #[clap(
+        long,
+        env = "STORE_URL",
+        required_unless_present = "mongo_uri",
+        help = "MongoDB, PostgreSQL or SQLite URL to use for storing data, e.g. sqlite://time-machine.db"
+    )]
+    pub store_url: Option<String>,
+
+    /// Deprecated name of the store URL, used when `STORE_URL` is not set
+    #[clap(long, env = "MONGO_URI", hide = true)]
+    pub mongo_uri: Option<String>,
+
+    #[clap(
+        long,
+        env = "DRY_RUN",
+        help = "Validate indexed blocks against the store without writing them.",
+        default_value = "false"
+    )]
+    pub dry_run: bool,
+
+    #[clap(
+        long,
+        env = "LEADER_SCHEDULE",
+        help = "Leader schedule file (output of `solana leader-schedule`) used when a block has no fee reward."
+    )]
+    pub leader_schedule: Option<PathBuf>,
+
+    #[clap(
+        long,
+        env = "VALIDATOR_METADATA",
+        help = "JSON file with validator names and vote accounts."
+    )]
+    pub validator_metadata: Option<PathBuf>,
+
+    #[clap(
+        long,
+        env = "IDL_DIR",
+        help = "Directory of Anchor IDLs used to decode programs without a classifier."
+    )]
+    pub idl_dir: Option<PathBuf>,
+
+    #[clap(
+        long = "enable-classifier",
+        env = "ENABLED_CLASSIFIERS",
+        value_delimiter = ',',
+        help = "Only use these classifiers, by name or program id."
+    )]
+    pub enabled_classifiers: Option<Vec<String>>,
+
+    #[clap(
+        long = "disable-classifier",
+        env = "DISABLED_CLASSIFIERS",
+        value_delimiter = ',',
+        help = "Never use these classifiers, by name or program id."
+    )]
+    pub disabled_classifiers: Vec<String>,
+
+    #[clap(
+        long,
+        env = "CLASSIFIER_WORKERS",
+        help = "Number of threads classifying blocks. Defaults to the number of CPUs."
+    )]
+    pub classifier_workers: Option<usize>,
+
+    #[clap(
+        long,
+        env = "PARALLEL_TRANSACTIONS",
+        help = "Classify each block's transactions in parallel.",
+        default_value = "false"
+    )]
+    pub parallel_transactions: bool,
+
+    #[clap(
+        long,
+        env = "MAX_PENDING_BLOCKS",
+        help = "Maximum blocks being fetched or waiting for a classifier. Defaults to twice the number of classifier workers."
+    )]
+    pub max_pending_blocks: Option<usize>,
+
+    #[clap(
+        long,
+        env = "LIVE_INDEXING",
+        help = "Index blocks into MongoDB as they are confirmed.",
+        default_value = "false"
+    )]
+    pub live_indexing: bool,
+
+    #[clap(
+        long,
+        env = "GEYSER_ENDPOINT",
+        requires = "live_indexing",
+        help = "Yellowstone gRPC endpoint to stream live blocks from, instead of polling RPC."
+    )]
+    pub geyser_endpoint: Option<String>,
+
+    #[clap(
+        long,
+        env = "GEYSER_X_TOKEN",
+        requires = "geyser_endpoint",
+        help = "Token sent to the Yellowstone gRPC endpoint."
+    )]
+    pub geyser_x_token: Option<String>,
+
+    #[clap(
+        long,
+        env = "BACKFILL_START_SLOT",
+        requires = "backfill_end_slot",
+        help = "First slot to backfill into MongoDB. Backfills resume from their last checkpoint."
+    )]
+    pub backfill_start_slot: Option<u64>,
+
+    #[clap(
+        long,
+        env = "BACKFILL_END_SLOT",
+        requires = "backfill_start_slot",
+        help = "Last slot to backfill into MongoDB, inclusive."
+    )]
+    pub backfill_end_slot: Option<u64>,
+
+    #[clap(
+        long,
+        env = "CACHE_MAX_ENTRIES",
+        help = "Maximum number of classified blocks cached in memory.",
+        default_value = "1000"
+    )]
+    pub cache_max_entries: usize,
+
+    #[clap(
+        long,
+        env = "CACHE_MAX_BYTES",
+        help = "Maximum size in bytes of the classified blocks cached in memory.",
+        default_value = "536870912"
+    )]
+    pub cache_max_bytes: usize,
+
+    #[clap(
+        long,
+        env = "CACHE_DIR",
+        help = "Directory where classified blocks are persisted."
+    )]
+    pub cache_dir: Option<PathBuf>,
+
+    #[clap(
+        long,
+        env = "CACHE_DIR_MAX_BYTES",
+        help = "Maximum size in bytes of the classified blocks persisted to the cache directory. The oldest are removed first.",
+        default_value = "10737418240"
+    )]
+    pub cache_dir_max_bytes: u64,
 }
 
 #[derive(ValueEnum, Debug, Clone)]
@@ -48,12 +198,40 @@ impl Into<TimeMachineStage> for TimeMachineStageArg {
 }
 
 pub async fn entry(args: ServeArgs) {
+    let classifier_workers = args.classifier_workers.unwrap_or_else(|| {
+        std::thread::available_parallelism()
+            .map(|workers| workers.get())
+            .unwrap_or(1)
+    });
+
     let server_config = TimeMachineServerConfig {
         rpc_url: args.rpc_url,
         port: args.port.unwrap_or(8080),
         rpc_requests_per_second: args.rpc_rate_limit,
         stage: args.stage.into(),
-        mongo_uri: args.mongo_uri,
+        store_url: args
+            .store_url
+            .or(args.mongo_uri)
+            .expect("clap requires a store URL"),
+        dry_run: args.dry_run,
+        leader_schedule_path: args.leader_schedule,
+        validator_metadata_path: args.validator_metadata,
+        idl_dir: args.idl_dir,
+        classifier_config: ClassifierConfig {
+            enabled: args.enabled_classifiers,
+            disabled: args.disabled_classifiers,
+        },
+        classifier_workers,
+        parallel_transactions: args.parallel_transactions,
+        max_pending_blocks: args.max_pending_blocks.unwrap_or(classifier_workers * 2),
+        live_indexing: args.live_indexing,
+        geyser_endpoint: args.geyser_endpoint,
+        geyser_x_token: args.geyser_x_token,
+        backfill_slots: args.backfill_start_slot.zip(args.backfill_end_slot),
+        cache_max_entries: args.cache_max_entries,
+        cache_max_bytes: args.cache_max_bytes,
+        cache_dir: args.cache_dir,
+        cache_dir_max_bytes: args.cache_dir_max_bytes,
     };
 
     let server = match TimeMachineServer::new(server_config).await {
@@ -0,0 +1,170 @@
+use actions::{Action, Block, Vote};
+use classifier_core::{
+    document,
+    document::{Document, ToDocumentValue, Value},
+    ClassifiableTransaction,
+};
+
+pub trait DocumentSerialize {
+    fn metadata_document(&self) -> Option<Document> {
+        None
+    }
+}
+
+impl DocumentSerialize for Block {
+    fn metadata_document(&self) -> Option<Document> {
+        // Keyed by slot by the store
+        let mut document = document! {
+            "block_time": self.block_time,
+        };
+
+        if let Some(validator_pubkey) = &self.validator_pubkey {
+            document.insert(
+                "validator_pubkey",
+                validator_pubkey,
+            );
+        }
+
+        if let Some(validator_name) = &self.validator_name {
+            document.insert("validator_name", validator_name);
+        }
+
+        if let Some(validator_vote_account) = &self.validator_vote_account {
+            document.insert("validator_vote_account", validator_vote_account);
+        }
+
+        if let Some(leader_source) = self.leader_source {
+            document.insert("leader_source", leader_source.to_document_value());
+        }
+
+        if let Some(total_sandwiches) = self.total_sandwiches {
+            document.insert("total_sandwiches", total_sandwiches as i64);
+        }
+
+        if let Some(total_atomic_arbitrages) = self.total_atomic_arbitrages {
+            document.insert("total_atomic_arbitrages", total_atomic_arbitrages as i64);
+        }
+
+        if let Some(total_base_fees) = self.total_base_fees {
+            document.insert("total_base_fees", total_base_fees);
+        }
+
+        if let Some(total_priority_fees) = self.total_priority_fees {
+            document.insert("total_priority_fees", total_priority_fees);
+        }
+
+        if let Some(total_tips) = self.total_tips {
+            document.insert("total_tips", total_tips);
+        }
+
+        let lamport_totals = [
+            ("total_jito_tips", self.total_jito_tips),
+            ("total_bloxroute_tips", self.total_bloxroute_tips),
+            ("total_fees_burned", self.total_fees_burned),
+            ("total_validator_fees", self.total_validator_fees),
+        ];
+
+        for (key, total) in lamport_totals {
+            if let Some(total) = total {
+                document.insert(key, total);
+            }
+        }
+
+        if let Some(fee_reward) = self.fee_reward {
+            document.insert("fee_reward", fee_reward);
+        }
+
+        if let Some(reward_discrepancies) = &self.reward_discrepancies {
+            document.insert(
+                "reward_discrepancies",
+                reward_discrepancies.to_document_value(),
+            );
+        }
+
+        if let Some(total_compute_units) = self.total_compute_units {
+            document.insert("total_compute_units", total_compute_units as i64);
+        }
+
+        if let Some(program_compute_units) = &self.program_compute_units {
+            document.insert(
+                "program_compute_units",
+                program_compute_units.to_document_value(),
+            );
+        }
+
+        if let Some(mev_compute_units) = &self.mev_compute_units {
+            document.insert("mev_compute_units", mev_compute_units.to_document_value());
+        }
+
+        if let Some(vote_summary) = &self.vote_summary {
+            document.insert("vote_summary", vote_summary.to_document_value());
+        }
+
+        Some(document)
+    }
+}
+
+impl DocumentSerialize for Action {
+    fn metadata_document(&self) -> Option<Document> {
+        match self {
+            Action::Block(block) => block.metadata_document(),
+            Action::ClassifiableTransaction(tx) => tx.metadata_document(),
+            Action::Vote(vote) => vote.metadata_document(),
+            // Every other action stores its full document, tagged with its type
+            action => match action.to_document_value() {
+                Value::Document(document) => Some(document),
+                _ => None,
+            },
+        }
+    }
+}
+
+impl DocumentSerialize for ClassifiableTransaction {
+    fn metadata_document(&self) -> Option<Document> {
+        let mut document = document! {
+            "signature": self.signature,
+        };
+
+        if let Some(compute_units_consumed) = self.compute_units_consumed {
+            document.insert("compute_units_consumed", compute_units_consumed as i64);
+        }
+
+        if let Some(nonce_account) = &self.nonce_account {
+            document.insert("nonce_account", *nonce_account);
+        }
+
+        let sol_balance_changes = self
+            .sol_balance_changes()
+            .into_iter()
+            .map(|(account, change)| {
+                document! {
+                    "account": account,
+                    "change": change as i64,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        if !sol_balance_changes.is_empty() {
+            document.insert("sol_balance_changes", sol_balance_changes);
+        }
+
+        Some(document)
+    }
+}
+
+impl DocumentSerialize for Vote {
+    fn metadata_document(&self) -> Option<Document> {
+        let update = self.update();
+
+        Some(document! {
+            "type": "Vote",
+            "instruction": self.instruction_name(),
+            "vote_account": update.vote_account,
+            "vote_authority": update.vote_authority,
+            "slots": update.slots.iter().map(|slot| *slot as i64).collect::<Vec<_>>(),
+            "root": update.root.map(|root| root as i64),
+            "hash": update.hash.to_string(),
+            "timestamp": update.timestamp,
+        })
+    }
+}
@@ -0,0 +1,141 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use classifier_core::document::Document;
+use thiserror::Error;
+
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+use super::sql_store::SqlBlockStore;
+
+use super::{
+    document_builder::BlockDocuments,
+    mev_query::MevEventQuery,
+    mev_stats::StatsGranularity,
+    mongo_client::{MongoDBClient, MongoDBClientConfig, MongoDBStage},
+};
+
+#[derive(Debug, Error)]
+pub enum BlockStoreError {
+    #[error("MongoDB error: {0}")]
+    MongoDBError(#[from] mongodb::error::Error),
+
+    #[cfg(any(feature = "postgres", feature = "sqlite"))]
+    #[error("SQL error: {0}")]
+    SqlError(#[from] sqlx::Error),
+
+    #[error("Invalid stored document: {0}")]
+    InvalidDocument(#[from] serde_json::Error),
+
+    #[error("Document of slot {slot} is too large to store ({size} bytes)")]
+    DocumentTooLarge { slot: u64, size: usize },
+
+    #[error("Unsupported store URL scheme: {0}")]
+    UnsupportedScheme(String),
+
+    #[error("Store backend not enabled in this build: {0}")]
+    BackendDisabled(String),
+}
+
+pub type Result<T> = std::result::Result<T, BlockStoreError>;
+
+/// Storage of indexed blocks, MEV rollups and indexing checkpoints. Every backend is
+/// written from the same [`BlockDocuments`] and returns the same documents to queries.
+#[async_trait]
+pub trait BlockStore: Send + Sync {
+    /// Create the tables, collections and indexes used by queries, if they do not
+    /// exist yet
+    async fn create_schema(&self) -> Result<()>;
+
+    /// Replace everything stored for a block, and update its rollups
+    async fn write_block_documents(&self, block_documents: BlockDocuments) -> Result<()>;
+
+    /// Write several blocks, each replaced atomically. Backends may share a connection
+    /// or session between the blocks.
+    ///
+    /// The batch is all-or-nothing for the caller: on error, blocks before the failing
+    /// one may already be written, but every block of the batch must be treated as
+    /// failed and written again. Writes replace the block, so rewriting is safe.
+    async fn write_many_block_documents(&self, blocks: Vec<BlockDocuments>) -> Result<()> {
+        for block_documents in blocks {
+            self.write_block_documents(block_documents).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Transactions tagged with MEV matching the query
+    async fn find_mev_events(&self, query: &MevEventQuery) -> Result<Vec<Document>>;
+
+    /// Block document and the metadata of its non transaction actions, if the slot is
+    /// indexed
+    async fn find_block(&self, slot: u64) -> Result<Option<(Document, Vec<Document>)>>;
+
+    /// Rollups of one granularity, most recent first
+    async fn find_mev_stats(
+        &self,
+        granularity: StatsGranularity,
+        start: Option<i64>,
+        end: Option<i64>,
+        limit: i64,
+    ) -> Result<Vec<Document>>;
+
+    /// Slot a named indexing job should resume from, if it has recorded one
+    async fn read_checkpoint(&self, name: &str) -> Result<Option<u64>>;
+
+    /// Record the slot a named indexing job should resume from
+    async fn write_checkpoint(&self, name: &str, slot: u64) -> Result<()>;
+
+    /// Serialized API response of an indexed block, if one was written under the
+    /// current `SCHEMA_VERSION`
+    async fn read_block_json(&self, slot: u64) -> Result<Option<String>>;
+
+    /// Store the serialized API response of a block, so that API servers can serve it
+    /// without classifying the block again. The response is recorded with the current
+    /// `SCHEMA_VERSION`, and replaces one written under another version.
+    async fn write_block_json(&self, slot: u64, json: &str) -> Result<()>;
+}
+
+pub struct BlockStoreConfig {
+    /// `mongodb://`, `postgres://` or `sqlite://` URL of the store
+    pub url: String,
+
+    /// Selects the MongoDB database. SQL databases are named in their URL.
+    pub stage: MongoDBStage,
+
+    /// Validate writes against the store without persisting them
+    pub dry_run: bool,
+}
+
+/// Connects to the store the URL points at and creates its schema
+pub async fn open_block_store(config: BlockStoreConfig) -> Result<Arc<dyn BlockStore>> {
+    let scheme = config.url.split(':').next().unwrap_or_default().to_string();
+
+    let store: Arc<dyn BlockStore> = match scheme.as_str() {
+        "mongodb" | "mongodb+srv" => Arc::new(
+            MongoDBClient::new(MongoDBClientConfig {
+                uri: config.url,
+                stage: config.stage,
+                dry_run: config.dry_run,
+            })
+            .await?,
+        ),
+        "postgres" | "postgresql" | "sqlite" => {
+            open_sql_store(&config.url, &scheme, config.dry_run).await?
+        }
+        _ => return Err(BlockStoreError::UnsupportedScheme(scheme)),
+    };
+
+    store.create_schema().await?;
+
+    Ok(store)
+}
+
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+async fn open_sql_store(url: &str, _scheme: &str, dry_run: bool) -> Result<Arc<dyn BlockStore>> {
+    Ok(Arc::new(SqlBlockStore::connect(url, dry_run).await?))
+}
+
+#[cfg(not(any(feature = "postgres", feature = "sqlite")))]
+async fn open_sql_store(_url: &str, scheme: &str, _dry_run: bool) -> Result<Arc<dyn BlockStore>> {
+    Err(BlockStoreError::BackendDisabled(scheme.to_string()))
+}
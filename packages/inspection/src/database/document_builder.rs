@@ -1,9 +1,11 @@
 use actions::{Action, ActionDescendants, ActionNodeId, ActionTrait, ActionTree};
-use classifier_core::{ClassifiableTransaction, SandwichAttackTag, TransactionTag};
-use mongodb::bson::{self};
+use classifier_core::{
+    document, document::Document, ClassifiableTransaction, SandwichAttackTag, TransactionTag,
+};
 use thiserror::Error;
 
-use super::mongo_actions::{MongoSerialize, pubkey_to_bson};
+use super::action_documents::DocumentSerialize;
+use super::mev_stats::BlockStats;
 
 #[derive(Debug, Error)]
 pub enum DocumentBuilderError {
@@ -19,10 +21,13 @@ pub enum DocumentBuilderError {
 
 type Result<T> = std::result::Result<T, DocumentBuilderError>;
 
+/// Documents written for one block, independent of the store they are written to
 pub struct BlockDocuments {
-    pub block: bson::Document,
-    pub transactions: Vec<bson::Document>,
-    pub block_metadata: Vec<bson::Document>,
+    pub slot: u64,
+    pub block: Document,
+    pub transactions: Vec<Document>,
+    pub block_metadata: Vec<Document>,
+    pub stats: BlockStats,
 }
 
 pub fn build_block_documents(tree: &ActionTree, block_id: ActionNodeId) -> Result<BlockDocuments> {
@@ -49,13 +54,15 @@ pub fn build_block_documents(tree: &ActionTree, block_id: ActionNodeId) -> Resul
     }
 
     let block_document = block_action
-        .metadata_bson()
+        .metadata_document()
         .ok_or_else(|| DocumentBuilderError::InvalidRootNode)?;
 
     Ok(BlockDocuments {
+        slot: block_action.slot,
         block: block_document,
         transactions: transaction_documents,
         block_metadata,
+        stats: BlockStats::build(block_action, tree, block_id),
     })
 }
 
@@ -64,7 +71,7 @@ fn build_action_stack(
     ordering: &mut usize,
     tree: &ActionTree,
     root_id: ActionNodeId,
-) -> Result<(Vec<bson::Document>, Vec<bson::Document>)> {
+) -> Result<(Vec<Document>, Vec<Document>)> {
     let root = tree.get(root_id).unwrap().get();
     if !root.is_document_root() {
         return Err(DocumentBuilderError::InvalidRootNode);
@@ -95,12 +102,11 @@ fn build_action_stack(
             }
             // Node is parent to transaction node, store its root metadata
             _ => {
-                let mut metadata = match descendant.metadata_bson() {
+                let mut metadata = match descendant.metadata_document() {
                     Some(metadata) => metadata,
                     None => continue,
                 };
 
-                metadata.insert("_id", bson::oid::ObjectId::new());
                 metadata.insert("block_id", slot_height);
                 root_metadata.push(metadata);
             }
@@ -123,7 +129,7 @@ fn build_transaction_document(
     descendants_iter: &mut std::iter::Peekable<ActionDescendants>,
     tree: &ActionTree,
     tx_id: ActionNodeId,
-) -> bson::Document {
+) -> Document {
     let mut parent_stack = vec![tx_id];
     let mut transaction_metadata = vec![];
     let mut tags = vec![];
@@ -132,38 +138,42 @@ fn build_transaction_document(
     for tag in &tx.tags {
         match tag {
             TransactionTag::AtomicArbitrage(arb) => {
-                tags.push(bson::doc! {
-                    "type": "atomicArbitrage",
-                    "mint": pubkey_to_bson(&arb.mint),
+                tags.push(document! {
+                    "type": tag.type_name(),
+                    "mint": arb.mint,
                     "profitAmount": arb.profit_amount.to_string(),
-                    "address": pubkey_to_bson(&arb.address),
+                    // Numeric copy of the profit for sorting, clamped to the i64 range. Left out
+                    // of API responses, see `mev_query::mev_event_json`.
+                    "profit": arb.profit_amount.clamp(i64::MIN as i128, i64::MAX as i128) as i64,
+                    "address": arb.address,
                 });
             }
             TransactionTag::SandwichAttack(sandwich) => {
                 match sandwich {
                     SandwichAttackTag::Frontrun { token_bought, amount, attacker_pubkey } => {
-                        tags.push(bson::doc! {
-                            "type": "sandwich_frontrun", 
-                            "tokenBought": pubkey_to_bson(token_bought),
+                        tags.push(document! {
+                            "type": tag.type_name(),
+                            "tokenBought": *token_bought,
                             "amount": *amount as i64,
-                            "attackerPubkey": pubkey_to_bson(attacker_pubkey),
+                            "attackerPubkey": *attacker_pubkey,
                         });
                     }
                     SandwichAttackTag::Victim { token_bought, amount, victim_pubkey } => {
-                        tags.push(bson::doc! {
-                            "type": "sandwich_victim",
-                            "tokenBought": pubkey_to_bson(token_bought),
+                        tags.push(document! {
+                            "type": tag.type_name(),
+                            "tokenBought": *token_bought,
                             "amount": *amount as i64,
-                            "victimPubkey": pubkey_to_bson(victim_pubkey),
+                            "victimPubkey": *victim_pubkey,
                         });
                     }
                     SandwichAttackTag::Backrun { token_sold, amount, attacker_pubkey, profit_amount } => {
-                        tags.push(bson::doc! {
-                            "type": "sandwich_backrun",
-                            "tokenSold": pubkey_to_bson(token_sold),
+                        tags.push(document! {
+                            "type": tag.type_name(),
+                            "tokenSold": *token_sold,
                             "amount": *amount as i64,
-                            "attackerPubkey": pubkey_to_bson(attacker_pubkey),
+                            "attackerPubkey": *attacker_pubkey,
                             "profitAmount": profit_amount.to_string(),
+                            "profit": *profit_amount,
                         });
                     }
                 }
@@ -187,7 +197,7 @@ fn build_transaction_document(
         descendants_iter.next();
 
         let descendant = descendant_node.get();
-        let mut metadata = match descendant.metadata_bson() {
+        let mut metadata = match descendant.metadata_document() {
             Some(metadata) => metadata,
             None => continue,
         };
@@ -196,8 +206,7 @@ fn build_transaction_document(
         transaction_metadata.push(metadata);
     }
 
-    let mut document = tx.metadata_bson().unwrap();
-    document.insert("_id", bson::oid::ObjectId::new());
+    let mut document = tx.metadata_document().unwrap();
     document.insert("metadata", transaction_metadata);
     document.insert("block_order", ordering as u32);
     document.insert("block_id", slot_height);
@@ -0,0 +1,197 @@
+use classifier_core::{document::Document, TransactionTag};
+use solana_sdk::pubkey::Pubkey;
+
+/// Maximum number of MEV events returned by one query
+pub const MAX_MEV_EVENTS_LIMIT: i64 = 100;
+
+/// Sandwich tag types written before tags were named by `TransactionTag::type_name`,
+/// with their current names. Queries match both, and responses use the current names.
+const LEGACY_TAG_TYPES: [(&str, &str); 3] = [
+    ("sandwich_frontrun", TransactionTag::SANDWICH_FRONTRUN),
+    ("sandwich_victim", TransactionTag::SANDWICH_VICTIM),
+    ("sandwich_backrun", TransactionTag::SANDWICH_BACKRUN),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MevEventType {
+    Arbitrage,
+    Sandwich,
+}
+
+impl MevEventType {
+    /// Tag types of the event, including legacy names of stored tags
+    pub fn tag_types(&self) -> Vec<&'static str> {
+        match self {
+            MevEventType::Arbitrage => vec![TransactionTag::ATOMIC_ARBITRAGE],
+            MevEventType::Sandwich => LEGACY_TAG_TYPES
+                .iter()
+                .flat_map(|(legacy, current)| [*current, *legacy])
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MevEventSort {
+    /// Most recent slots first
+    #[default]
+    SlotDescending,
+    SlotAscending,
+
+    /// Most profitable events first
+    ProfitDescending,
+    ProfitAscending,
+}
+
+/// Filters over transactions tagged with MEV. All filters are optional and combined,
+/// and all conditions on tags must match the same tag.
+#[derive(Debug, Clone, Default)]
+pub struct MevEventQuery {
+    pub start_slot: Option<u64>,
+
+    /// Last slot to include, inclusive
+    pub end_slot: Option<u64>,
+    pub event_type: Option<MevEventType>,
+
+    /// Arbitrageur, sandwich attacker or sandwich victim
+    pub address: Option<Pubkey>,
+
+    /// Mint arbitraged, or bought or sold in a sandwich
+    pub mint: Option<Pubkey>,
+
+    pub sort: MevEventSort,
+    pub offset: u64,
+    pub limit: i64,
+}
+
+impl MevEventQuery {
+    /// Tag types matching the event type, every MEV tag when unset
+    pub fn tag_types(&self) -> Vec<&'static str> {
+        match self.event_type {
+            Some(event_type) => event_type.tag_types(),
+            None => MevEventType::Arbitrage
+                .tag_types()
+                .into_iter()
+                .chain(MevEventType::Sandwich.tag_types())
+                .collect(),
+        }
+    }
+
+    pub fn limit(&self) -> i64 {
+        self.limit.clamp(1, MAX_MEV_EVENTS_LIMIT)
+    }
+}
+
+/// JSON of a stored transaction tagged with MEV, with its tags as in the JSON schema.
+/// Legacy tag types are renamed, and the numeric profit kept for sorting is removed.
+pub fn mev_event_json(document: &Document) -> serde_json::Value {
+    let mut json = document.to_json();
+
+    let tags = json
+        .get_mut("tags")
+        .and_then(serde_json::Value::as_array_mut)
+        .into_iter()
+        .flatten()
+        .filter_map(serde_json::Value::as_object_mut);
+
+    for tag in tags {
+        tag.remove("profit");
+
+        let current = LEGACY_TAG_TYPES.iter().find_map(|(legacy, current)| {
+            (tag.get("type").and_then(serde_json::Value::as_str) == Some(*legacy))
+                .then_some(*current)
+        });
+        if let Some(current) = current {
+            tag.insert("type".to_string(), current.into());
+        }
+    }
+
+    json
+}
+
+#[cfg(test)]
+mod tests {
+    use classifier_core::{document, document::Value};
+
+    use super::*;
+
+    #[test]
+    fn sandwich_matches_legacy_tag_types() {
+        let tag_types = MevEventType::Sandwich.tag_types();
+
+        for (legacy, current) in LEGACY_TAG_TYPES {
+            assert!(tag_types.contains(&legacy));
+            assert!(tag_types.contains(&current));
+        }
+    }
+
+    #[test]
+    fn query_without_event_type_matches_every_tag_type() {
+        let tag_types = MevEventQuery::default().tag_types();
+
+        assert!(tag_types.contains(&TransactionTag::ATOMIC_ARBITRAGE));
+        for (legacy, current) in LEGACY_TAG_TYPES {
+            assert!(tag_types.contains(&legacy));
+            assert!(tag_types.contains(&current));
+        }
+        assert_eq!(
+            MevEventType::Arbitrage.tag_types(),
+            vec![TransactionTag::ATOMIC_ARBITRAGE]
+        );
+    }
+
+    #[test]
+    fn limit_is_clamped() {
+        let limit = |limit| MevEventQuery {
+            limit,
+            ..Default::default()
+        };
+
+        assert_eq!(limit(0).limit(), 1);
+        assert_eq!(limit(10).limit(), 10);
+        assert_eq!(limit(1_000).limit(), MAX_MEV_EVENTS_LIMIT);
+    }
+
+    #[test]
+    fn event_json_renames_legacy_tags() {
+        let mut transaction = Document::new();
+        transaction.insert("block_order", 0i64);
+        transaction.insert(
+            "tags",
+            Value::Array(vec![document! {
+                "type": "sandwich_backrun",
+                "amount": 10i64,
+                "profitAmount": "5",
+                "profit": 5i64,
+            }
+            .into()]),
+        );
+
+        let json = mev_event_json(&transaction);
+
+        assert_eq!(
+            json["tags"],
+            serde_json::json!([{ "type": "sandwichBackrun", "amount": 10, "profitAmount": "5" }])
+        );
+    }
+
+    #[test]
+    fn event_json_keeps_current_tags() {
+        let mut transaction = Document::new();
+        transaction.insert(
+            "tags",
+            Value::Array(vec![document! {
+                "type": TransactionTag::ATOMIC_ARBITRAGE,
+                "profit": 5i64,
+            }
+            .into()]),
+        );
+
+        let json = mev_event_json(&transaction);
+
+        assert_eq!(
+            json["tags"],
+            serde_json::json!([{ "type": TransactionTag::ATOMIC_ARBITRAGE }])
+        );
+    }
+}
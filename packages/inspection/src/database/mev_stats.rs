@@ -0,0 +1,514 @@
+use std::collections::BTreeMap;
+
+use actions::{Action, ActionNodeId, ActionTree, Block};
+use classifier_core::{
+    document,
+    document::{Document, Value},
+    SandwichAttackTag, TransactionTag,
+};
+use solana_sdk::pubkey::Pubkey;
+
+/// Scalar counters of a rollup document
+const COUNTER_FIELDS: [&str; 6] = [
+    "blocks",
+    "arbitrage_count",
+    "sandwich_count",
+    "tips",
+    "priority_fees",
+    "base_fees",
+];
+
+/// Counters of a rollup document keyed by mint or address
+const KEYED_COUNTER_FIELDS: [&str; 5] = [
+    "arbitrage_profit",
+    "sandwich_profit",
+    "victim_loss",
+    "searchers",
+    "sandwiched_mints",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatsGranularity {
+    Slot,
+    Hour,
+    Day,
+}
+
+impl StatsGranularity {
+    pub const ALL: [StatsGranularity; 3] = [
+        StatsGranularity::Slot,
+        StatsGranularity::Hour,
+        StatsGranularity::Day,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StatsGranularity::Slot => "slot",
+            StatsGranularity::Hour => "hour",
+            StatsGranularity::Day => "day",
+        }
+    }
+
+    pub fn parse(granularity: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|candidate| candidate.as_str() == granularity)
+    }
+
+    /// Slot for slot rollups, otherwise the unix timestamp the period starts at
+    pub fn bucket(&self, slot: u64, block_time: i64) -> i64 {
+        match self {
+            StatsGranularity::Slot => slot as i64,
+            StatsGranularity::Hour => block_time - block_time.rem_euclid(3600),
+            StatsGranularity::Day => block_time - block_time.rem_euclid(86400),
+        }
+    }
+
+    pub fn rollup_id(&self, bucket: i64) -> String {
+        format!("{}:{}", self.as_str(), bucket)
+    }
+}
+
+/// MEV and fee totals of a single block. Profits and losses are raw token amounts, so
+/// they are kept separately for each mint.
+#[derive(Debug, Clone, Default)]
+pub struct BlockStats {
+    pub slot: u64,
+    pub block_time: i64,
+
+    pub arbitrage_count: i64,
+    pub arbitrage_profit: BTreeMap<Pubkey, i64>,
+
+    pub sandwich_count: i64,
+
+    /// Profit of sandwich attackers, in the mint they profited in. This is what the
+    /// attackers gained, not what the victims lost to worse prices.
+    pub sandwich_profit: BTreeMap<Pubkey, i64>,
+
+    /// Value sandwich victims lost, in the mint they bought. Estimated as what the
+    /// victim would have received at the frontrun's price, less what it received.
+    pub victim_loss: BTreeMap<Pubkey, i64>,
+
+    pub tips: i64,
+    pub priority_fees: i64,
+    pub base_fees: i64,
+
+    /// Number of arbitrages and sandwiches landed by each searcher
+    pub searchers: BTreeMap<Pubkey, i64>,
+
+    /// Number of sandwiched victim swaps for each mint bought
+    pub sandwiched_mints: BTreeMap<Pubkey, i64>,
+}
+
+impl BlockStats {
+    pub fn build(block: &Block, tree: &ActionTree, block_id: ActionNodeId) -> Self {
+        let mut stats = BlockStats {
+            slot: block.slot,
+            block_time: block.block_time,
+            tips: block.total_tips.unwrap_or(0) as i64,
+            priority_fees: block.total_priority_fees.unwrap_or(0) as i64,
+            base_fees: block.total_base_fees.unwrap_or(0) as i64,
+            ..Default::default()
+        };
+
+        // Input and output amounts of the latest frontrun, by mint bought
+        let mut frontrun_prices = BTreeMap::<Pubkey, (u64, u64)>::new();
+
+        for txn_id in tree.descendants(block_id) {
+            let txn = match tree.get(txn_id).unwrap().get() {
+                Action::ClassifiableTransaction(txn) => txn,
+                _ => continue,
+            };
+
+            for tag in &txn.tags {
+                match tag {
+                    TransactionTag::AtomicArbitrage(arb) => {
+                        stats.arbitrage_count += 1;
+                        let profit = arb.profit_amount.clamp(i64::MIN as i128, i64::MAX as i128);
+                        *stats.arbitrage_profit.entry(arb.mint).or_default() += profit as i64;
+                        *stats.searchers.entry(arb.address).or_default() += 1;
+                    }
+                    TransactionTag::SandwichAttack(SandwichAttackTag::Victim {
+                        token_bought,
+                        amount,
+                        ..
+                    }) => {
+                        *stats.sandwiched_mints.entry(*token_bought).or_default() += 1;
+
+                        let loss = frontrun_prices.get(token_bought).and_then(
+                            |(front_input, front_output)| {
+                                let (input, _) = swap_buying(tree, txn_id, token_bought)?;
+                                let expected = input as i128 * *front_output as i128
+                                    / (*front_input).max(1) as i128;
+                                Some(expected - *amount as i128)
+                            },
+                        );
+
+                        if let Some(loss) = loss.filter(|loss| *loss > 0) {
+                            *stats.victim_loss.entry(*token_bought).or_default() +=
+                                loss.min(i64::MAX as i128) as i64;
+                        }
+                    }
+                    TransactionTag::SandwichAttack(SandwichAttackTag::Backrun {
+                        token_sold,
+                        attacker_pubkey,
+                        profit_amount,
+                        ..
+                    }) => {
+                        stats.sandwich_count += 1;
+                        *stats.searchers.entry(*attacker_pubkey).or_default() += 1;
+
+                        // Profit is denominated in the mint the backrun bought back
+                        let profit_mint = tree.descendants(txn_id).find_map(|id| {
+                            match tree.get(id).unwrap().get() {
+                                Action::DexSwap(swap) if swap.input_mint == *token_sold => {
+                                    Some(swap.output_mint)
+                                }
+                                _ => None,
+                            }
+                        });
+
+                        if let Some(profit_mint) = profit_mint {
+                            *stats.sandwich_profit.entry(profit_mint).or_default() +=
+                                *profit_amount;
+                        }
+                    }
+                    TransactionTag::SandwichAttack(SandwichAttackTag::Frontrun {
+                        token_bought,
+                        ..
+                    }) => {
+                        if let Some(price) = swap_buying(tree, txn_id, token_bought) {
+                            frontrun_prices.insert(*token_bought, price);
+                        }
+                    }
+                }
+            }
+        }
+
+        stats
+    }
+
+    /// Counters of the block as a rollup document body
+    fn counters(&self) -> Document {
+        let keyed = |counts: &BTreeMap<Pubkey, i64>| {
+            counts
+                .iter()
+                .map(|(key, count)| (key.to_string(), Value::Int(*count)))
+                .collect::<Document>()
+        };
+
+        document! {
+            "blocks": 1_i64,
+            "arbitrage_count": self.arbitrage_count,
+            "arbitrage_profit": keyed(&self.arbitrage_profit),
+            "sandwich_count": self.sandwich_count,
+            "sandwich_profit": keyed(&self.sandwich_profit),
+            "victim_loss": keyed(&self.victim_loss),
+            "tips": self.tips,
+            "priority_fees": self.priority_fees,
+            "base_fees": self.base_fees,
+            "searchers": keyed(&self.searchers),
+            "sandwiched_mints": keyed(&self.sandwiched_mints),
+        }
+    }
+
+    /// Slot rollup document, which also records what the block contributed to the
+    /// hourly and daily rollups
+    pub fn slot_rollup(&self) -> Document {
+        let granularity = StatsGranularity::Slot;
+        let bucket = granularity.bucket(self.slot, self.block_time);
+
+        let mut document = document! {
+            "_id": granularity.rollup_id(bucket),
+            "granularity": granularity.as_str(),
+            "bucket": bucket,
+            "block_time": self.block_time,
+        };
+        document.extend(self.counters());
+
+        document
+    }
+
+    /// Counter increments, keyed by field path, moving the rollups from a previously
+    /// written slot rollup of the same block to this one, so rewriting a block does
+    /// not count it twice
+    pub fn increments(&self, previous: Option<&Document>) -> Document {
+        let mut increments = flatten_counters(&self.counters());
+
+        if let Some(previous) = previous {
+            for (field, count) in flatten_counters(previous) {
+                *increments.entry(field).or_default() -= count;
+            }
+        }
+
+        increments
+            .into_iter()
+            .filter(|(_, count)| *count != 0)
+            .map(|(field, count)| (field, Value::Int(count)))
+            .collect()
+    }
+}
+
+/// Input and output amounts of the first swap in a transaction buying `mint`
+fn swap_buying(tree: &ActionTree, txn_id: ActionNodeId, mint: &Pubkey) -> Option<(u64, u64)> {
+    tree.descendants(txn_id)
+        .find_map(|id| match tree.get(id).unwrap().get() {
+            Action::DexSwap(swap) if swap.output_mint == *mint => {
+                Some((swap.input_amount, swap.output_amount))
+            }
+            _ => None,
+        })
+}
+
+/// Adds increments to a rollup document, for stores which cannot increment fields
+/// in place
+pub fn apply_increments(rollup: &mut Document, increments: &Document) {
+    for (path, increment) in increments.iter() {
+        let increment = match increment.as_i64() {
+            Some(increment) => increment,
+            None => continue,
+        };
+
+        match path.split_once('.') {
+            Some((field, key)) => {
+                let mut counts = rollup.get_document(field).cloned().unwrap_or_default();
+                counts.insert(key, counts.get_i64(key).unwrap_or(0) + increment);
+                rollup.insert(field, counts);
+            }
+            None => {
+                rollup.insert(path.as_str(), rollup.get_i64(path).unwrap_or(0) + increment);
+            }
+        }
+    }
+}
+
+/// Counter field paths of a rollup document and their values
+fn flatten_counters(document: &Document) -> BTreeMap<String, i64> {
+    let mut counters = BTreeMap::new();
+
+    for field in COUNTER_FIELDS {
+        if let Some(count) = document.get_i64(field) {
+            counters.insert(field.to_string(), count);
+        }
+    }
+
+    for field in KEYED_COUNTER_FIELDS {
+        if let Some(counts) = document.get_document(field) {
+            for (key, count) in counts.iter() {
+                if let Some(count) = count.as_i64() {
+                    counters.insert(format!("{}.{}", field, key), count);
+                }
+            }
+        }
+    }
+
+    counters
+}
+
+/// Converts a rollup document to JSON, keeping the largest `top` searchers and
+/// sandwiched mints
+pub fn rollup_to_json(document: &Document, top: usize) -> serde_json::Value {
+    let count = |field: &str| document.get_i64(field).unwrap_or(0);
+
+    serde_json::json!({
+        "granularity": document.get_str("granularity").unwrap_or_default(),
+        "bucket": count("bucket"),
+        "blocks": count("blocks"),
+        "arbitrageCount": count("arbitrage_count"),
+        "arbitrageProfit": keyed_counts(document, "arbitrage_profit", "mint", "amount", None),
+        "sandwichCount": count("sandwich_count"),
+        "sandwichProfit": keyed_counts(document, "sandwich_profit", "mint", "amount", None),
+        "victimLoss": keyed_counts(document, "victim_loss", "mint", "amount", None),
+        "tips": count("tips"),
+        "priorityFees": count("priority_fees"),
+        "baseFees": count("base_fees"),
+        "topSearchers": keyed_counts(document, "searchers", "address", "count", Some(top)),
+        "topSandwichedMints": keyed_counts(document, "sandwiched_mints", "mint", "count", Some(top)),
+    })
+}
+
+fn keyed_counts(
+    document: &Document,
+    field: &str,
+    key_name: &str,
+    count_name: &str,
+    top: Option<usize>,
+) -> serde_json::Value {
+    let mut counts = match document.get_document(field) {
+        Some(counts) => counts
+            .iter()
+            .filter_map(|(key, count)| Some((key.clone(), count.as_i64()?)))
+            .filter(|(_, count)| *count != 0)
+            .collect::<Vec<_>>(),
+        None => Vec::new(),
+    };
+
+    counts.sort_by(|(_, a), (_, b)| b.cmp(a));
+    if let Some(top) = top {
+        counts.truncate(top);
+    }
+
+    counts
+        .into_iter()
+        .map(|(key, count)| serde_json::json!({ key_name: key, count_name: count }))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use actions::DexSwap;
+    use classifier_core::ClassifiableTransaction;
+    use solana_sdk::{
+        message::{Message, VersionedMessage},
+        signature::Signature,
+        transaction::VersionedTransaction,
+    };
+    use solana_transaction_status::UiTransactionStatusMeta;
+
+    use super::*;
+
+    fn transaction(payer: Pubkey, tag: TransactionTag) -> Action {
+        let meta: UiTransactionStatusMeta = serde_json::from_value(serde_json::json!({
+            "err": null,
+            "status": { "Ok": null },
+            "fee": 5000,
+            "preBalances": [1_000_000],
+            "postBalances": [995_000],
+            "logMessages": [],
+        }))
+        .unwrap();
+
+        let mut transaction = ClassifiableTransaction::new(
+            VersionedTransaction {
+                signatures: vec![Signature::new_unique()],
+                message: VersionedMessage::Legacy(Message::new(&[], Some(&payer))),
+            },
+            meta,
+        );
+        transaction.tags.push(tag);
+        transaction.into()
+    }
+
+    fn swap(input_mint: Pubkey, output_mint: Pubkey, input: u64, output: u64) -> Action {
+        DexSwap {
+            input_mint,
+            output_mint,
+            input_token_account: Pubkey::new_unique(),
+            output_token_account: Pubkey::new_unique(),
+            input_amount: input,
+            output_amount: output,
+        }
+        .into()
+    }
+
+    /// Block with a sandwich buying `mint` for `quote`. The frontrun buys at 10 per
+    /// quote, the victim gets 8 and the attacker sells back for 20 quote profit.
+    fn sandwich_block(quote: Pubkey, mint: Pubkey) -> (Block, ActionTree) {
+        let attacker = Pubkey::new_unique();
+        let victim = Pubkey::new_unique();
+        let block = Block::new(100, 99, 7200, None);
+        let mut tree = ActionTree::new(Block::new(100, 99, 7200, None).into());
+
+        let transactions = [
+            (
+                transaction(
+                    attacker,
+                    TransactionTag::SandwichAttack(SandwichAttackTag::Frontrun {
+                        token_bought: mint,
+                        amount: 1000,
+                        attacker_pubkey: attacker,
+                    }),
+                ),
+                swap(quote, mint, 100, 1000),
+            ),
+            (
+                transaction(
+                    victim,
+                    TransactionTag::SandwichAttack(SandwichAttackTag::Victim {
+                        token_bought: mint,
+                        amount: 400,
+                        victim_pubkey: victim,
+                    }),
+                ),
+                swap(quote, mint, 50, 400),
+            ),
+            (
+                transaction(
+                    attacker,
+                    TransactionTag::SandwichAttack(SandwichAttackTag::Backrun {
+                        token_sold: mint,
+                        amount: 1000,
+                        attacker_pubkey: attacker,
+                        profit_amount: 20,
+                    }),
+                ),
+                swap(mint, quote, 1000, 120),
+            ),
+        ];
+
+        for (transaction, swap) in transactions {
+            let txn_id = tree.insert_child(tree.root(), transaction);
+            tree.insert_child(txn_id, swap);
+        }
+
+        (block, tree)
+    }
+
+    #[test]
+    fn rollup_reports_sandwich_profit_and_victim_loss() {
+        let (quote, mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (block, tree) = sandwich_block(quote, mint);
+
+        let stats = BlockStats::build(&block, &tree, tree.root());
+        let json = rollup_to_json(&stats.slot_rollup(), 10);
+
+        assert_eq!(json["sandwichCount"], 1);
+        assert_eq!(
+            json["sandwichProfit"],
+            serde_json::json!([{ "mint": quote.to_string(), "amount": 20 }])
+        );
+        // 50 quote at the frontrun's price buys 500, the victim got 400
+        assert_eq!(
+            json["victimLoss"],
+            serde_json::json!([{ "mint": mint.to_string(), "amount": 100 }])
+        );
+        assert_eq!(
+            json["topSandwichedMints"],
+            serde_json::json!([{ "mint": mint.to_string(), "count": 1 }])
+        );
+    }
+
+    #[test]
+    fn rewriting_block_moves_both_counters() {
+        let (quote, mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (block, tree) = sandwich_block(quote, mint);
+        let stats = BlockStats::build(&block, &tree, tree.root());
+
+        let mut rollup = Document::new();
+        apply_increments(&mut rollup, &stats.increments(None));
+        assert_eq!(
+            rollup
+                .get_document("victim_loss")
+                .unwrap()
+                .get_i64(&mint.to_string()),
+            Some(100)
+        );
+
+        // Rewritten without the sandwich
+        let rewritten = BlockStats {
+            slot: stats.slot,
+            block_time: stats.block_time,
+            ..Default::default()
+        };
+        apply_increments(
+            &mut rollup,
+            &rewritten.increments(Some(&stats.slot_rollup())),
+        );
+
+        let json = rollup_to_json(&rollup, 10);
+        assert_eq!(json["blocks"], 1);
+        assert_eq!(json["sandwichCount"], 0);
+        assert_eq!(json["sandwichProfit"], serde_json::json!([]));
+        assert_eq!(json["victimLoss"], serde_json::json!([]));
+    }
+}
@@ -1,3 +1,8 @@
+mod action_documents;
+pub mod block_store;
 pub mod document_builder;
-mod mongo_actions;
+pub mod mev_query;
+pub mod mev_stats;
 pub mod mongo_client;
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+pub mod sql_store;
@@ -1,16 +1,24 @@
 use std::time::Instant;
 
+use async_trait::async_trait;
+use classifier_core::{
+    document::{self, Value},
+    json::SCHEMA_VERSION,
+};
 use mongodb::{
-    bson::{doc, Document},
-    error::UNKNOWN_TRANSACTION_COMMIT_RESULT,
-    options::{ClientOptions, TransactionOptions, WriteConcern},
-    Client, Collection,
+    bson::{self, doc, Bson, Document},
+    error::{TRANSIENT_TRANSACTION_ERROR, UNKNOWN_TRANSACTION_COMMIT_RESULT},
+    options::{ClientOptions, IndexOptions, TransactionOptions, WriteConcern},
+    Client, ClientSession, Collection, Database, IndexModel,
 };
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
 
-use super::document_builder::BlockDocuments;
-
-pub type MongoDBClientError = mongodb::error::Error;
-type Result<T> = std::result::Result<T, MongoDBClientError>;
+use super::{
+    block_store::{BlockStore, BlockStoreError, Result},
+    document_builder::BlockDocuments,
+    mev_query::{MevEventQuery, MevEventSort},
+    mev_stats::{BlockStats, StatsGranularity},
+};
 
 pub enum MongoDBStage {
     Beta,
@@ -24,15 +32,27 @@ impl ToString for MongoDBStage {
     }
 }
 
+/// Largest document MongoDB accepts
+const MAX_DOCUMENT_SIZE: usize = 16 * 1024 * 1024;
+
+/// Times a block transaction, or the commit of one, is attempted before a transient
+/// error is returned
+const MAX_TRANSACTION_ATTEMPTS: usize = 5;
+
 pub struct MongoDBClientConfig {
     pub uri: String,
     pub stage: MongoDBStage,
+
+    /// Abort block transactions instead of committing them, and skip checkpoint and
+    /// block JSON writes
+    pub dry_run: bool,
 }
 
 #[derive(Clone)]
 pub struct MongoDBClient {
     client: Client,
     database_name: String,
+    dry_run: bool,
 }
 
 impl MongoDBClient {
@@ -44,7 +64,7 @@ impl MongoDBClient {
             Ok(client) => client,
             Err(err) => {
                 tracing::error!("Failed to create MongoDB client: {:?}", err);
-                return Err(err);
+                return Err(err.into());
             }
         };
 
@@ -53,86 +73,636 @@ impl MongoDBClient {
         Ok(Self {
             client,
             database_name: format!("time-machine-{}", config.stage.to_string()),
+            dry_run: config.dry_run,
         })
     }
 
-    pub async fn write_block_documents(&self, block_documents: BlockDocuments) -> Result<()> {
+    /// Write a block in its own transaction on the session. The whole transaction is
+    /// retried when it fails with a transient error, and the commit alone when its
+    /// result is unknown.
+    async fn write_block_transaction(
+        &self,
+        session: &mut ClientSession,
+        block: &BlockBson,
+    ) -> Result<()> {
         let timestamp = Instant::now();
-        tracing::trace!("Writing block documents to MongoDB");
-
-        // Start a session for the transaction
-        let mut session = self.client.start_session().await?;
+        tracing::trace!("Writing block {} to MongoDB", block.block_id);
 
+        let db = self.client.database(&self.database_name);
         let txn_options = TransactionOptions::builder()
             .write_concern(WriteConcern::majority())
             .build();
 
-        // Start the transaction
-        session
-            .start_transaction()
-            .with_options(txn_options)
-            .await?;
+        let mut attempt = 0;
+        'transaction: loop {
+            attempt += 1;
 
-        // Reference the database and collections
-        let db = self.client.database(&self.database_name);
-        let blocks_collection: Collection<Document> = db.collection("blocks");
+            session
+                .start_transaction()
+                .with_options(txn_options.clone())
+                .await?;
+
+            if let Err(err) = Self::write_block(&db, session, block).await {
+                // The server may already have aborted the transaction
+                let _ = session.abort_transaction().await;
+
+                if err.contains_label(TRANSIENT_TRANSACTION_ERROR)
+                    && attempt < MAX_TRANSACTION_ATTEMPTS
+                {
+                    tracing::warn!("Retrying transaction for block {}: {}", block.block_id, err);
+                    continue 'transaction;
+                }
+
+                return Err(err.into());
+            }
+
+            if self.dry_run {
+                session.abort_transaction().await?;
+                tracing::debug!("Validated block {} without writing", block.block_id);
+                return Ok(());
+            }
+
+            let mut commit_attempt = 0;
+            loop {
+                commit_attempt += 1;
+
+                match session.commit_transaction().await {
+                    Ok(()) => {
+                        tracing::trace!("Transaction committed in {:?}", timestamp.elapsed());
+                        return Ok(());
+                    }
+                    Err(err)
+                        if err.contains_label(UNKNOWN_TRANSACTION_COMMIT_RESULT)
+                            && commit_attempt < MAX_TRANSACTION_ATTEMPTS =>
+                    {
+                        tracing::warn!("Retrying commit for block {}: {}", block.block_id, err);
+                    }
+                    Err(err)
+                        if err.contains_label(TRANSIENT_TRANSACTION_ERROR)
+                            && attempt < MAX_TRANSACTION_ATTEMPTS =>
+                    {
+                        tracing::warn!(
+                            "Retrying transaction for block {}: {}",
+                            block.block_id,
+                            err
+                        );
+                        continue 'transaction;
+                    }
+                    Err(err) => {
+                        tracing::error!("Failed to commit transaction: {:?}", err);
+                        return Err(err.into());
+                    }
+                }
+            }
+        }
+    }
 
-        let block_id = block_documents.block.get("_id").unwrap().clone();
-        let block_filter = doc! { "_id": block_id.clone() };
+    /// Replace everything stored for a block within the session's transaction
+    async fn write_block(
+        db: &Database,
+        session: &mut ClientSession,
+        block: &BlockBson,
+    ) -> mongodb::error::Result<()> {
+        let blocks_collection: Collection<Document> = db.collection("blocks");
         blocks_collection
-            .replace_one(block_filter, block_documents.block)
+            .replace_one(doc! { "_id": block.block_id }, &block.block)
             .upsert(true)
-            .session(&mut session)
+            .session(&mut *session)
             .await?;
 
-        // Delete and replace transactions
-        if !block_documents.transactions.is_empty() {
-            let transactions_collection = db.collection("transactions");
+        // Delete and replace transactions and metadata, so a rewrite never leaves
+        // documents of a previous classification behind
+        for (collection_name, documents) in [
+            ("transactions", &block.transactions),
+            ("block_metadata", &block.block_metadata),
+        ] {
+            let collection: Collection<Document> = db.collection(collection_name);
 
-            transactions_collection
-                .delete_many(doc! { "block_id": block_id.clone() })
-                .session(&mut session)
+            collection
+                .delete_many(doc! { "block_id": block.block_id })
+                .session(&mut *session)
                 .await?;
 
-            transactions_collection
-                .insert_many(block_documents.transactions)
-                .await?;
+            if !documents.is_empty() {
+                collection
+                    .insert_many(documents)
+                    .session(&mut *session)
+                    .await?;
+            }
         }
 
-        // Delete and replace metadata
-        if !block_documents.block_metadata.is_empty() {
-            let metadata_collection = db.collection("block_metadata");
-            metadata_collection
-                .delete_many(doc! { "block_id": block_id })
-                .session(&mut session)
-                .await?;
+        Self::write_block_stats(db, session, &block.stats).await
+    }
+
+    /// Replace the slot rollup of a block and apply the difference to its hourly and
+    /// daily rollups
+    async fn write_block_stats(
+        db: &Database,
+        session: &mut ClientSession,
+        stats: &BlockStats,
+    ) -> mongodb::error::Result<()> {
+        let stats_collection: Collection<Document> = db.collection("mev_stats");
+
+        let slot_rollup = document_to_bson(stats.slot_rollup());
+        let slot_filter = doc! { "_id": slot_rollup.get("_id").unwrap().clone() };
+
+        let previous = stats_collection
+            .find_one(slot_filter.clone())
+            .session(&mut *session)
+            .await?
+            .map(document_from_bson);
+        let increments = document_to_bson(stats.increments(previous.as_ref()));
+
+        stats_collection
+            .replace_one(slot_filter, slot_rollup)
+            .upsert(true)
+            .session(&mut *session)
+            .await?;
+
+        for granularity in [StatsGranularity::Hour, StatsGranularity::Day] {
+            let bucket = granularity.bucket(stats.slot, stats.block_time);
+
+            let mut update = doc! {
+                "$setOnInsert": {
+                    "granularity": granularity.as_str(),
+                    "bucket": bucket,
+                },
+            };
+            if !increments.is_empty() {
+                update.insert("$inc", increments.clone());
+            }
 
-            metadata_collection
-                .insert_many(block_documents.block_metadata)
+            stats_collection
+                .update_one(doc! { "_id": granularity.rollup_id(bucket) }, update)
+                .upsert(true)
+                .session(&mut *session)
                 .await?;
         }
 
-        // Commit transaction, retrying if necessary
-        loop {
-            let result = session.commit_transaction().await;
-            if let Err(ref error) = result {
-                tracing::error!("Error committing transaction: {:?}", error);
+        Ok(())
+    }
+}
 
-                if error.contains_label(UNKNOWN_TRANSACTION_COMMIT_RESULT) {
-                    continue;
-                }
-            }
+#[async_trait]
+impl BlockStore for MongoDBClient {
+    async fn create_schema(&self) -> Result<()> {
+        let db = self.client.database(&self.database_name);
 
-            match result {
-                Ok(_) => {
-                    tracing::trace!("Transaction committed in {:?}", timestamp.elapsed());
-                    return Ok(());
-                }
-                Err(err) => {
-                    tracing::error!("Failed to commit transaction: {:?}", err);
-                    return Err(err);
-                }
+        let transactions_collection: Collection<Document> = db.collection("transactions");
+        transactions_collection
+            .create_indexes(vec![
+                index("block_order", doc! { "block_id": 1, "block_order": 1 }),
+                index("tag_type", doc! { "tags.type": 1, "block_id": -1 }),
+                index("tag_profit", doc! { "tags.profit": -1 }),
+                index("tag_address", doc! { "tags.address": 1 }),
+                index("tag_attacker", doc! { "tags.attackerPubkey": 1 }),
+                index("tag_victim", doc! { "tags.victimPubkey": 1 }),
+                index("tag_mint", doc! { "tags.mint": 1 }),
+                index("tag_token_bought", doc! { "tags.tokenBought": 1 }),
+                index("tag_token_sold", doc! { "tags.tokenSold": 1 }),
+            ])
+            .await?;
+
+        let metadata_collection: Collection<Document> = db.collection("block_metadata");
+        metadata_collection
+            .create_index(index("block_id", doc! { "block_id": 1 }))
+            .await?;
+
+        let stats_collection: Collection<Document> = db.collection("mev_stats");
+        stats_collection
+            .create_index(index("bucket", doc! { "granularity": 1, "bucket": -1 }))
+            .await?;
+
+        tracing::info!("Created MongoDB indexes");
+
+        Ok(())
+    }
+
+    async fn find_mev_events(&self, query: &MevEventQuery) -> Result<Vec<document::Document>> {
+        let db = self.client.database(&self.database_name);
+        let transactions_collection: Collection<Document> = db.collection("transactions");
+
+        let mut cursor = transactions_collection
+            .find(mev_event_filter(query))
+            .sort(mev_event_sort(query.sort))
+            .skip(query.offset)
+            .limit(query.limit())
+            .await?;
+
+        let mut documents = Vec::new();
+        while cursor.advance().await? {
+            documents.push(document_from_bson(cursor.deserialize_current()?));
+        }
+
+        Ok(documents)
+    }
+
+    async fn find_block(
+        &self,
+        slot: u64,
+    ) -> Result<Option<(document::Document, Vec<document::Document>)>> {
+        let db = self.client.database(&self.database_name);
+        let blocks_collection: Collection<Document> = db.collection("blocks");
+        let metadata_collection: Collection<Document> = db.collection("block_metadata");
+
+        let block = match blocks_collection
+            .find_one(doc! { "_id": slot as i64 })
+            .await?
+        {
+            Some(block) => block,
+            None => return Ok(None),
+        };
+
+        let mut cursor = metadata_collection
+            .find(doc! { "block_id": slot as i64 })
+            .await?;
+
+        let mut metadata = Vec::new();
+        while cursor.advance().await? {
+            metadata.push(document_from_bson(cursor.deserialize_current()?));
+        }
+
+        Ok(Some((document_from_bson(block), metadata)))
+    }
+
+    async fn find_mev_stats(
+        &self,
+        granularity: StatsGranularity,
+        start: Option<i64>,
+        end: Option<i64>,
+        limit: i64,
+    ) -> Result<Vec<document::Document>> {
+        let db = self.client.database(&self.database_name);
+        let stats_collection: Collection<Document> = db.collection("mev_stats");
+
+        let mut filter = doc! { "granularity": granularity.as_str() };
+        let mut bucket_range = Document::new();
+        if let Some(start) = start {
+            bucket_range.insert("$gte", start);
+        }
+        if let Some(end) = end {
+            bucket_range.insert("$lte", end);
+        }
+        if !bucket_range.is_empty() {
+            filter.insert("bucket", bucket_range);
+        }
+
+        let mut cursor = stats_collection
+            .find(filter)
+            .sort(doc! { "bucket": -1 })
+            .limit(limit)
+            .await?;
+
+        let mut documents = Vec::new();
+        while cursor.advance().await? {
+            documents.push(document_from_bson(cursor.deserialize_current()?));
+        }
+
+        Ok(documents)
+    }
+
+    async fn read_checkpoint(&self, name: &str) -> Result<Option<u64>> {
+        let db = self.client.database(&self.database_name);
+        let checkpoints_collection: Collection<Document> = db.collection("checkpoints");
+
+        let checkpoint = checkpoints_collection
+            .find_one(doc! { "_id": name })
+            .await?;
+
+        Ok(checkpoint
+            .and_then(|checkpoint| checkpoint.get_i64("slot").ok())
+            .map(|slot| slot as u64))
+    }
+
+    async fn write_checkpoint(&self, name: &str, slot: u64) -> Result<()> {
+        if self.dry_run {
+            return Ok(());
+        }
+
+        let db = self.client.database(&self.database_name);
+        let checkpoints_collection: Collection<Document> = db.collection("checkpoints");
+
+        checkpoints_collection
+            .replace_one(
+                doc! { "_id": name },
+                doc! { "_id": name, "slot": slot as i64 },
+            )
+            .upsert(true)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn read_block_json(&self, slot: u64) -> Result<Option<String>> {
+        let db = self.client.database(&self.database_name);
+        let block_json_collection: Collection<Document> = db.collection("block_json");
+
+        let block_json = block_json_collection
+            .find_one(doc! { "_id": slot as i64, "schema_version": SCHEMA_VERSION as i32 })
+            .await?;
+
+        Ok(block_json.and_then(|block_json| block_json.get_str("json").ok().map(String::from)))
+    }
+
+    async fn write_block_json(&self, slot: u64, json: &str) -> Result<()> {
+        if self.dry_run {
+            return Ok(());
+        }
+
+        let db = self.client.database(&self.database_name);
+        let block_json_collection: Collection<Document> = db.collection("block_json");
+
+        block_json_collection
+            .replace_one(
+                doc! { "_id": slot as i64 },
+                doc! { "_id": slot as i64, "schema_version": SCHEMA_VERSION as i32, "json": json },
+            )
+            .upsert(true)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn write_block_documents(&self, block_documents: BlockDocuments) -> Result<()> {
+        let block = BlockBson::new(block_documents)?;
+        let mut session = self.client.start_session().await?;
+
+        self.write_block_transaction(&mut session, &block).await
+    }
+
+    async fn write_many_block_documents(&self, blocks: Vec<BlockDocuments>) -> Result<()> {
+        let blocks = blocks
+            .into_iter()
+            .map(BlockBson::new)
+            .collect::<Result<Vec<_>>>()?;
+
+        let timestamp = Instant::now();
+        let mut session = self.client.start_session().await?;
+
+        for block in &blocks {
+            self.write_block_transaction(&mut session, block).await?;
+        }
+
+        tracing::trace!("Wrote {} blocks in {:?}", blocks.len(), timestamp.elapsed());
+
+        Ok(())
+    }
+}
+
+/// BSON documents of a block, converted and size checked once so that transaction
+/// retries resend the same documents
+struct BlockBson {
+    block_id: i64,
+    block: Document,
+    transactions: Vec<Document>,
+    block_metadata: Vec<Document>,
+    stats: BlockStats,
+}
+
+impl BlockBson {
+    fn new(block_documents: BlockDocuments) -> Result<Self> {
+        let block_id = block_documents.slot as i64;
+
+        let mut block = doc! { "_id": block_id };
+        block.extend(document_to_bson(block_documents.block));
+
+        let block = Self {
+            block_id,
+            block,
+            transactions: block_documents
+                .transactions
+                .into_iter()
+                .map(document_to_bson)
+                .collect(),
+            block_metadata: block_documents
+                .block_metadata
+                .into_iter()
+                .map(document_to_bson)
+                .collect(),
+            stats: block_documents.stats,
+        };
+
+        block.validate()?;
+
+        Ok(block)
+    }
+
+    /// Encode every document, failing on any the server would reject for its size
+    fn validate(&self) -> Result<()> {
+        let documents = std::iter::once(&self.block)
+            .chain(&self.transactions)
+            .chain(&self.block_metadata);
+
+        for document in documents {
+            let size = bson::to_vec(document)
+                .map_err(mongodb::error::Error::from)?
+                .len();
+
+            if size > MAX_DOCUMENT_SIZE {
+                return Err(BlockStoreError::DocumentTooLarge {
+                    slot: self.block_id as u64,
+                    size,
+                });
             }
         }
+
+        Ok(())
+    }
+}
+
+fn index(name: &str, keys: Document) -> IndexModel {
+    IndexModel::builder()
+        .keys(keys)
+        .options(IndexOptions::builder().name(name.to_string()).build())
+        .build()
+}
+
+/// Filter matching transactions with a tag satisfying every condition of the query
+fn mev_event_filter(query: &MevEventQuery) -> Document {
+    let mut filter = Document::new();
+
+    let mut slot_range = Document::new();
+    if let Some(start_slot) = query.start_slot {
+        slot_range.insert("$gte", start_slot as i64);
+    }
+    if let Some(end_slot) = query.end_slot {
+        slot_range.insert("$lte", end_slot as i64);
+    }
+    if !slot_range.is_empty() {
+        filter.insert("block_id", slot_range);
+    }
+
+    let mut tag_conditions = vec![doc! { "type": { "$in": query.tag_types() } }];
+
+    if let Some(address) = &query.address {
+        let address = pubkey_to_bson(address);
+        tag_conditions.push(doc! {
+            "$or": [
+                { "address": address.clone() },
+                { "attackerPubkey": address.clone() },
+                { "victimPubkey": address },
+            ]
+        });
+    }
+
+    if let Some(mint) = &query.mint {
+        let mint = pubkey_to_bson(mint);
+        tag_conditions.push(doc! {
+            "$or": [
+                { "mint": mint.clone() },
+                { "tokenBought": mint.clone() },
+                { "tokenSold": mint },
+            ]
+        });
+    }
+
+    filter.insert("tags", doc! { "$elemMatch": { "$and": tag_conditions } });
+
+    filter
+}
+
+fn mev_event_sort(sort: MevEventSort) -> Document {
+    match sort {
+        MevEventSort::SlotDescending => doc! { "block_id": -1, "block_order": -1 },
+        MevEventSort::SlotAscending => doc! { "block_id": 1, "block_order": 1 },
+        MevEventSort::ProfitDescending => doc! { "tags.profit": -1, "block_id": -1 },
+        MevEventSort::ProfitAscending => doc! { "tags.profit": 1, "block_id": -1 },
+    }
+}
+
+fn binary(bytes: Vec<u8>) -> Bson {
+    Bson::Binary(bson::Binary {
+        subtype: bson::spec::BinarySubtype::Generic,
+        bytes,
+    })
+}
+
+fn pubkey_to_bson(pubkey: &Pubkey) -> Bson {
+    binary(pubkey.to_bytes().to_vec())
+}
+
+/// Pubkeys and signatures are stored as binary, and lamport amounts which may not fit
+/// an Int64 as big endian binary
+fn value_to_bson(value: Value) -> Bson {
+    match value {
+        Value::Null => Bson::Null,
+        Value::Bool(value) => Bson::Boolean(value),
+        Value::Int(value) => Bson::Int64(value),
+        Value::Float(value) => Bson::Double(value),
+        Value::String(value) => Bson::String(value),
+        Value::Pubkey(pubkey) => pubkey_to_bson(&pubkey),
+        Value::Signature(signature) => binary(<[u8; 64]>::from(signature).to_vec()),
+        Value::U64(value) => binary(value.to_be_bytes().to_vec()),
+        Value::U128(value) => binary(value.to_be_bytes().to_vec()),
+        Value::Array(values) => Bson::Array(values.into_iter().map(value_to_bson).collect()),
+        Value::Document(document) => Bson::Document(document_to_bson(document)),
+    }
+}
+
+fn document_to_bson(document: document::Document) -> Document {
+    document
+        .into_iter()
+        .map(|(key, value)| (key, value_to_bson(value)))
+        .collect()
+}
+
+fn value_from_bson(value: Bson) -> Value {
+    match value {
+        Bson::Null | Bson::Undefined => Value::Null,
+        Bson::Boolean(value) => Value::Bool(value),
+        Bson::Int32(value) => Value::Int(value as i64),
+        Bson::Int64(value) => Value::Int(value),
+        Bson::Double(value) => Value::Float(value),
+        Bson::String(value) => Value::String(value),
+        Bson::ObjectId(id) => Value::String(id.to_hex()),
+        Bson::Binary(binary) if binary.subtype == bson::spec::BinarySubtype::Generic => {
+            binary_from_bson(&binary.bytes)
+                .unwrap_or_else(|| Value::from_json(Bson::Binary(binary).into_relaxed_extjson()))
+        }
+        Bson::Array(values) => Value::Array(values.into_iter().map(value_from_bson).collect()),
+        Bson::Document(document) => Value::Document(document_from_bson(document)),
+        value => Value::from_json(value.into_relaxed_extjson()),
+    }
+}
+
+fn binary_from_bson(bytes: &[u8]) -> Option<Value> {
+    Some(match bytes.len() {
+        8 => Value::U64(u64::from_be_bytes(bytes.try_into().ok()?)),
+        16 => Value::U128(u128::from_be_bytes(bytes.try_into().ok()?)),
+        32 => Value::Pubkey(Pubkey::try_from(bytes).ok()?),
+        64 => Value::Signature(Signature::try_from(bytes).ok()?),
+        _ => return None,
+    })
+}
+
+fn document_from_bson(document: Document) -> document::Document {
+    document
+        .into_iter()
+        .map(|(key, value)| (key, value_from_bson(value)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::mev_query::MevEventType;
+
+    #[test]
+    fn mev_event_filter_matches_conditions_on_one_tag() {
+        let (address, mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let query = MevEventQuery {
+            start_slot: Some(10),
+            end_slot: Some(20),
+            event_type: Some(MevEventType::Arbitrage),
+            address: Some(address),
+            mint: Some(mint),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            mev_event_filter(&query),
+            doc! {
+                "block_id": { "$gte": 10i64, "$lte": 20i64 },
+                "tags": { "$elemMatch": { "$and": [
+                    { "type": { "$in": ["atomicArbitrage"] } },
+                    { "$or": [
+                        { "address": pubkey_to_bson(&address) },
+                        { "attackerPubkey": pubkey_to_bson(&address) },
+                        { "victimPubkey": pubkey_to_bson(&address) },
+                    ] },
+                    { "$or": [
+                        { "mint": pubkey_to_bson(&mint) },
+                        { "tokenBought": pubkey_to_bson(&mint) },
+                        { "tokenSold": pubkey_to_bson(&mint) },
+                    ] },
+                ] } },
+            }
+        );
+    }
+
+    #[test]
+    fn mev_event_filter_without_conditions_matches_every_mev_tag() {
+        let query = MevEventQuery::default();
+
+        assert_eq!(
+            mev_event_filter(&query),
+            doc! {
+                "tags": { "$elemMatch": { "$and": [
+                    { "type": { "$in": query.tag_types() } },
+                ] } },
+            }
+        );
+    }
+
+    #[test]
+    fn mev_events_sorted_by_profit_break_ties_by_slot() {
+        assert_eq!(
+            mev_event_sort(MevEventSort::ProfitDescending),
+            doc! { "tags.profit": -1, "block_id": -1 }
+        );
+        assert_eq!(
+            mev_event_sort(MevEventSort::SlotAscending),
+            doc! { "block_id": 1, "block_order": 1 }
+        );
     }
 }
@@ -0,0 +1,785 @@
+use std::time::Instant;
+
+use async_trait::async_trait;
+use classifier_core::{
+    document,
+    document::{Document, Value},
+    json::SCHEMA_VERSION,
+};
+use sqlx::{
+    any::{AnyArguments, AnyPoolOptions},
+    query::Query,
+    Any, AnyPool, Executor, Row, Transaction,
+};
+
+use super::{
+    block_store::{BlockStore, Result},
+    document_builder::BlockDocuments,
+    mev_query::{MevEventQuery, MevEventSort},
+    mev_stats::{apply_increments, BlockStats, StatsGranularity},
+};
+
+/// Slots per TimescaleDB chunk, roughly a day
+const HYPERTABLE_CHUNK_SLOTS: i64 = 216_000;
+
+/// Tables partitioned by slot when TimescaleDB is installed
+const HYPERTABLES: [&str; 4] = [
+    "blocks",
+    "transactions",
+    "transaction_tags",
+    "block_metadata",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SqlDialect {
+    Postgres,
+    Sqlite,
+}
+
+impl SqlDialect {
+    fn from_url(url: &str) -> Self {
+        if url.starts_with("sqlite:") {
+            SqlDialect::Sqlite
+        } else {
+            SqlDialect::Postgres
+        }
+    }
+
+    /// Column type of stored documents
+    fn document_type(&self) -> &'static str {
+        match self {
+            SqlDialect::Postgres => "JSONB",
+            SqlDialect::Sqlite => "TEXT",
+        }
+    }
+
+    /// Binds a document serialized as JSON text
+    fn document_param(&self, index: usize) -> String {
+        match self {
+            SqlDialect::Postgres => format!("CAST(${} AS JSONB)", index),
+            SqlDialect::Sqlite => format!("${}", index),
+        }
+    }
+
+    /// Selects a document column as JSON text
+    fn document_column(&self, column: &str) -> String {
+        match self {
+            SqlDialect::Postgres => format!("CAST({} AS TEXT)", column),
+            SqlDialect::Sqlite => column.to_string(),
+        }
+    }
+}
+
+/// Parameter of a query built at runtime
+enum SqlParam {
+    Int(i64),
+    Text(String),
+}
+
+/// Block store backed by PostgreSQL, optionally with TimescaleDB, or an embedded SQLite
+/// file. Documents are stored as JSON next to the columns queries filter on, and each
+/// MEV tag gets a row in `transaction_tags`.
+pub struct SqlBlockStore {
+    pool: AnyPool,
+    dialect: SqlDialect,
+
+    /// Roll back block transactions instead of committing them, and skip checkpoint
+    /// and block JSON writes
+    dry_run: bool,
+}
+
+impl SqlBlockStore {
+    /// Connects to a `postgres://` or `sqlite://` URL. SQLite files are created if they
+    /// do not exist.
+    pub async fn connect(url: &str, dry_run: bool) -> Result<Self> {
+        let dialect = SqlDialect::from_url(url);
+        let (url, max_connections) = match dialect {
+            // SQLite allows a single writer, so one connection avoids busy errors
+            SqlDialect::Sqlite if !url.contains('?') => (format!("{}?mode=rwc", url), 1),
+            SqlDialect::Sqlite => (url.to_string(), 1),
+            SqlDialect::Postgres => (url.to_string(), 10),
+        };
+
+        let pool = AnyPoolOptions::new()
+            .max_connections(max_connections)
+            .acquire_timeout(std::time::Duration::from_secs(10))
+            .connect(&url)
+            .await?;
+
+        tracing::info!("Connected to {:?} block store", dialect);
+
+        Ok(Self {
+            pool,
+            dialect,
+            dry_run,
+        })
+    }
+
+    fn schema(&self) -> Vec<String> {
+        let document_type = self.dialect.document_type();
+
+        vec![
+            format!(
+                "CREATE TABLE IF NOT EXISTS blocks (
+                    slot BIGINT PRIMARY KEY,
+                    block_time BIGINT NOT NULL,
+                    document {document_type} NOT NULL
+                )"
+            ),
+            format!(
+                "CREATE TABLE IF NOT EXISTS transactions (
+                    slot BIGINT NOT NULL,
+                    block_order BIGINT NOT NULL,
+                    signature TEXT NOT NULL,
+                    document {document_type} NOT NULL,
+                    PRIMARY KEY (slot, block_order)
+                )"
+            ),
+            "CREATE INDEX IF NOT EXISTS transactions_signature ON transactions (signature)"
+                .to_string(),
+            "CREATE TABLE IF NOT EXISTS transaction_tags (
+                slot BIGINT NOT NULL,
+                block_order BIGINT NOT NULL,
+                tag_type TEXT NOT NULL,
+                address TEXT,
+                mint TEXT,
+                profit BIGINT
+            )"
+            .to_string(),
+            "CREATE INDEX IF NOT EXISTS transaction_tags_transaction
+                ON transaction_tags (slot, block_order)"
+                .to_string(),
+            "CREATE INDEX IF NOT EXISTS transaction_tags_type ON transaction_tags (tag_type, slot)"
+                .to_string(),
+            "CREATE INDEX IF NOT EXISTS transaction_tags_address ON transaction_tags (address)"
+                .to_string(),
+            "CREATE INDEX IF NOT EXISTS transaction_tags_mint ON transaction_tags (mint)"
+                .to_string(),
+            "CREATE INDEX IF NOT EXISTS transaction_tags_profit ON transaction_tags (profit)"
+                .to_string(),
+            format!(
+                "CREATE TABLE IF NOT EXISTS block_metadata (
+                    slot BIGINT NOT NULL,
+                    document {document_type} NOT NULL
+                )"
+            ),
+            "CREATE INDEX IF NOT EXISTS block_metadata_slot ON block_metadata (slot)".to_string(),
+            format!(
+                "CREATE TABLE IF NOT EXISTS mev_stats (
+                    id TEXT PRIMARY KEY,
+                    granularity TEXT NOT NULL,
+                    bucket BIGINT NOT NULL,
+                    document {document_type} NOT NULL
+                )"
+            ),
+            "CREATE INDEX IF NOT EXISTS mev_stats_bucket ON mev_stats (granularity, bucket)"
+                .to_string(),
+            "CREATE TABLE IF NOT EXISTS checkpoints (
+                name TEXT PRIMARY KEY,
+                slot BIGINT NOT NULL
+            )"
+            .to_string(),
+            "CREATE TABLE IF NOT EXISTS block_json (
+                slot BIGINT PRIMARY KEY,
+                schema_version INTEGER NOT NULL,
+                json TEXT NOT NULL
+            )"
+            .to_string(),
+        ]
+    }
+
+    /// Partition the slot keyed tables into hypertables, if the TimescaleDB extension
+    /// is installed in the database
+    async fn create_hypertables(&self) -> Result<()> {
+        let installed: i64 =
+            sqlx::query("SELECT COUNT(*) FROM pg_extension WHERE extname = 'timescaledb'")
+                .fetch_one(&self.pool)
+                .await?
+                .try_get(0)?;
+
+        if installed == 0 {
+            return Ok(());
+        }
+
+        for table in HYPERTABLES {
+            let statement = format!(
+                "DO $$ BEGIN
+                    PERFORM create_hypertable(
+                        '{table}',
+                        'slot',
+                        chunk_time_interval => {HYPERTABLE_CHUNK_SLOTS}::BIGINT,
+                        if_not_exists => TRUE,
+                        migrate_data => TRUE
+                    );
+                END $$"
+            );
+
+            // Sent without parameters, so the dollar quoting is not read as placeholders
+            (&self.pool).execute(statement.as_str()).await?;
+        }
+
+        tracing::info!("Created TimescaleDB hypertables");
+
+        Ok(())
+    }
+
+    async fn fetch_documents(&self, sql: &str, params: Vec<SqlParam>) -> Result<Vec<Document>> {
+        let rows = bind_params(sqlx::query(sql), params)
+            .fetch_all(&self.pool)
+            .await?;
+
+        rows.iter()
+            .map(|row| parse_document(&row.try_get::<String, _>(0)?))
+            .collect()
+    }
+
+    /// Stored document of a rollup, if one was written
+    async fn read_rollup(
+        &self,
+        txn: &mut Transaction<'_, Any>,
+        id: &str,
+    ) -> Result<Option<Document>> {
+        let row = sqlx::query(&format!(
+            "SELECT {} FROM mev_stats WHERE id = $1",
+            self.dialect.document_column("document")
+        ))
+        .bind(id)
+        .fetch_optional(&mut **txn)
+        .await?;
+
+        row.map(|row| parse_document(&row.try_get::<String, _>(0)?))
+            .transpose()
+    }
+
+    async fn write_rollup(
+        &self,
+        txn: &mut Transaction<'_, Any>,
+        granularity: StatsGranularity,
+        bucket: i64,
+        rollup: &Document,
+    ) -> Result<()> {
+        sqlx::query(&format!(
+            "INSERT INTO mev_stats (id, granularity, bucket, document) VALUES ($1, $2, $3, {})
+            ON CONFLICT (id) DO UPDATE SET document = excluded.document",
+            self.dialect.document_param(4)
+        ))
+        .bind(granularity.rollup_id(bucket))
+        .bind(granularity.as_str())
+        .bind(bucket)
+        .bind(rollup.to_json().to_string())
+        .execute(&mut **txn)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Replace the slot rollup of a block and add the difference to its hourly and
+    /// daily rollups
+    async fn write_block_stats(
+        &self,
+        txn: &mut Transaction<'_, Any>,
+        stats: &BlockStats,
+    ) -> Result<()> {
+        // Hourly and daily rollups are read and rewritten, so concurrent writers must
+        // not interleave
+        if self.dialect == SqlDialect::Postgres {
+            sqlx::query("LOCK TABLE mev_stats IN SHARE ROW EXCLUSIVE MODE")
+                .execute(&mut **txn)
+                .await?;
+        }
+
+        let slot = StatsGranularity::Slot;
+        let slot_bucket = slot.bucket(stats.slot, stats.block_time);
+
+        let previous = self.read_rollup(txn, &slot.rollup_id(slot_bucket)).await?;
+        let increments = stats.increments(previous.as_ref());
+
+        self.write_rollup(txn, slot, slot_bucket, &stats.slot_rollup())
+            .await?;
+
+        if increments.is_empty() {
+            return Ok(());
+        }
+
+        for granularity in [StatsGranularity::Hour, StatsGranularity::Day] {
+            let bucket = granularity.bucket(stats.slot, stats.block_time);
+
+            let mut rollup = match self
+                .read_rollup(txn, &granularity.rollup_id(bucket))
+                .await?
+            {
+                Some(rollup) => rollup,
+                None => document! {
+                    "_id": granularity.rollup_id(bucket),
+                    "granularity": granularity.as_str(),
+                    "bucket": bucket,
+                },
+            };
+            apply_increments(&mut rollup, &increments);
+
+            self.write_rollup(txn, granularity, bucket, &rollup).await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl BlockStore for SqlBlockStore {
+    async fn create_schema(&self) -> Result<()> {
+        for statement in self.schema() {
+            (&self.pool).execute(statement.as_str()).await?;
+        }
+
+        if self.dialect == SqlDialect::Postgres {
+            self.create_hypertables().await?;
+        }
+
+        tracing::info!("Created {:?} block store schema", self.dialect);
+
+        Ok(())
+    }
+
+    async fn write_block_documents(&self, block_documents: BlockDocuments) -> Result<()> {
+        let timestamp = Instant::now();
+        tracing::trace!("Writing block documents to {:?}", self.dialect);
+
+        let slot = block_documents.slot as i64;
+        let mut txn = self.pool.begin().await?;
+
+        for table in ["transaction_tags", "transactions", "block_metadata"] {
+            sqlx::query(&format!("DELETE FROM {} WHERE slot = $1", table))
+                .bind(slot)
+                .execute(&mut *txn)
+                .await?;
+        }
+
+        sqlx::query(&format!(
+            "INSERT INTO blocks (slot, block_time, document) VALUES ($1, $2, {})
+            ON CONFLICT (slot) DO UPDATE
+            SET block_time = excluded.block_time, document = excluded.document",
+            self.dialect.document_param(3)
+        ))
+        .bind(slot)
+        .bind(block_documents.stats.block_time)
+        .bind(block_documents.block.to_json().to_string())
+        .execute(&mut *txn)
+        .await?;
+
+        let insert_transaction = format!(
+            "INSERT INTO transactions (slot, block_order, signature, document)
+            VALUES ($1, $2, $3, {})",
+            self.dialect.document_param(4)
+        );
+
+        for transaction in &block_documents.transactions {
+            let block_order = transaction.get_i64("block_order").unwrap_or_default();
+            let signature = match transaction.get("signature") {
+                Some(Value::Signature(signature)) => signature.to_string(),
+                _ => String::new(),
+            };
+
+            sqlx::query(&insert_transaction)
+                .bind(slot)
+                .bind(block_order)
+                .bind(signature)
+                .bind(transaction.to_json().to_string())
+                .execute(&mut *txn)
+                .await?;
+
+            let tags = match transaction.get("tags") {
+                Some(Value::Array(tags)) => tags,
+                _ => continue,
+            };
+
+            for tag in tags.iter().filter_map(Value::as_document) {
+                sqlx::query(
+                    "INSERT INTO transaction_tags (slot, block_order, tag_type, address, mint, profit)
+                    VALUES ($1, $2, $3, $4, $5, $6)",
+                )
+                .bind(slot)
+                .bind(block_order)
+                .bind(tag.get_str("type").unwrap_or_default().to_string())
+                .bind(first_pubkey(tag, &["address", "attackerPubkey", "victimPubkey"]))
+                .bind(first_pubkey(tag, &["mint", "tokenBought", "tokenSold"]))
+                .bind(tag.get_i64("profit"))
+                .execute(&mut *txn)
+                .await?;
+            }
+        }
+
+        let insert_metadata = format!(
+            "INSERT INTO block_metadata (slot, document) VALUES ($1, {})",
+            self.dialect.document_param(2)
+        );
+
+        for metadata in &block_documents.block_metadata {
+            sqlx::query(&insert_metadata)
+                .bind(slot)
+                .bind(metadata.to_json().to_string())
+                .execute(&mut *txn)
+                .await?;
+        }
+
+        self.write_block_stats(&mut txn, &block_documents.stats)
+            .await?;
+
+        if self.dry_run {
+            txn.rollback().await?;
+            tracing::debug!("Validated block {} without writing", slot);
+            return Ok(());
+        }
+
+        txn.commit().await?;
+        tracing::trace!("Transaction committed in {:?}", timestamp.elapsed());
+
+        Ok(())
+    }
+
+    async fn find_mev_events(&self, query: &MevEventQuery) -> Result<Vec<Document>> {
+        let mut params = Vec::new();
+        let mut param = |value: SqlParam| {
+            params.push(value);
+            format!("${}", params.len())
+        };
+
+        // All conditions must match the same tag
+        let tag_types = query
+            .tag_types()
+            .into_iter()
+            .map(|tag_type| param(SqlParam::Text(tag_type.to_string())))
+            .collect::<Vec<_>>();
+        let mut tag_conditions = vec![format!("g.tag_type IN ({})", tag_types.join(", "))];
+
+        if let Some(address) = &query.address {
+            tag_conditions.push(format!(
+                "g.address = {}",
+                param(SqlParam::Text(address.to_string()))
+            ));
+        }
+        if let Some(mint) = &query.mint {
+            tag_conditions.push(format!(
+                "g.mint = {}",
+                param(SqlParam::Text(mint.to_string()))
+            ));
+        }
+
+        let mut conditions = vec![format!(
+            "EXISTS (SELECT 1 FROM transaction_tags g
+            WHERE g.slot = t.slot AND g.block_order = t.block_order AND {})",
+            tag_conditions.join(" AND ")
+        )];
+
+        if let Some(start_slot) = query.start_slot {
+            conditions.push(format!(
+                "t.slot >= {}",
+                param(SqlParam::Int(start_slot as i64))
+            ));
+        }
+        if let Some(end_slot) = query.end_slot {
+            conditions.push(format!(
+                "t.slot <= {}",
+                param(SqlParam::Int(end_slot as i64))
+            ));
+        }
+
+        // Matches MongoDB, where transactions without a profit sort lowest
+        let profit = |aggregate: &str| {
+            format!(
+                "(SELECT {}(g.profit) FROM transaction_tags g
+                WHERE g.slot = t.slot AND g.block_order = t.block_order)",
+                aggregate
+            )
+        };
+        let order = match query.sort {
+            MevEventSort::SlotDescending => "t.slot DESC, t.block_order DESC".to_string(),
+            MevEventSort::SlotAscending => "t.slot ASC, t.block_order ASC".to_string(),
+            MevEventSort::ProfitDescending => {
+                format!("{} DESC NULLS LAST, t.slot DESC", profit("MAX"))
+            }
+            MevEventSort::ProfitAscending => {
+                format!("{} ASC NULLS FIRST, t.slot DESC", profit("MIN"))
+            }
+        };
+
+        let limit = param(SqlParam::Int(query.limit()));
+        let offset = param(SqlParam::Int(query.offset as i64));
+
+        let sql = format!(
+            "SELECT {} FROM transactions t WHERE {} ORDER BY {} LIMIT {} OFFSET {}",
+            self.dialect.document_column("t.document"),
+            conditions.join(" AND "),
+            order,
+            limit,
+            offset
+        );
+
+        self.fetch_documents(&sql, params).await
+    }
+
+    async fn find_block(&self, slot: u64) -> Result<Option<(Document, Vec<Document>)>> {
+        let block = self
+            .fetch_documents(
+                &format!(
+                    "SELECT {} FROM blocks WHERE slot = $1",
+                    self.dialect.document_column("document")
+                ),
+                vec![SqlParam::Int(slot as i64)],
+            )
+            .await?
+            .pop();
+
+        let block = match block {
+            Some(block) => block,
+            None => return Ok(None),
+        };
+
+        let metadata = self
+            .fetch_documents(
+                &format!(
+                    "SELECT {} FROM block_metadata WHERE slot = $1",
+                    self.dialect.document_column("document")
+                ),
+                vec![SqlParam::Int(slot as i64)],
+            )
+            .await?;
+
+        Ok(Some((block, metadata)))
+    }
+
+    async fn find_mev_stats(
+        &self,
+        granularity: StatsGranularity,
+        start: Option<i64>,
+        end: Option<i64>,
+        limit: i64,
+    ) -> Result<Vec<Document>> {
+        let mut params = vec![SqlParam::Text(granularity.as_str().to_string())];
+        let mut conditions = vec!["granularity = $1".to_string()];
+
+        if let Some(start) = start {
+            params.push(SqlParam::Int(start));
+            conditions.push(format!("bucket >= ${}", params.len()));
+        }
+        if let Some(end) = end {
+            params.push(SqlParam::Int(end));
+            conditions.push(format!("bucket <= ${}", params.len()));
+        }
+        params.push(SqlParam::Int(limit));
+
+        let sql = format!(
+            "SELECT {} FROM mev_stats WHERE {} ORDER BY bucket DESC LIMIT ${}",
+            self.dialect.document_column("document"),
+            conditions.join(" AND "),
+            params.len()
+        );
+
+        self.fetch_documents(&sql, params).await
+    }
+
+    async fn read_checkpoint(&self, name: &str) -> Result<Option<u64>> {
+        let row = sqlx::query("SELECT slot FROM checkpoints WHERE name = $1")
+            .bind(name)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(row
+            .map(|row| row.try_get::<i64, _>(0))
+            .transpose()?
+            .map(|slot| slot as u64))
+    }
+
+    async fn write_checkpoint(&self, name: &str, slot: u64) -> Result<()> {
+        if self.dry_run {
+            return Ok(());
+        }
+
+        sqlx::query(
+            "INSERT INTO checkpoints (name, slot) VALUES ($1, $2)
+            ON CONFLICT (name) DO UPDATE SET slot = excluded.slot",
+        )
+        .bind(name)
+        .bind(slot as i64)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn read_block_json(&self, slot: u64) -> Result<Option<String>> {
+        let row =
+            sqlx::query("SELECT json FROM block_json WHERE slot = $1 AND schema_version = $2")
+                .bind(slot as i64)
+                .bind(SCHEMA_VERSION as i32)
+                .fetch_optional(&self.pool)
+                .await?;
+
+        Ok(row.map(|row| row.try_get(0)).transpose()?)
+    }
+
+    async fn write_block_json(&self, slot: u64, json: &str) -> Result<()> {
+        if self.dry_run {
+            return Ok(());
+        }
+
+        sqlx::query(
+            "INSERT INTO block_json (slot, schema_version, json) VALUES ($1, $2, $3)
+            ON CONFLICT (slot) DO UPDATE
+            SET schema_version = excluded.schema_version, json = excluded.json",
+        )
+        .bind(slot as i64)
+        .bind(SCHEMA_VERSION as i32)
+        .bind(json)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
+
+fn bind_params<'q>(
+    mut query: Query<'q, Any, AnyArguments<'q>>,
+    params: Vec<SqlParam>,
+) -> Query<'q, Any, AnyArguments<'q>> {
+    for param in params {
+        query = match param {
+            SqlParam::Int(value) => query.bind(value),
+            SqlParam::Text(value) => query.bind(value),
+        };
+    }
+
+    query
+}
+
+fn parse_document(json: &str) -> Result<Document> {
+    let document = Document::from_json(serde_json::from_str(json)?).ok_or_else(|| {
+        <serde_json::Error as serde::de::Error>::custom("stored document is not an object")
+    })?;
+
+    Ok(document)
+}
+
+/// Base58 string of the first of the fields holding a pubkey
+fn first_pubkey(document: &Document, fields: &[&str]) -> Option<String> {
+    fields.iter().find_map(|field| match document.get(field) {
+        Some(Value::Pubkey(pubkey)) => Some(pubkey.to_string()),
+        _ => None,
+    })
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use solana_sdk::{pubkey::Pubkey, signature::Signature};
+
+    use super::*;
+
+    async fn memory_store() -> SqlBlockStore {
+        let store = SqlBlockStore::connect("sqlite::memory:", false)
+            .await
+            .unwrap();
+        store.create_schema().await.unwrap();
+        store
+    }
+
+    fn block_documents(slot: u64, arbitrageur: Pubkey) -> BlockDocuments {
+        let mut block = Document::new();
+        block.insert("slot", slot);
+        block.insert("blockhash", "blockhash");
+
+        let mut tag = Document::new();
+        tag.insert("type", "atomicArbitrage");
+        tag.insert("address", arbitrageur);
+        tag.insert("profit", 1_000i64);
+
+        let mut transaction = Document::new();
+        transaction.insert("slot", slot);
+        transaction.insert("block_order", 0i64);
+        transaction.insert("signature", Value::Signature(Signature::default()));
+        transaction.insert("tags", Value::Array(vec![tag.into()]));
+
+        let mut metadata = Document::new();
+        metadata.insert("leader", arbitrageur);
+
+        BlockDocuments {
+            slot,
+            block,
+            transactions: vec![transaction],
+            block_metadata: vec![metadata],
+            stats: BlockStats {
+                slot,
+                block_time: 7200,
+                arbitrage_count: 1,
+                ..Default::default()
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn sqlite_round_trip() {
+        let store = memory_store().await;
+
+        assert_eq!(store.read_checkpoint("backfill").await.unwrap(), None);
+        store.write_checkpoint("backfill", 10).await.unwrap();
+        store.write_checkpoint("backfill", 12).await.unwrap();
+        assert_eq!(store.read_checkpoint("backfill").await.unwrap(), Some(12));
+
+        assert_eq!(store.read_block_json(100).await.unwrap(), None);
+        store
+            .write_block_json(100, r#"{"slot":100}"#)
+            .await
+            .unwrap();
+        assert_eq!(
+            store.read_block_json(100).await.unwrap().as_deref(),
+            Some(r#"{"slot":100}"#)
+        );
+
+        let arbitrageur = Pubkey::new_unique();
+        let documents = block_documents(100, arbitrageur);
+        store
+            .write_block_documents(block_documents(100, arbitrageur))
+            .await
+            .unwrap();
+        // Rewriting a block replaces its rows
+        store
+            .write_block_documents(block_documents(100, arbitrageur))
+            .await
+            .unwrap();
+
+        let (block, metadata) = store.find_block(100).await.unwrap().unwrap();
+        assert_eq!(block.to_json(), documents.block.to_json());
+        assert_eq!(metadata.len(), 1);
+        assert_eq!(metadata[0].to_json(), documents.block_metadata[0].to_json());
+        assert!(store.find_block(101).await.unwrap().is_none());
+
+        let events = store
+            .find_mev_events(&MevEventQuery {
+                address: Some(arbitrageur),
+                limit: 10,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].to_json(), documents.transactions[0].to_json());
+    }
+
+    #[tokio::test]
+    async fn block_json_of_another_schema_version_is_ignored() {
+        let store = memory_store().await;
+        store.write_block_json(100, "{}").await.unwrap();
+
+        sqlx::query("UPDATE block_json SET schema_version = $1")
+            .bind(SCHEMA_VERSION as i32 - 1)
+            .execute(&store.pool)
+            .await
+            .unwrap();
+        assert_eq!(store.read_block_json(100).await.unwrap(), None);
+
+        // Rewritten under the current version
+        store.write_block_json(100, "{}").await.unwrap();
+        assert_eq!(
+            store.read_block_json(100).await.unwrap().as_deref(),
+            Some("{}")
+        );
+    }
+}
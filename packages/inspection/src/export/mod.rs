@@ -0,0 +1,458 @@
+//! Columnar export of classified blocks, for analysis in notebooks and query engines
+//! such as DuckDB, Polars or Spark.
+//!
+//! Every export writes the tables listed in [`Table`], one file per table and slot
+//! partition:
+//!
+//! ```text
+//! {out_dir}/{table}/slots_{start}_{end}.{parquet|arrow}
+//! ```
+//!
+//! where `start` and `end` are the first and last slot of the partition within the
+//! exported range, zero padded so that files sort by slot. Every table of a written
+//! partition has a file, even when it has no rows. Exporting the same range again
+//! replaces its files, while exporting part of a partition writes files of its own
+//! rather than replacing those of the whole partition.
+//!
+//! Column names and types are defined in [`schema`]. Pubkeys and signatures are base58
+//! strings and lamport and token amounts are unsigned 64 bit integers, except profits
+//! which are 128 bit decimals. The schema version is stored in the metadata of every
+//! file under [`schema::SCHEMA_VERSION_KEY`].
+
+pub mod schema;
+mod tables;
+
+use std::{
+    fs::{self, File},
+    io::BufWriter,
+    path::{Path, PathBuf},
+};
+
+use actions::{Action, ActionTree};
+use arrow::{error::ArrowError, ipc::writer::FileWriter, record_batch::RecordBatch};
+use classifier_core::AddressLookupTableSource;
+use classifier_handler::{classify_block, ClassifierRegistry};
+use parquet::{
+    arrow::ArrowWriter,
+    basic::{Compression, ZstdLevel},
+    errors::ParquetError,
+    file::properties::WriterProperties,
+};
+use solana_client::{rpc_client::RpcClient, rpc_config::RpcBlockConfig};
+use solana_transaction_status::{TransactionDetails, UiTransactionEncoding};
+use thiserror::Error;
+
+use crate::{
+    filtering::{post_process, PostProcessConfig},
+    label_tree,
+    rpc::is_skipped_slot,
+    validators::ValidatorRegistry,
+};
+use schema::Table;
+use tables::TableRows;
+
+#[derive(Debug, Error)]
+pub enum ExportError {
+    #[error("IO error: {0}")]
+    IoError(#[from] std::io::Error),
+
+    #[error("Arrow error: {0}")]
+    ArrowError(#[from] ArrowError),
+
+    #[error("Parquet error: {0}")]
+    ParquetError(#[from] ParquetError),
+
+    #[error("Root node is not a block")]
+    InvalidRootNode,
+
+    #[error("Slot {slot} added after slot {last_slot}, blocks must be added in slot order")]
+    SlotOutOfOrder { slot: u64, last_slot: u64 },
+}
+
+type Result<T> = std::result::Result<T, ExportError>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// Zstd compressed Parquet files
+    Parquet,
+
+    /// Arrow IPC files, also known as Feather v2
+    ArrowIpc,
+}
+
+impl ExportFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Parquet => "parquet",
+            ExportFormat::ArrowIpc => "arrow",
+        }
+    }
+}
+
+pub struct ExportConfig {
+    /// Directory the table directories are created in
+    pub out_dir: PathBuf,
+
+    pub format: ExportFormat,
+
+    /// Slots covered by each file. Partitions start at multiples of this size.
+    pub partition_slots: u64,
+}
+
+/// Buffers the rows of classified blocks and writes a file per table whenever a slot
+/// partition is complete
+pub struct TableExporter {
+    config: ExportConfig,
+    rows: TableRows,
+
+    /// First slot of the partition being buffered
+    partition_start: Option<u64>,
+
+    /// Slots being exported, which file names are limited to
+    slot_range: Option<(u64, u64)>,
+    last_slot: Option<u64>,
+    files: Vec<PathBuf>,
+}
+
+impl TableExporter {
+    pub fn new(config: ExportConfig) -> Self {
+        Self {
+            config: ExportConfig {
+                partition_slots: config.partition_slots.max(1),
+                ..config
+            },
+            rows: TableRows::default(),
+            partition_start: None,
+            slot_range: None,
+            last_slot: None,
+            files: Vec::new(),
+        }
+    }
+
+    /// Adds a classified, labelled and post processed block. Blocks must be added in
+    /// increasing slot order.
+    pub fn add_block(&mut self, tree: &ActionTree) -> Result<()> {
+        let block = match tree.get(tree.root()).map(|node| node.get()) {
+            Some(Action::Block(block)) => block,
+            _ => return Err(ExportError::InvalidRootNode),
+        };
+
+        if let Some(last_slot) = self.last_slot {
+            if block.slot <= last_slot {
+                return Err(ExportError::SlotOutOfOrder {
+                    slot: block.slot,
+                    last_slot,
+                });
+            }
+        }
+
+        let partition_start = block.slot - block.slot % self.config.partition_slots;
+        if self.partition_start != Some(partition_start) {
+            self.flush()?;
+            self.partition_start = Some(partition_start);
+        }
+
+        self.rows.push_block(tree, block);
+        self.last_slot = Some(block.slot);
+
+        Ok(())
+    }
+
+    /// Writes the buffered partition and returns the paths of every file written
+    pub fn finish(mut self) -> Result<Vec<PathBuf>> {
+        self.flush()?;
+
+        Ok(self.files)
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        let partition_start = match self.partition_start {
+            Some(partition_start) if !self.rows.is_empty() => partition_start,
+            _ => return Ok(()),
+        };
+
+        let partition_end = partition_start + self.config.partition_slots - 1;
+        let (first_slot, last_slot) = match self.slot_range {
+            Some((start_slot, end_slot)) => {
+                (partition_start.max(start_slot), partition_end.min(end_slot))
+            }
+            None => (partition_start, partition_end),
+        };
+
+        for table in Table::ALL {
+            let batch = self.rows.take_batch(table)?;
+
+            let table_dir = self.config.out_dir.join(table.name());
+            fs::create_dir_all(&table_dir)?;
+
+            let path = table_dir.join(format!(
+                "slots_{:012}_{:012}.{}",
+                first_slot,
+                last_slot,
+                self.config.format.extension()
+            ));
+
+            write_batch(self.config.format, &path, &batch)?;
+            self.files.push(path);
+        }
+
+        Ok(())
+    }
+}
+
+fn write_batch(format: ExportFormat, path: &Path, batch: &RecordBatch) -> Result<()> {
+    let file = File::create(path)?;
+
+    match format {
+        ExportFormat::Parquet => {
+            let properties = WriterProperties::builder()
+                .set_compression(Compression::ZSTD(ZstdLevel::default()))
+                .build();
+
+            let mut writer = ArrowWriter::try_new(file, batch.schema(), Some(properties))?;
+            writer.write(batch)?;
+            writer.close()?;
+        }
+        ExportFormat::ArrowIpc => {
+            let mut writer = FileWriter::try_new(BufWriter::new(file), &batch.schema())?;
+            writer.write(batch)?;
+            writer.finish()?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Where blocks are fetched from and how they are classified
+pub struct ExportSources<'a> {
+    pub rpc_client: &'a RpcClient,
+
+    /// Only consulted for transactions whose meta is missing loaded addresses
+    pub lookup_tables: Option<&'a dyn AddressLookupTableSource>,
+    pub classifier_registry: &'a ClassifierRegistry,
+    pub validator_registry: &'a ValidatorRegistry,
+    pub parallel_transactions: bool,
+}
+
+#[derive(Debug, Default)]
+pub struct ExportSummary {
+    pub blocks: u64,
+
+    /// Slots without a block
+    pub skipped_slots: u64,
+
+    /// Slots whose block could not be fetched or classified
+    pub failed_slots: Vec<u64>,
+
+    pub files: Vec<PathBuf>,
+}
+
+/// Classifies every block from `start_slot` to `end_slot` inclusive and writes them to
+/// the exporter. Blocks which fail to fetch or classify are logged and left out.
+pub fn export_slot_range(
+    sources: &ExportSources,
+    start_slot: u64,
+    end_slot: u64,
+    mut exporter: TableExporter,
+) -> Result<ExportSummary> {
+    let mut summary = ExportSummary::default();
+    exporter.slot_range = Some((start_slot, end_slot));
+
+    for slot in start_slot..=end_slot {
+        let block = match sources.rpc_client.get_block_with_config(
+            slot,
+            RpcBlockConfig {
+                max_supported_transaction_version: Some(0),
+                encoding: Some(UiTransactionEncoding::Base64),
+                transaction_details: Some(TransactionDetails::Full),
+                ..Default::default()
+            },
+        ) {
+            Ok(block) => block,
+            Err(err) if is_skipped_slot(&err) => {
+                summary.skipped_slots += 1;
+                continue;
+            }
+            Err(err) => {
+                tracing::warn!("Failed to get block {}: {:?}", slot, err);
+                summary.failed_slots.push(slot);
+                continue;
+            }
+        };
+
+        let mut tree = match classify_block(
+            slot,
+            block,
+            None,
+            sources.lookup_tables,
+            sources.classifier_registry,
+            sources.parallel_transactions,
+        ) {
+            Ok(tree) => tree,
+            Err(err) => {
+                tracing::warn!("Failed to classify block {}: {:?}", slot, err);
+                summary.failed_slots.push(slot);
+                continue;
+            }
+        };
+
+        sources.validator_registry.enrich_block(&mut tree);
+        label_tree(&mut tree);
+
+        post_process(
+            PostProcessConfig {
+                retain_votes: false,
+                remove_empty_transactions: true,
+                cluster_jito_bundles: true,
+            },
+            &mut tree,
+        );
+
+        exporter.add_block(&tree)?;
+        summary.blocks += 1;
+    }
+
+    summary.files = exporter.finish()?;
+
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use actions::{Block, DexSwap, JitoTip};
+    use arrow::array::{Array, Decimal128Array};
+    use classifier_core::{AtomicArbitrageTag, ClassifiableTransaction, TransactionTag};
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+    use solana_sdk::{
+        message::{Message, VersionedMessage},
+        pubkey::Pubkey,
+        signature::Signature,
+        transaction::VersionedTransaction,
+    };
+    use solana_transaction_status::UiTransactionStatusMeta;
+
+    use super::*;
+
+    fn out_dir(name: &str) -> PathBuf {
+        let out_dir = std::env::temp_dir().join(format!("export-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&out_dir);
+        out_dir
+    }
+
+    /// Block with one arbitrage transaction, which makes a swap and a tip
+    fn block_tree(slot: u64, profit_amount: i128) -> ActionTree {
+        let payer = Pubkey::new_unique();
+        let meta: UiTransactionStatusMeta = serde_json::from_value(serde_json::json!({
+            "err": null,
+            "status": { "Ok": null },
+            "fee": 5000,
+            "preBalances": [1_000_000],
+            "postBalances": [995_000],
+            "logMessages": [],
+        }))
+        .unwrap();
+
+        let mut transaction = ClassifiableTransaction::new(
+            VersionedTransaction {
+                signatures: vec![Signature::default()],
+                message: VersionedMessage::Legacy(Message::new(&[], Some(&payer))),
+            },
+            meta,
+        );
+        transaction
+            .tags
+            .push(TransactionTag::AtomicArbitrage(AtomicArbitrageTag {
+                mint: Pubkey::new_unique(),
+                profit_amount,
+                address: payer,
+            }));
+
+        let mut tree = ActionTree::new(Block::new(slot, slot - 1, 0, None).into());
+        let transaction_id = tree.insert_child(tree.root(), transaction.into());
+        tree.insert_child(
+            transaction_id,
+            DexSwap {
+                input_mint: Pubkey::new_unique(),
+                output_mint: Pubkey::new_unique(),
+                input_token_account: Pubkey::new_unique(),
+                output_token_account: Pubkey::new_unique(),
+                input_amount: 100,
+                output_amount: 200,
+            }
+            .into(),
+        );
+        tree.insert_child(transaction_id, JitoTip::new(payer, 1000).into());
+
+        tree
+    }
+
+    fn read_parquet(path: &Path) -> RecordBatch {
+        let mut reader = ParquetRecordBatchReaderBuilder::try_new(File::open(path).unwrap())
+            .unwrap()
+            .build()
+            .unwrap();
+        let batch = reader.next().unwrap().unwrap();
+        assert!(reader.next().is_none());
+        batch
+    }
+
+    #[test]
+    fn exports_one_row_per_table() {
+        let out_dir = out_dir("rows");
+        let mut exporter = TableExporter::new(ExportConfig {
+            out_dir: out_dir.clone(),
+            format: ExportFormat::Parquet,
+            partition_slots: 100,
+        });
+        exporter.add_block(&block_tree(150, i128::MAX)).unwrap();
+        let files = exporter.finish().unwrap();
+
+        assert_eq!(files.len(), Table::ALL.len());
+        for (table, path) in Table::ALL.iter().zip(&files) {
+            assert_eq!(
+                path,
+                &out_dir
+                    .join(table.name())
+                    .join("slots_000000000100_000000000199.parquet")
+            );
+
+            let batch = read_parquet(path);
+            assert_eq!(batch.num_rows(), 1, "{} rows", table.name());
+            assert_eq!(batch.schema().fields(), table.schema().fields());
+        }
+
+        // Wider than the column, so written as null
+        let tags = read_parquet(&files[3]);
+        let profit_amount = tags
+            .column_by_name("profit_amount")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<Decimal128Array>()
+            .unwrap();
+        assert!(profit_amount.is_null(0));
+
+        fs::remove_dir_all(&out_dir).unwrap();
+    }
+
+    #[test]
+    fn partial_partition_is_written_to_its_own_files() {
+        let out_dir = out_dir("partial");
+        let mut exporter = TableExporter::new(ExportConfig {
+            out_dir: out_dir.clone(),
+            format: ExportFormat::ArrowIpc,
+            partition_slots: 100,
+        });
+        exporter.slot_range = Some((150, 160));
+        exporter.add_block(&block_tree(155, 1)).unwrap();
+        let files = exporter.finish().unwrap();
+
+        assert_eq!(
+            files[0],
+            out_dir
+                .join("blocks")
+                .join("slots_000000000150_000000000160.arrow")
+        );
+
+        fs::remove_dir_all(&out_dir).unwrap();
+    }
+}
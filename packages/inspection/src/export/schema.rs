@@ -0,0 +1,167 @@
+use std::{collections::HashMap, sync::Arc};
+
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+
+/// Version of the exported table schemas, recorded in the metadata of every file.
+/// Bumped when a column is removed, renamed or changes type. Columns may be appended
+/// without a bump.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Schema metadata key holding [`SCHEMA_VERSION`]
+pub const SCHEMA_VERSION_KEY: &str = "mev_classifier.schema_version";
+
+/// Schema metadata key holding the table name
+pub const TABLE_KEY: &str = "mev_classifier.table";
+
+/// Profit amounts are whole token units in the widest Decimal128, which holds 38 digits.
+/// An i128 can hold 39, so larger profits, beyond any real token supply, are null.
+pub const PROFIT_PRECISION: u8 = 38;
+
+/// Flat tables written by an export. Rows of every table other than `blocks` are
+/// keyed by `(slot, block_order)`, the position of their transaction within the
+/// classified block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Table {
+    Blocks,
+    Transactions,
+    DexSwaps,
+    Tags,
+    Tips,
+}
+
+impl Table {
+    pub const ALL: [Table; 5] = [
+        Table::Blocks,
+        Table::Transactions,
+        Table::DexSwaps,
+        Table::Tags,
+        Table::Tips,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Table::Blocks => "blocks",
+            Table::Transactions => "transactions",
+            Table::DexSwaps => "dex_swaps",
+            Table::Tags => "tags",
+            Table::Tips => "tips",
+        }
+    }
+
+    pub fn schema(&self) -> SchemaRef {
+        let fields = match self {
+            Table::Blocks => blocks_fields(),
+            Table::Transactions => transactions_fields(),
+            Table::DexSwaps => dex_swaps_fields(),
+            Table::Tags => tags_fields(),
+            Table::Tips => tips_fields(),
+        };
+
+        let metadata = HashMap::from([
+            (SCHEMA_VERSION_KEY.to_string(), SCHEMA_VERSION.to_string()),
+            (TABLE_KEY.to_string(), self.name().to_string()),
+        ]);
+
+        Arc::new(Schema::new_with_metadata(fields, metadata))
+    }
+}
+
+/// One row per classified block, from [`actions::Block`]
+fn blocks_fields() -> Vec<Field> {
+    vec![
+        Field::new("slot", DataType::UInt64, false),
+        Field::new("parent_slot", DataType::UInt64, false),
+        // Unix timestamp in seconds
+        Field::new("block_time", DataType::Int64, false),
+        Field::new("validator_pubkey", DataType::Utf8, true),
+        Field::new("validator_name", DataType::Utf8, true),
+        Field::new("validator_vote_account", DataType::Utf8, true),
+        // `feeReward` or `leaderSchedule`
+        Field::new("leader_source", DataType::Utf8, true),
+        Field::new("total_base_fees", DataType::UInt64, true),
+        Field::new("total_priority_fees", DataType::UInt64, true),
+        Field::new("total_tips", DataType::UInt64, true),
+        Field::new("total_jito_tips", DataType::UInt64, true),
+        Field::new("total_bloxroute_tips", DataType::UInt64, true),
+        Field::new("total_fees_burned", DataType::UInt64, true),
+        Field::new("total_validator_fees", DataType::UInt64, true),
+        Field::new("fee_reward", DataType::Int64, true),
+        Field::new("total_compute_units", DataType::UInt64, true),
+        Field::new("total_sandwiches", DataType::UInt64, true),
+        Field::new("total_atomic_arbitrages", DataType::UInt64, true),
+        // Classified transactions retained in the block, excluding votes
+        Field::new("transaction_count", DataType::UInt32, false),
+    ]
+}
+
+/// One row per classified transaction, from [`classifier_core::ClassifiableTransaction`]
+fn transactions_fields() -> Vec<Field> {
+    vec![
+        Field::new("slot", DataType::UInt64, false),
+        Field::new("block_order", DataType::UInt32, false),
+        Field::new("signature", DataType::Utf8, false),
+        // First static account key
+        Field::new("fee_payer", DataType::Utf8, true),
+        Field::new("success", DataType::Boolean, false),
+        Field::new("fee", DataType::UInt64, false),
+        Field::new("num_signatures", DataType::UInt8, false),
+        Field::new("compute_units_consumed", DataType::UInt64, true),
+        Field::new("compute_unit_limit", DataType::UInt32, true),
+        Field::new("compute_unit_price", DataType::UInt64, true),
+        Field::new("nonce_account", DataType::Utf8, true),
+    ]
+}
+
+/// One row per swap within a transaction, from [`actions::DexSwap`]
+fn dex_swaps_fields() -> Vec<Field> {
+    vec![
+        Field::new("slot", DataType::UInt64, false),
+        Field::new("block_order", DataType::UInt32, false),
+        // Position of the swap within its transaction
+        Field::new("swap_index", DataType::UInt32, false),
+        Field::new("signature", DataType::Utf8, false),
+        Field::new("input_mint", DataType::Utf8, false),
+        Field::new("output_mint", DataType::Utf8, false),
+        Field::new("input_token_account", DataType::Utf8, false),
+        Field::new("output_token_account", DataType::Utf8, false),
+        Field::new("input_amount", DataType::UInt64, false),
+        Field::new("output_amount", DataType::UInt64, false),
+    ]
+}
+
+/// One row per MEV label, from [`classifier_core::TransactionTag`]
+fn tags_fields() -> Vec<Field> {
+    vec![
+        Field::new("slot", DataType::UInt64, false),
+        Field::new("block_order", DataType::UInt32, false),
+        Field::new("signature", DataType::Utf8, false),
+        // `atomicArbitrage`, `sandwichFrontrun`, `sandwichVictim` or `sandwichBackrun`,
+        // as in stored transaction documents
+        Field::new("tag_type", DataType::Utf8, false),
+        // Arbitrageur, sandwich attacker or victim
+        Field::new("address", DataType::Utf8, false),
+        // Arbitraged mint, or the token bought or sold in a sandwich
+        Field::new("mint", DataType::Utf8, false),
+        // Sandwich swap amount, null for arbitrages
+        Field::new("amount", DataType::UInt64, true),
+        // Null for sandwich frontruns and victims
+        Field::new(
+            "profit_amount",
+            DataType::Decimal128(PROFIT_PRECISION, 0),
+            true,
+        ),
+    ]
+}
+
+/// One row per tip, from [`actions::JitoTip`] and [`actions::BloxrouteTip`]
+fn tips_fields() -> Vec<Field> {
+    vec![
+        Field::new("slot", DataType::UInt64, false),
+        Field::new("block_order", DataType::UInt32, false),
+        Field::new("signature", DataType::Utf8, false),
+        // `jito` or `bloxroute`
+        Field::new("provider", DataType::Utf8, false),
+        Field::new("tipper", DataType::Utf8, false),
+        Field::new("amount", DataType::UInt64, false),
+    ]
+}
@@ -0,0 +1,423 @@
+use std::sync::Arc;
+
+use actions::{Action, ActionNodeId, ActionTree, Block, DexSwap};
+use arrow::{
+    array::{
+        ArrayRef, BooleanArray, Decimal128Array, Int64Array, StringArray, UInt32Array, UInt64Array,
+        UInt8Array,
+    },
+    datatypes::{Decimal128Type, DecimalType},
+    error::ArrowError,
+    record_batch::RecordBatch,
+};
+use classifier_core::{ClassifiableTransaction, SandwichAttackTag, TransactionTag};
+use solana_sdk::pubkey::Pubkey;
+
+use super::schema::{Table, PROFIT_PRECISION};
+
+/// Rows of every table, buffered until a partition is written
+#[derive(Default)]
+pub(super) struct TableRows {
+    blocks: BlockRows,
+    transactions: TransactionRows,
+    dex_swaps: DexSwapRows,
+    tags: TagRows,
+    tips: TipRows,
+}
+
+impl TableRows {
+    /// Adds the rows of a classified and post processed block tree
+    pub(super) fn push_block(&mut self, tree: &ActionTree, block: &Block) {
+        let mut block_order = 0;
+
+        // Transactions are numbered in tree order, as in stored transaction documents
+        for node_id in tree.descendants(tree.root()) {
+            if let Action::ClassifiableTransaction(tx) = tree.get(node_id).unwrap().get() {
+                self.push_transaction(tree, node_id, block.slot, block_order, tx);
+                block_order += 1;
+            }
+        }
+
+        self.blocks.push(block, block_order);
+    }
+
+    fn push_transaction(
+        &mut self,
+        tree: &ActionTree,
+        tx_id: ActionNodeId,
+        slot: u64,
+        block_order: u32,
+        tx: &ClassifiableTransaction,
+    ) {
+        let signature = tx.signature.to_string();
+        self.transactions.push(slot, block_order, &signature, tx);
+
+        for tag in &tx.tags {
+            self.tags.push(slot, block_order, &signature, tag);
+        }
+
+        let mut swap_index = 0;
+        for node_id in tree.descendants(tx_id).skip(1) {
+            match tree.get(node_id).unwrap().get() {
+                Action::DexSwap(swap) => {
+                    self.dex_swaps
+                        .push(slot, block_order, swap_index, &signature, swap);
+                    swap_index += 1;
+                }
+                Action::JitoTip(tip) => {
+                    self.tips.push(
+                        slot,
+                        block_order,
+                        &signature,
+                        "jito",
+                        &tip.tipper,
+                        tip.tip_amount,
+                    );
+                }
+                Action::BloxrouteTip(tip) => {
+                    self.tips.push(
+                        slot,
+                        block_order,
+                        &signature,
+                        "bloxroute",
+                        &tip.tipper,
+                        tip.tip_amount,
+                    );
+                }
+                _ => {}
+            }
+        }
+    }
+
+    pub(super) fn is_empty(&self) -> bool {
+        self.blocks.slot.is_empty()
+    }
+
+    /// Converts the buffered rows of a table into a record batch, leaving the table empty
+    pub(super) fn take_batch(&mut self, table: Table) -> Result<RecordBatch, ArrowError> {
+        let columns = match table {
+            Table::Blocks => std::mem::take(&mut self.blocks).into_columns(),
+            Table::Transactions => std::mem::take(&mut self.transactions).into_columns(),
+            Table::DexSwaps => std::mem::take(&mut self.dex_swaps).into_columns(),
+            Table::Tags => std::mem::take(&mut self.tags).into_columns()?,
+            Table::Tips => std::mem::take(&mut self.tips).into_columns(),
+        };
+
+        RecordBatch::try_new(table.schema(), columns)
+    }
+}
+
+#[derive(Default)]
+struct BlockRows {
+    slot: Vec<u64>,
+    parent_slot: Vec<u64>,
+    block_time: Vec<i64>,
+    validator_pubkey: Vec<Option<String>>,
+    validator_name: Vec<Option<String>>,
+    validator_vote_account: Vec<Option<String>>,
+    leader_source: Vec<Option<&'static str>>,
+    total_base_fees: Vec<Option<u64>>,
+    total_priority_fees: Vec<Option<u64>>,
+    total_tips: Vec<Option<u64>>,
+    total_jito_tips: Vec<Option<u64>>,
+    total_bloxroute_tips: Vec<Option<u64>>,
+    total_fees_burned: Vec<Option<u64>>,
+    total_validator_fees: Vec<Option<u64>>,
+    fee_reward: Vec<Option<i64>>,
+    total_compute_units: Vec<Option<u64>>,
+    total_sandwiches: Vec<Option<u64>>,
+    total_atomic_arbitrages: Vec<Option<u64>>,
+    transaction_count: Vec<u32>,
+}
+
+impl BlockRows {
+    fn push(&mut self, block: &Block, transaction_count: u32) {
+        self.slot.push(block.slot);
+        self.parent_slot.push(block.parent_slot);
+        self.block_time.push(block.block_time);
+        self.validator_pubkey.push(block.validator_pubkey.clone());
+        self.validator_name.push(block.validator_name.clone());
+        self.validator_vote_account
+            .push(block.validator_vote_account.clone());
+        self.leader_source
+            .push(block.leader_source.map(|source| source.as_str()));
+        self.total_base_fees.push(block.total_base_fees);
+        self.total_priority_fees.push(block.total_priority_fees);
+        self.total_tips.push(block.total_tips);
+        self.total_jito_tips.push(block.total_jito_tips);
+        self.total_bloxroute_tips.push(block.total_bloxroute_tips);
+        self.total_fees_burned.push(block.total_fees_burned);
+        self.total_validator_fees.push(block.total_validator_fees);
+        self.fee_reward.push(block.fee_reward);
+        self.total_compute_units.push(block.total_compute_units);
+        self.total_sandwiches.push(block.total_sandwiches);
+        self.total_atomic_arbitrages
+            .push(block.total_atomic_arbitrages);
+        self.transaction_count.push(transaction_count);
+    }
+
+    fn into_columns(self) -> Vec<ArrayRef> {
+        vec![
+            Arc::new(UInt64Array::from(self.slot)),
+            Arc::new(UInt64Array::from(self.parent_slot)),
+            Arc::new(Int64Array::from(self.block_time)),
+            Arc::new(StringArray::from(self.validator_pubkey)),
+            Arc::new(StringArray::from(self.validator_name)),
+            Arc::new(StringArray::from(self.validator_vote_account)),
+            Arc::new(StringArray::from(self.leader_source)),
+            Arc::new(UInt64Array::from(self.total_base_fees)),
+            Arc::new(UInt64Array::from(self.total_priority_fees)),
+            Arc::new(UInt64Array::from(self.total_tips)),
+            Arc::new(UInt64Array::from(self.total_jito_tips)),
+            Arc::new(UInt64Array::from(self.total_bloxroute_tips)),
+            Arc::new(UInt64Array::from(self.total_fees_burned)),
+            Arc::new(UInt64Array::from(self.total_validator_fees)),
+            Arc::new(Int64Array::from(self.fee_reward)),
+            Arc::new(UInt64Array::from(self.total_compute_units)),
+            Arc::new(UInt64Array::from(self.total_sandwiches)),
+            Arc::new(UInt64Array::from(self.total_atomic_arbitrages)),
+            Arc::new(UInt32Array::from(self.transaction_count)),
+        ]
+    }
+}
+
+#[derive(Default)]
+struct TransactionRows {
+    slot: Vec<u64>,
+    block_order: Vec<u32>,
+    signature: Vec<String>,
+    fee_payer: Vec<Option<String>>,
+    success: Vec<bool>,
+    fee: Vec<u64>,
+    num_signatures: Vec<u8>,
+    compute_units_consumed: Vec<Option<u64>>,
+    compute_unit_limit: Vec<Option<u32>>,
+    compute_unit_price: Vec<Option<u64>>,
+    nonce_account: Vec<Option<String>>,
+}
+
+impl TransactionRows {
+    fn push(&mut self, slot: u64, block_order: u32, signature: &str, tx: &ClassifiableTransaction) {
+        self.slot.push(slot);
+        self.block_order.push(block_order);
+        self.signature.push(signature.to_string());
+        self.fee_payer
+            .push(tx.static_keys.first().map(Pubkey::to_string));
+        self.success.push(tx.status.is_ok());
+        self.fee.push(tx.fee);
+        self.num_signatures.push(tx.num_signatures);
+        self.compute_units_consumed.push(tx.compute_units_consumed);
+        self.compute_unit_limit.push(tx.compute_unit_limit);
+        self.compute_unit_price.push(tx.compute_unit_price);
+        self.nonce_account
+            .push(tx.nonce_account.as_ref().map(Pubkey::to_string));
+    }
+
+    fn into_columns(self) -> Vec<ArrayRef> {
+        vec![
+            Arc::new(UInt64Array::from(self.slot)),
+            Arc::new(UInt32Array::from(self.block_order)),
+            Arc::new(StringArray::from(self.signature)),
+            Arc::new(StringArray::from(self.fee_payer)),
+            Arc::new(BooleanArray::from(self.success)),
+            Arc::new(UInt64Array::from(self.fee)),
+            Arc::new(UInt8Array::from(self.num_signatures)),
+            Arc::new(UInt64Array::from(self.compute_units_consumed)),
+            Arc::new(UInt32Array::from(self.compute_unit_limit)),
+            Arc::new(UInt64Array::from(self.compute_unit_price)),
+            Arc::new(StringArray::from(self.nonce_account)),
+        ]
+    }
+}
+
+#[derive(Default)]
+struct DexSwapRows {
+    slot: Vec<u64>,
+    block_order: Vec<u32>,
+    swap_index: Vec<u32>,
+    signature: Vec<String>,
+    input_mint: Vec<String>,
+    output_mint: Vec<String>,
+    input_token_account: Vec<String>,
+    output_token_account: Vec<String>,
+    input_amount: Vec<u64>,
+    output_amount: Vec<u64>,
+}
+
+impl DexSwapRows {
+    fn push(
+        &mut self,
+        slot: u64,
+        block_order: u32,
+        swap_index: u32,
+        signature: &str,
+        swap: &DexSwap,
+    ) {
+        self.slot.push(slot);
+        self.block_order.push(block_order);
+        self.swap_index.push(swap_index);
+        self.signature.push(signature.to_string());
+        self.input_mint.push(swap.input_mint.to_string());
+        self.output_mint.push(swap.output_mint.to_string());
+        self.input_token_account
+            .push(swap.input_token_account.to_string());
+        self.output_token_account
+            .push(swap.output_token_account.to_string());
+        self.input_amount.push(swap.input_amount);
+        self.output_amount.push(swap.output_amount);
+    }
+
+    fn into_columns(self) -> Vec<ArrayRef> {
+        vec![
+            Arc::new(UInt64Array::from(self.slot)),
+            Arc::new(UInt32Array::from(self.block_order)),
+            Arc::new(UInt32Array::from(self.swap_index)),
+            Arc::new(StringArray::from(self.signature)),
+            Arc::new(StringArray::from(self.input_mint)),
+            Arc::new(StringArray::from(self.output_mint)),
+            Arc::new(StringArray::from(self.input_token_account)),
+            Arc::new(StringArray::from(self.output_token_account)),
+            Arc::new(UInt64Array::from(self.input_amount)),
+            Arc::new(UInt64Array::from(self.output_amount)),
+        ]
+    }
+}
+
+#[derive(Default)]
+struct TagRows {
+    slot: Vec<u64>,
+    block_order: Vec<u32>,
+    signature: Vec<String>,
+    tag_type: Vec<&'static str>,
+    address: Vec<String>,
+    mint: Vec<String>,
+    amount: Vec<Option<u64>>,
+    profit_amount: Vec<Option<i128>>,
+}
+
+impl TagRows {
+    fn push(&mut self, slot: u64, block_order: u32, signature: &str, tag: &TransactionTag) {
+        let (tag_type, address, mint, amount, profit_amount) = match tag {
+            TransactionTag::AtomicArbitrage(arb) => (
+                tag.type_name(),
+                arb.address,
+                arb.mint,
+                None,
+                Some(arb.profit_amount),
+            ),
+            TransactionTag::SandwichAttack(SandwichAttackTag::Frontrun {
+                token_bought,
+                amount,
+                attacker_pubkey,
+            }) => (
+                tag.type_name(),
+                *attacker_pubkey,
+                *token_bought,
+                Some(*amount),
+                None,
+            ),
+            TransactionTag::SandwichAttack(SandwichAttackTag::Victim {
+                token_bought,
+                amount,
+                victim_pubkey,
+            }) => (
+                tag.type_name(),
+                *victim_pubkey,
+                *token_bought,
+                Some(*amount),
+                None,
+            ),
+            TransactionTag::SandwichAttack(SandwichAttackTag::Backrun {
+                token_sold,
+                amount,
+                attacker_pubkey,
+                profit_amount,
+            }) => (
+                tag.type_name(),
+                *attacker_pubkey,
+                *token_sold,
+                Some(*amount),
+                Some(*profit_amount as i128),
+            ),
+        };
+
+        // Writing a profit wider than the column would produce an invalid file
+        let profit_amount = profit_amount.filter(|profit_amount| {
+            let valid =
+                Decimal128Type::is_valid_decimal_precision(*profit_amount, PROFIT_PRECISION);
+            if !valid {
+                tracing::warn!(
+                    "Profit {} of {} does not fit the export",
+                    profit_amount,
+                    signature
+                );
+            }
+            valid
+        });
+
+        self.slot.push(slot);
+        self.block_order.push(block_order);
+        self.signature.push(signature.to_string());
+        self.tag_type.push(tag_type);
+        self.address.push(address.to_string());
+        self.mint.push(mint.to_string());
+        self.amount.push(amount);
+        self.profit_amount.push(profit_amount);
+    }
+
+    fn into_columns(self) -> Result<Vec<ArrayRef>, ArrowError> {
+        let profit_amount = Decimal128Array::from(self.profit_amount)
+            .with_precision_and_scale(PROFIT_PRECISION, 0)?;
+
+        Ok(vec![
+            Arc::new(UInt64Array::from(self.slot)),
+            Arc::new(UInt32Array::from(self.block_order)),
+            Arc::new(StringArray::from(self.signature)),
+            Arc::new(StringArray::from(self.tag_type)),
+            Arc::new(StringArray::from(self.address)),
+            Arc::new(StringArray::from(self.mint)),
+            Arc::new(UInt64Array::from(self.amount)),
+            Arc::new(profit_amount),
+        ])
+    }
+}
+
+#[derive(Default)]
+struct TipRows {
+    slot: Vec<u64>,
+    block_order: Vec<u32>,
+    signature: Vec<String>,
+    provider: Vec<&'static str>,
+    tipper: Vec<String>,
+    amount: Vec<u64>,
+}
+
+impl TipRows {
+    fn push(
+        &mut self,
+        slot: u64,
+        block_order: u32,
+        signature: &str,
+        provider: &'static str,
+        tipper: &Pubkey,
+        amount: u64,
+    ) {
+        self.slot.push(slot);
+        self.block_order.push(block_order);
+        self.signature.push(signature.to_string());
+        self.provider.push(provider);
+        self.tipper.push(tipper.to_string());
+        self.amount.push(amount);
+    }
+
+    fn into_columns(self) -> Vec<ArrayRef> {
+        vec![
+            Arc::new(UInt64Array::from(self.slot)),
+            Arc::new(UInt32Array::from(self.block_order)),
+            Arc::new(StringArray::from(self.signature)),
+            Arc::new(StringArray::from(self.provider)),
+            Arc::new(StringArray::from(self.tipper)),
+            Arc::new(UInt64Array::from(self.amount)),
+        ]
+    }
+}
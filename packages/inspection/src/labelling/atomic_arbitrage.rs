@@ -81,6 +81,8 @@ fn try_find_atomic_arb(
 
     let last_swap = last_swap.unwrap();
 
+    // Swaps report native and wrapped SOL with the same mint and in lamports, so round
+    // trips from SOL into wrapped SOL compare equal
     if first_swap.input_mint == last_swap.output_mint {
         let profit_amount = last_swap.output_amount as i128 - first_swap.input_amount as i128;
 
@@ -0,0 +1,53 @@
+use actions::{Action, ActionNodeId, ActionTree, MevComputeUnits};
+use classifier_core::{SandwichAttackTag, TransactionTag};
+
+/// Groups compute units consumed by each transaction in the block by its MEV label.
+/// Must run after all transaction tags have been applied.
+pub fn summarize_mev_compute_units(root: ActionNodeId, tree: &mut ActionTree) {
+    let mut mev_compute_units = MevComputeUnits::default();
+
+    for child_id in tree.children(root) {
+        let txn = match tree.get(child_id).unwrap().get() {
+            Action::ClassifiableTransaction(txn) => txn,
+            _ => continue,
+        };
+
+        let compute_units = txn.compute_units_consumed.unwrap_or_default();
+
+        let is_sandwich_attack = txn.tags.iter().any(|tag| {
+            matches!(
+                tag,
+                TransactionTag::SandwichAttack(
+                    SandwichAttackTag::Frontrun { .. } | SandwichAttackTag::Backrun { .. }
+                )
+            )
+        });
+        let is_arbitrage = txn
+            .tags
+            .iter()
+            .any(|tag| matches!(tag, TransactionTag::AtomicArbitrage(_)));
+        let is_sandwich_victim = txn.tags.iter().any(|tag| {
+            matches!(
+                tag,
+                TransactionTag::SandwichAttack(SandwichAttackTag::Victim { .. })
+            )
+        });
+
+        if is_sandwich_attack {
+            mev_compute_units.sandwich_attack += compute_units;
+        } else if is_arbitrage {
+            mev_compute_units.atomic_arbitrage += compute_units;
+        } else if is_sandwich_victim {
+            mev_compute_units.sandwich_victim += compute_units;
+        } else {
+            mev_compute_units.other += compute_units;
+        }
+    }
+
+    match tree.get_mut(root).unwrap().get_mut() {
+        Action::Block(block) => {
+            block.mev_compute_units = Some(mev_compute_units);
+        }
+        _ => unreachable!("Root node should be a block"),
+    }
+}
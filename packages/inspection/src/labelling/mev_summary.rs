@@ -0,0 +1,34 @@
+use actions::{Action, ActionNodeId, ActionTree};
+use classifier_core::{SandwichAttackTag, TransactionTag};
+
+/// Counts MEV events in the block so blocks can be compared by leader.
+/// Each sandwich is counted once, by its backrun.
+pub fn summarize_mev_counts(root: ActionNodeId, tree: &mut ActionTree) {
+    let mut total_sandwiches = 0;
+    let mut total_atomic_arbitrages = 0;
+
+    for node_id in tree.descendants(root) {
+        let txn = match tree.get(node_id).unwrap().get() {
+            Action::ClassifiableTransaction(txn) => txn,
+            _ => continue,
+        };
+
+        for tag in &txn.tags {
+            match tag {
+                TransactionTag::AtomicArbitrage(_) => total_atomic_arbitrages += 1,
+                TransactionTag::SandwichAttack(SandwichAttackTag::Backrun { .. }) => {
+                    total_sandwiches += 1
+                }
+                _ => {}
+            }
+        }
+    }
+
+    match tree.get_mut(root).unwrap().get_mut() {
+        Action::Block(block) => {
+            block.total_sandwiches = Some(total_sandwiches);
+            block.total_atomic_arbitrages = Some(total_atomic_arbitrages);
+        }
+        _ => unreachable!("Root node should be a block"),
+    }
+}
@@ -1,8 +1,11 @@
 use actions::ActionTree;
 
 mod atomic_arbitrage;
+mod compute_units;
 mod dex_swap;
+mod mev_summary;
 mod sandwich;
+mod votes;
 
 pub fn label_tree(tree: &mut ActionTree) {
     let root = tree.root();
@@ -10,4 +13,16 @@ pub fn label_tree(tree: &mut ActionTree) {
     dex_swap::classify_dex_swaps(root, tree);
     atomic_arbitrage::classify_atomic_arbitrage(root, tree);
     sandwich::classify_sandwich_attack(root, tree);
+    compute_units::summarize_mev_compute_units(root, tree);
+    mev_summary::summarize_mev_counts(root, tree);
+    votes::summarize_votes(root, tree);
+}
+
+/// Labels a tree rooted at a single transaction. Labels which need the rest of the block,
+/// such as sandwiches and block summaries, are skipped.
+pub fn label_transaction_tree(tree: &mut ActionTree) {
+    let root = tree.root();
+
+    dex_swap::classify_dex_swaps(root, tree);
+    atomic_arbitrage::classify_atomic_arbitrage(root, tree);
 }
@@ -0,0 +1,164 @@
+use actions::{Action, ActionNodeId, ActionTree, VoteSummary};
+
+/// Summarizes vote instructions in the block. Must run before post processing, which
+/// drops vote actions unless they are retained.
+///
+/// Latency is the number of slots between the block and the newest slot each vote
+/// targets. Votes in failed transactions are counted separately and excluded from
+/// latency.
+pub fn summarize_votes(root: ActionNodeId, tree: &mut ActionTree) {
+    let slot = match tree.get(root).unwrap().get() {
+        Action::Block(block) => block.slot,
+        _ => unreachable!("Root node should be a block"),
+    };
+
+    let mut summary = VoteSummary::default();
+    let mut latencies = Vec::new();
+
+    for txn_id in tree.children(root) {
+        let txn = match tree.get(txn_id).unwrap().get() {
+            Action::ClassifiableTransaction(txn) => txn,
+            _ => continue,
+        };
+
+        let mut has_vote = false;
+
+        for node_id in tree.descendants(txn_id) {
+            let vote = match tree.get(node_id).unwrap().get() {
+                Action::Vote(vote) => vote,
+                _ => continue,
+            };
+
+            has_vote = true;
+
+            if txn.status.is_err() {
+                summary.failed_votes += 1;
+                continue;
+            }
+
+            summary.votes += 1;
+
+            if let Some(target_slot) = vote.update().target_slot() {
+                latencies.push(slot.saturating_sub(target_slot));
+            }
+        }
+
+        if has_vote {
+            summary.compute_units += txn.compute_units_consumed.unwrap_or_default();
+        }
+    }
+
+    latencies.sort_unstable();
+
+    summary.min_latency = latencies.first().copied();
+    summary.max_latency = latencies.last().copied();
+    summary.median_latency = latencies.get(latencies.len() / 2).copied();
+
+    match tree.get_mut(root).unwrap().get_mut() {
+        Action::Block(block) => {
+            block.vote_summary = Some(summary);
+        }
+        _ => unreachable!("Root node should be a block"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use actions::{Block, Vote, VoteUpdate};
+    use classifier_core::ClassifiableTransaction;
+    use solana_sdk::{
+        hash::Hash,
+        message::{Message, VersionedMessage},
+        pubkey::Pubkey,
+        signature::Signature,
+        transaction::VersionedTransaction,
+    };
+    use solana_transaction_status::UiTransactionStatusMeta;
+
+    use super::*;
+
+    fn transaction(failed: bool, compute_units: u64) -> Action {
+        let err = failed.then(|| serde_json::json!({ "InstructionError": [0, { "Custom": 1 }] }));
+        let status = match &err {
+            Some(err) => serde_json::json!({ "Err": err }),
+            None => serde_json::json!({ "Ok": null }),
+        };
+        let meta: UiTransactionStatusMeta = serde_json::from_value(serde_json::json!({
+            "err": err,
+            "status": status,
+            "fee": 5000,
+            "preBalances": [],
+            "postBalances": [],
+            "computeUnitsConsumed": compute_units,
+        }))
+        .unwrap();
+
+        ClassifiableTransaction::new(
+            VersionedTransaction {
+                signatures: vec![Signature::new_unique()],
+                message: VersionedMessage::Legacy(Message::new(&[], Some(&Pubkey::new_unique()))),
+            },
+            meta,
+        )
+        .into()
+    }
+
+    fn vote(slots: &[u64]) -> Action {
+        Vote::CompactUpdateVoteState(VoteUpdate {
+            vote_account: Pubkey::new_unique(),
+            vote_authority: Pubkey::new_unique(),
+            slots: slots.to_vec(),
+            root: None,
+            hash: Hash::default(),
+            timestamp: None,
+        })
+        .into()
+    }
+
+    fn summary(transactions: Vec<(Action, Vec<Action>)>) -> Option<VoteSummary> {
+        let mut tree = ActionTree::new(Block::new(100, 99, 0, None).into());
+        for (transaction, actions) in transactions {
+            let txn_id = tree.insert_child(tree.root(), transaction);
+            for action in actions {
+                tree.insert_child(txn_id, action);
+            }
+        }
+
+        summarize_votes(tree.root(), &mut tree);
+
+        match tree.get(tree.root()).unwrap().get() {
+            Action::Block(block) => block.vote_summary.clone(),
+            _ => unreachable!("Root node should be a block"),
+        }
+    }
+
+    #[test]
+    fn summarizes_votes_and_latency() {
+        let summary = summary(vec![
+            (transaction(false, 2_000), vec![vote(&[95, 97])]),
+            (transaction(false, 3_000), vec![vote(&[90])]),
+            (transaction(false, 2_500), vec![vote(&[99])]),
+            (transaction(true, 1_000), vec![vote(&[98])]),
+            (transaction(false, 50_000), vec![]),
+        ]);
+
+        assert_eq!(
+            summary,
+            Some(VoteSummary {
+                votes: 3,
+                failed_votes: 1,
+                compute_units: 8_500,
+                min_latency: Some(1),
+                max_latency: Some(10),
+                median_latency: Some(3),
+            })
+        );
+    }
+
+    #[test]
+    fn blocks_without_votes_have_empty_summary() {
+        let summary = summary(vec![(transaction(false, 50_000), vec![])]);
+
+        assert_eq!(summary, Some(VoteSummary::default()));
+    }
+}
@@ -1,6 +1,11 @@
 pub mod database;
+#[cfg(feature = "export")]
+pub mod export;
 pub mod filtering;
 mod jito_bundle_client;
 mod labelling;
+pub mod lookup_tables;
+pub mod rpc;
+pub mod validators;
 
-pub use labelling::label_tree;
+pub use labelling::{label_transaction_tree, label_tree};
@@ -0,0 +1,55 @@
+use std::{collections::HashMap, sync::Mutex};
+
+use classifier_core::AddressLookupTableSource;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{address_lookup_table::state::AddressLookupTable, pubkey::Pubkey};
+
+/// Resolves address lookup tables from their current on-chain state, caching each table
+/// after the first fetch.
+pub struct RpcLookupTableSource {
+    rpc_client: RpcClient,
+    tables: Mutex<HashMap<Pubkey, Option<Vec<Pubkey>>>>,
+}
+
+impl RpcLookupTableSource {
+    pub fn new(rpc_url: String) -> Self {
+        Self {
+            rpc_client: RpcClient::new(rpc_url),
+            tables: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn fetch_addresses(&self, table: &Pubkey) -> Option<Vec<Pubkey>> {
+        let account = match self.rpc_client.get_account(table) {
+            Ok(account) => account,
+            Err(err) => {
+                tracing::warn!("Failed to fetch lookup table {}: {:?}", table, err);
+                return None;
+            }
+        };
+
+        match AddressLookupTable::deserialize(&account.data) {
+            Ok(lookup_table) => Some(lookup_table.addresses.to_vec()),
+            Err(err) => {
+                tracing::warn!("Failed to deserialize lookup table {}: {:?}", table, err);
+                None
+            }
+        }
+    }
+}
+
+impl AddressLookupTableSource for RpcLookupTableSource {
+    fn get_lookup_table_addresses(&self, table: &Pubkey) -> Option<Vec<Pubkey>> {
+        if let Some(addresses) = self.tables.lock().unwrap().get(table) {
+            return addresses.clone();
+        }
+
+        let addresses = self.fetch_addresses(table);
+        self.tables
+            .lock()
+            .unwrap()
+            .insert(*table, addresses.clone());
+
+        addresses
+    }
+}
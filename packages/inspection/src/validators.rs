@@ -0,0 +1,247 @@
+use std::{collections::HashMap, path::Path};
+
+use actions::{Action, ActionTree, LeaderSource};
+use serde::Deserialize;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ValidatorRegistryError {
+    #[error("Failed to read file: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Failed to parse validator metadata: {0}")]
+    InvalidMetadata(#[from] serde_json::Error),
+
+    #[error("Invalid leader schedule entry on line {0}")]
+    InvalidLeaderSchedule(usize),
+}
+
+type Result<T> = std::result::Result<T, ValidatorRegistryError>;
+
+/// Entry of the validator metadata file
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidatorMetadata {
+    pub identity: String,
+    pub name: Option<String>,
+    pub vote_account: Option<String>,
+}
+
+/// Local lookup of block leaders and validator details, used to enrich blocks whose
+/// leader could not be resolved from the block rewards.
+#[derive(Debug, Default)]
+pub struct ValidatorRegistry {
+    leader_schedule: HashMap<u64, String>,
+    validators: HashMap<String, ValidatorMetadata>,
+}
+
+impl ValidatorRegistry {
+    pub fn load(
+        leader_schedule_path: Option<&Path>,
+        validator_metadata_path: Option<&Path>,
+    ) -> Result<Self> {
+        let mut registry = Self::default();
+
+        if let Some(path) = leader_schedule_path {
+            registry.leader_schedule = parse_leader_schedule(&std::fs::read_to_string(path)?)?;
+            tracing::info!(
+                "Loaded leader schedule with {} slots",
+                registry.leader_schedule.len()
+            );
+        }
+
+        if let Some(path) = validator_metadata_path {
+            let validators: Vec<ValidatorMetadata> = serde_json::from_slice(&std::fs::read(path)?)?;
+
+            registry.validators = validators
+                .into_iter()
+                .map(|validator| (validator.identity.clone(), validator))
+                .collect();
+            tracing::info!(
+                "Loaded metadata for {} validators",
+                registry.validators.len()
+            );
+        }
+
+        Ok(registry)
+    }
+
+    pub fn scheduled_leader(&self, slot: u64) -> Option<&str> {
+        self.leader_schedule.get(&slot).map(String::as_str)
+    }
+
+    pub fn metadata(&self, identity: &str) -> Option<&ValidatorMetadata> {
+        self.validators.get(identity)
+    }
+
+    /// Fill in the block leader from the leader schedule if it is unknown, then attach
+    /// the leader's name and vote account when metadata is available.
+    pub fn enrich_block(&self, tree: &mut ActionTree) {
+        let root = tree.root();
+        let block = match tree.get_mut(root).unwrap().get_mut() {
+            Action::Block(block) => block,
+            _ => unreachable!("Root node should be a block"),
+        };
+
+        if block.validator_pubkey.is_none() {
+            if let Some(leader) = self.scheduled_leader(block.slot) {
+                block.validator_pubkey = Some(leader.to_string());
+                block.leader_source = Some(LeaderSource::LeaderSchedule);
+            }
+        }
+
+        let metadata = match block
+            .validator_pubkey
+            .as_deref()
+            .and_then(|identity| self.metadata(identity))
+        {
+            Some(metadata) => metadata,
+            None => return,
+        };
+
+        block.validator_name = metadata.name.clone();
+        block.validator_vote_account = metadata.vote_account.clone();
+    }
+}
+
+/// Parses the output of `solana leader-schedule`, one `<slot> <identity>` pair per line.
+fn parse_leader_schedule(contents: &str) -> Result<HashMap<u64, String>> {
+    let mut schedule = HashMap::new();
+
+    for (line_idx, line) in contents.lines().enumerate() {
+        let mut parts = line.split_whitespace();
+
+        let (slot, identity) = match (parts.next(), parts.next()) {
+            (None, _) => continue,
+            (Some(slot), Some(identity)) => (slot, identity),
+            _ => return Err(ValidatorRegistryError::InvalidLeaderSchedule(line_idx + 1)),
+        };
+
+        let slot = slot
+            .parse::<u64>()
+            .map_err(|_| ValidatorRegistryError::InvalidLeaderSchedule(line_idx + 1))?;
+
+        schedule.insert(slot, identity.to_string());
+    }
+
+    Ok(schedule)
+}
+
+#[cfg(test)]
+mod tests {
+    use actions::Block;
+
+    use super::*;
+
+    const LEADER_SCHEDULE: &str = "
+  100       LeaderA
+  101       LeaderA
+
+  102       LeaderB
+";
+
+    const VALIDATOR_METADATA: &str = r#"[
+        { "identity": "LeaderA", "name": "Validator A", "voteAccount": "VoteA" },
+        { "identity": "LeaderC" }
+    ]"#;
+
+    fn registry() -> ValidatorRegistry {
+        let dir = std::env::temp_dir().join(format!(
+            "validators-{}-{:?}",
+            std::process::id(),
+            std::thread::current().id()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let leader_schedule_path = dir.join("leader_schedule.txt");
+        let validator_metadata_path = dir.join("validators.json");
+        std::fs::write(&leader_schedule_path, LEADER_SCHEDULE).unwrap();
+        std::fs::write(&validator_metadata_path, VALIDATOR_METADATA).unwrap();
+
+        let registry =
+            ValidatorRegistry::load(Some(&leader_schedule_path), Some(&validator_metadata_path));
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        registry.unwrap()
+    }
+
+    fn enriched_block(registry: &ValidatorRegistry, block: Block) -> Block {
+        let mut tree = ActionTree::new(block.into());
+        registry.enrich_block(&mut tree);
+
+        match tree.get(tree.root()).unwrap().get() {
+            Action::Block(block) => block.clone(),
+            _ => unreachable!("Root node should be a block"),
+        }
+    }
+
+    #[test]
+    fn parses_leader_schedule() {
+        let schedule = parse_leader_schedule(LEADER_SCHEDULE).unwrap();
+
+        assert_eq!(schedule.len(), 3);
+        assert_eq!(schedule[&101], "LeaderA");
+        assert_eq!(schedule[&102], "LeaderB");
+    }
+
+    #[test]
+    fn rejects_invalid_leader_schedule_lines() {
+        assert!(matches!(
+            parse_leader_schedule("100 LeaderA\n101"),
+            Err(ValidatorRegistryError::InvalidLeaderSchedule(2))
+        ));
+        assert!(matches!(
+            parse_leader_schedule("slot LeaderA"),
+            Err(ValidatorRegistryError::InvalidLeaderSchedule(1))
+        ));
+    }
+
+    #[test]
+    fn loads_validator_metadata() {
+        let registry = registry();
+
+        assert_eq!(registry.scheduled_leader(100), Some("LeaderA"));
+        assert_eq!(registry.scheduled_leader(103), None);
+
+        let metadata = registry.metadata("LeaderA").unwrap();
+        assert_eq!(metadata.name.as_deref(), Some("Validator A"));
+        assert_eq!(metadata.vote_account.as_deref(), Some("VoteA"));
+
+        let metadata = registry.metadata("LeaderC").unwrap();
+        assert_eq!(metadata.name, None);
+        assert_eq!(metadata.vote_account, None);
+    }
+
+    #[test]
+    fn enriches_unknown_leader_from_schedule() {
+        let block = enriched_block(&registry(), Block::new(100, 99, 0, None));
+
+        assert_eq!(block.validator_pubkey.as_deref(), Some("LeaderA"));
+        assert_eq!(block.leader_source, Some(LeaderSource::LeaderSchedule));
+        assert_eq!(block.validator_name.as_deref(), Some("Validator A"));
+        assert_eq!(block.validator_vote_account.as_deref(), Some("VoteA"));
+    }
+
+    #[test]
+    fn keeps_known_leader() {
+        let block = enriched_block(
+            &registry(),
+            Block::new(100, 99, 0, Some("LeaderC".to_string())),
+        );
+
+        assert_eq!(block.validator_pubkey.as_deref(), Some("LeaderC"));
+        assert_eq!(block.leader_source, None);
+        assert_eq!(block.validator_name, None);
+    }
+
+    #[test]
+    fn leaves_unscheduled_blocks_without_leader() {
+        let block = enriched_block(&registry(), Block::new(102, 101, 0, None));
+        assert_eq!(block.validator_pubkey.as_deref(), Some("LeaderB"));
+        assert_eq!(block.validator_name, None);
+
+        let block = enriched_block(&registry(), Block::new(103, 102, 0, None));
+        assert_eq!(block.validator_pubkey, None);
+        assert_eq!(block.leader_source, None);
+    }
+}
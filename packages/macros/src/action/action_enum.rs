@@ -1,3 +1,4 @@
+use heck::ToLowerCamelCase;
 use proc_macro2::{Ident, TokenStream};
 use quote::quote;
 use syn::{
@@ -57,6 +58,15 @@ pub fn parse_define_action_enum(input: ActionEnumInput) -> TokenStream {
         }
     });
 
+    // Name each variant in camel case, as the type of its JSON
+    let type_name_arms = variants.iter().map(|variant| {
+        let type_name = variant.to_string().to_lower_camel_case();
+
+        quote! {
+            #enum_name::#variant(_) => #type_name,
+        }
+    });
+
     // Generate match arms for each trait method dynamically
     let method_impls = trait_methods.iter().filter_map(|item| {
         if let syn::TraitItem::Method(method) = item {
@@ -103,12 +113,21 @@ pub fn parse_define_action_enum(input: ActionEnumInput) -> TokenStream {
     let expanded = quote! {
         #trait_definition
 
-        // Define the action enum
-        #[macros::action_enum]
+        // Define the action enum, with the action's name as its document type
+        #[macros::action_enum(tag = "type")]
         pub enum #enum_name {
             #(#variant_defs)*
         }
 
+        impl #enum_name {
+            /// Name of the action in camel case, used as the `type` of its JSON
+            pub fn type_name(&self) -> &'static str {
+                match self {
+                    #(#type_name_arms)*
+                }
+            }
+        }
+
         // Map variants to correct trait impls
         impl #trait_name for #enum_name {
             #(#method_impls)*
@@ -1,6 +1,14 @@
 use proc_macro2::TokenStream;
 use quote::quote;
-use syn::{ItemEnum, ItemStruct};
+use syn::{
+    parse::{Parse, ParseStream},
+    Fields, Ident, ItemEnum, ItemStruct, LitStr, Token,
+};
+
+use super::{
+    document::{gen_enum_document_impl, gen_struct_document_impl, DEFAULT_ENUM_TAG},
+    json::{gen_enum_json_impl, gen_struct_json_impl, strip_json_attributes},
+};
 
 pub fn get_action_derivations() -> TokenStream {
     quote! {
@@ -8,36 +16,88 @@ pub fn get_action_derivations() -> TokenStream {
     }
 }
 
-pub fn parse_action_attribute(input: ItemStruct) -> TokenStream {
+/// Arguments of `#[action_enum]`
+#[derive(Debug)]
+pub struct ActionEnumArgs {
+    /// Document key holding the variant name, in camel case for JSON
+    pub tag: String,
+}
+
+impl Parse for ActionEnumArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut tag = DEFAULT_ENUM_TAG.to_string();
+
+        while !input.is_empty() {
+            let name: Ident = input.parse()?;
+            input.parse::<Token![=]>()?;
+
+            if name == "tag" {
+                tag = input.parse::<LitStr>()?.value();
+            } else {
+                return Err(syn::Error::new(name.span(), "Expected 'tag'"));
+            }
+
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+        }
+
+        Ok(Self { tag })
+    }
+}
+
+pub fn parse_action_attribute(mut input: ItemStruct) -> TokenStream {
+    let derivations = get_action_derivations();
+    let document_impl = gen_struct_document_impl(&input);
+    let json_impl = gen_struct_json_impl(&input);
+
+    strip_json_attributes(&mut input.fields);
+
     let struct_name = &input.ident;
     let struct_vis = &input.vis;
     let struct_fields = &input.fields;
 
-    let derivations = get_action_derivations();
+    // Tuple and unit structs end with a semicolon
+    let semicolon = match struct_fields {
+        Fields::Named(_) => quote!(),
+        Fields::Unnamed(_) | Fields::Unit => quote!(;),
+    };
 
     // Generate the derive statement with the common traits
     let expanded = quote! {
         #derivations
-        #struct_vis struct #struct_name #struct_fields
+        #struct_vis struct #struct_name #struct_fields #semicolon
+
+        #document_impl
+        #json_impl
     };
 
     expanded
 }
 
-pub fn parse_action_attribute_enum(input: ItemEnum) -> TokenStream {
+pub fn parse_action_attribute_enum(args: ActionEnumArgs, mut input: ItemEnum) -> TokenStream {
+    let derivations = get_action_derivations();
+    let document_impl = gen_enum_document_impl(&input, &args.tag);
+    let json_impl = gen_enum_json_impl(&input, &args.tag);
+
+    for variant in input.variants.iter_mut() {
+        strip_json_attributes(&mut variant.fields);
+    }
+
     let enum_name = &input.ident;
     let enum_vis = &input.vis;
     let enum_variants = &input.variants;
     let enum_generics = &input.generics;
 
-    let derivations = get_action_derivations();
-
     // Generate the derive statement with the common traits
     let expanded = quote! {
         #derivations
         #enum_vis enum #enum_name #enum_generics {
             #enum_variants
         }
+
+        #document_impl
+        #json_impl
     };
 
     expanded
@@ -0,0 +1,163 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{parse_quote, Fields, Generics, Index, ItemEnum, ItemStruct, Path};
+
+/// Document key holding the variant name of an action enum, unless the enum sets its
+/// own with `#[action_enum(tag = "...")]`
+pub const DEFAULT_ENUM_TAG: &str = "action";
+
+/// Document key holding the variant name of an enum defined by a program's IDL
+pub const IDL_ENUM_TAG: &str = "variant";
+
+/// Require every type parameter to implement `bound`, so generic fields can be converted
+pub fn add_trait_bounds(generics: &Generics, bound: &Path) -> Generics {
+    let mut generics = generics.clone();
+    let type_params = generics
+        .type_params()
+        .map(|param| param.ident.clone())
+        .collect::<Vec<_>>();
+
+    let where_clause = generics.make_where_clause();
+    for param in type_params {
+        where_clause.predicates.push(parse_quote!(#param: #bound));
+    }
+
+    generics
+}
+
+/// Implement `ToDocumentValue` for an action struct. Named fields become document
+/// fields of the same name, tuple fields an array.
+pub fn gen_struct_document_impl(input: &ItemStruct) -> TokenStream {
+    let struct_name = &input.ident;
+    let generics = add_trait_bounds(
+        &input.generics,
+        &parse_quote!(::classifier_core::document::ToDocumentValue),
+    );
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let value = match &input.fields {
+        Fields::Named(fields) => {
+            let inserts = fields.named.iter().map(|field| {
+                let field_name = field.ident.as_ref().unwrap();
+                let key = field_name.to_string();
+
+                quote! {
+                    document.insert(
+                        #key,
+                        ::classifier_core::document::ToDocumentValue::to_document_value(&self.#field_name),
+                    );
+                }
+            });
+
+            quote! {
+                let mut document = ::classifier_core::document::Document::new();
+                #(#inserts)*
+                ::classifier_core::document::Value::Document(document)
+            }
+        }
+        Fields::Unnamed(fields) => {
+            let indices = (0..fields.unnamed.len()).map(Index::from);
+
+            quote! {
+                ::classifier_core::document::Value::Array(vec![
+                    #(::classifier_core::document::ToDocumentValue::to_document_value(&self.#indices)),*
+                ])
+            }
+        }
+        Fields::Unit => quote! {
+            ::classifier_core::document::Value::Document(::classifier_core::document::Document::new())
+        },
+    };
+
+    quote! {
+        impl #impl_generics ::classifier_core::document::ToDocumentValue for #struct_name #ty_generics #where_clause {
+            fn to_document_value(&self) -> ::classifier_core::document::Value {
+                #value
+            }
+        }
+    }
+}
+
+/// Implement `ToDocumentValue` for an action enum. The variant name is stored under
+/// the tag key, followed by the fields of the variant's document. Variants holding
+/// anything other than a document store it under `value`.
+pub fn gen_enum_document_impl(input: &ItemEnum, tag: &str) -> TokenStream {
+    let enum_name = &input.ident;
+    let generics = add_trait_bounds(
+        &input.generics,
+        &parse_quote!(::classifier_core::document::ToDocumentValue),
+    );
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let match_arms = input.variants.iter().map(|variant| {
+        let variant_name = &variant.ident;
+        let name = variant_name.to_string();
+
+        match &variant.fields {
+            Fields::Unit => quote! {
+                Self::#variant_name => (#name, None),
+            },
+            Fields::Unnamed(fields) if fields.unnamed.len() == 1 => quote! {
+                Self::#variant_name(inner) => (
+                    #name,
+                    Some(::classifier_core::document::ToDocumentValue::to_document_value(inner)),
+                ),
+            },
+            Fields::Unnamed(fields) => {
+                let bindings = (0..fields.unnamed.len())
+                    .map(|index| format_ident!("field_{}", index))
+                    .collect::<Vec<_>>();
+
+                quote! {
+                    Self::#variant_name(#(#bindings),*) => (
+                        #name,
+                        Some(::classifier_core::document::Value::Array(vec![
+                            #(::classifier_core::document::ToDocumentValue::to_document_value(#bindings)),*
+                        ])),
+                    ),
+                }
+            }
+            Fields::Named(fields) => {
+                let field_names = fields
+                    .named
+                    .iter()
+                    .map(|field| field.ident.as_ref().unwrap())
+                    .collect::<Vec<_>>();
+                let keys = field_names.iter().map(|field_name| field_name.to_string());
+
+                quote! {
+                    Self::#variant_name { #(#field_names),* } => {
+                        let mut fields = ::classifier_core::document::Document::new();
+                        #(fields.insert(
+                            #keys,
+                            ::classifier_core::document::ToDocumentValue::to_document_value(#field_names),
+                        );)*
+
+                        (#name, Some(::classifier_core::document::Value::Document(fields)))
+                    }
+                }
+            }
+        }
+    });
+
+    quote! {
+        impl #impl_generics ::classifier_core::document::ToDocumentValue for #enum_name #ty_generics #where_clause {
+            fn to_document_value(&self) -> ::classifier_core::document::Value {
+                let (variant, payload) = match self {
+                    #(#match_arms)*
+                };
+
+                let mut document = ::classifier_core::document::Document::new();
+                document.insert(#tag, variant);
+
+                match payload {
+                    Some(::classifier_core::document::Value::Document(fields)) => document.extend(fields),
+                    Some(value) => document.insert("value", value),
+                    None => {}
+                }
+
+                ::classifier_core::document::Value::Document(document)
+            }
+        }
+    }
+}
@@ -0,0 +1,383 @@
+use heck::ToLowerCamelCase;
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{
+    parse_quote, Attribute, Field, Fields, Index, ItemEnum, ItemStruct, Lit, Meta, NestedMeta,
+};
+
+use super::document::add_trait_bounds;
+
+/// JSON options of a field, set with `#[json(skip)]` or `#[json(rename = "...")]`
+struct JsonFieldArgs {
+    skip: bool,
+    rename: Option<String>,
+}
+
+impl JsonFieldArgs {
+    fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut args = Self {
+            skip: false,
+            rename: None,
+        };
+
+        for attr in attrs.iter().filter(|attr| attr.path.is_ident("json")) {
+            let list = match attr.parse_meta()? {
+                Meta::List(list) => list,
+                meta => return Err(syn::Error::new_spanned(meta, "Expected #[json(...)]")),
+            };
+
+            for nested in list.nested {
+                match nested {
+                    NestedMeta::Meta(Meta::Path(path)) if path.is_ident("skip") => {
+                        args.skip = true;
+                    }
+                    NestedMeta::Meta(Meta::NameValue(name_value))
+                        if name_value.path.is_ident("rename") =>
+                    {
+                        match name_value.lit {
+                            Lit::Str(rename) => args.rename = Some(rename.value()),
+                            lit => return Err(syn::Error::new_spanned(lit, "Expected a string")),
+                        }
+                    }
+                    nested => {
+                        return Err(syn::Error::new_spanned(
+                            nested,
+                            "Expected 'skip' or 'rename'",
+                        ))
+                    }
+                }
+            }
+        }
+
+        Ok(args)
+    }
+}
+
+/// Removes `#[json(...)]` attributes, which are only read by the action macros
+pub fn strip_json_attributes(fields: &mut Fields) {
+    for field in fields.iter_mut() {
+        field.attrs.retain(|attr| !attr.path.is_ident("json"));
+    }
+}
+
+/// Doc comment of an item, used as the description of its schema
+fn doc_description(attrs: &[Attribute]) -> Option<String> {
+    let lines = attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("doc"))
+        .filter_map(|attr| match attr.parse_meta() {
+            Ok(Meta::NameValue(name_value)) => match name_value.lit {
+                Lit::Str(doc) => Some(doc.value().trim().to_string()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    if lines.is_empty() {
+        return None;
+    }
+
+    Some(lines.join(" ").trim().to_string())
+}
+
+/// Schema expression of a field's type, with the field's doc comment as its description
+fn gen_field_schema(field: &Field) -> TokenStream {
+    let ty = &field.ty;
+    let schema = quote! {
+        <#ty as ::classifier_core::json::JsonSchema>::json_schema()
+    };
+
+    match doc_description(&field.attrs) {
+        Some(description) => quote! {{
+            let mut schema = #schema;
+            schema["description"] = ::serde_json::Value::from(#description);
+            schema
+        }},
+        None => schema,
+    }
+}
+
+/// Schema expression of an object holding every named field which is not skipped
+fn gen_object_schema(fields: &syn::FieldsNamed) -> syn::Result<TokenStream> {
+    let mut keys = Vec::new();
+    let mut schemas = Vec::new();
+
+    for field in fields.named.iter() {
+        let field_name = field.ident.as_ref().unwrap();
+        if let Some(key) = field_key(field, &field_name.to_string())? {
+            keys.push(key);
+            schemas.push(gen_field_schema(field));
+        }
+    }
+
+    Ok(quote! {
+        ::classifier_core::json::object_schema(vec![
+            #((#keys, #schemas)),*
+        ])
+    })
+}
+
+/// Schema expression of an array holding one item per tuple field
+fn gen_tuple_schema(fields: &syn::FieldsUnnamed) -> TokenStream {
+    let schemas = fields.unnamed.iter().map(gen_field_schema);
+    let len = fields.unnamed.len();
+
+    quote! {
+        ::serde_json::json!({
+            "type": "array",
+            "prefixItems": [#(#schemas),*],
+            "minItems": #len,
+            "maxItems": #len,
+        })
+    }
+}
+
+/// Sets the description of a schema expression from an item's doc comment
+fn with_description(schema: TokenStream, attrs: &[Attribute]) -> TokenStream {
+    match doc_description(attrs) {
+        Some(description) => quote! {
+            let mut schema = #schema;
+            schema["description"] = ::serde_json::Value::from(#description);
+            schema
+        },
+        None => schema,
+    }
+}
+
+/// JSON key of a field, its name in camel case unless renamed. None if skipped.
+fn field_key(field: &Field, name: &str) -> syn::Result<Option<String>> {
+    let args = JsonFieldArgs::parse(&field.attrs)?;
+    if args.skip {
+        return Ok(None);
+    }
+
+    Ok(Some(
+        args.rename.unwrap_or_else(|| name.to_lower_camel_case()),
+    ))
+}
+
+/// Inserts for every named field which is not skipped, reading each field through
+/// `access`
+fn gen_object_inserts(
+    fields: &syn::FieldsNamed,
+    access: impl Fn(&syn::Ident) -> TokenStream,
+) -> syn::Result<Vec<TokenStream>> {
+    let mut inserts = Vec::new();
+
+    for field in fields.named.iter() {
+        let field_name = field.ident.as_ref().unwrap();
+        let key = match field_key(field, &field_name.to_string())? {
+            Some(key) => key,
+            None => continue,
+        };
+        let value = access(field_name);
+
+        inserts.push(quote! {
+            object.insert(
+                #key.to_string(),
+                ::classifier_core::json::ToJsonValue::to_json_value(#value),
+            );
+        });
+    }
+
+    Ok(inserts)
+}
+
+/// Implement `ToJsonValue` and `JsonSchema` for an action struct. Named fields become
+/// object fields in camel case, tuple fields an array.
+pub fn gen_struct_json_impl(input: &ItemStruct) -> TokenStream {
+    let struct_name = &input.ident;
+    let generics = add_trait_bounds(
+        &input.generics,
+        &parse_quote!(::classifier_core::json::ToJsonValue),
+    );
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let schema_generics = add_trait_bounds(
+        &input.generics,
+        &parse_quote!(::classifier_core::json::JsonSchema),
+    );
+    let (schema_impl_generics, _, schema_where_clause) = schema_generics.split_for_impl();
+
+    let schema = match &input.fields {
+        Fields::Named(fields) => match gen_object_schema(fields) {
+            Ok(schema) => schema,
+            Err(err) => return err.to_compile_error(),
+        },
+        Fields::Unnamed(fields) => gen_tuple_schema(fields),
+        Fields::Unit => quote! {
+            ::classifier_core::json::object_schema(vec![])
+        },
+    };
+    let schema = with_description(schema, &input.attrs);
+
+    let value = match &input.fields {
+        Fields::Named(fields) => {
+            let inserts = match gen_object_inserts(fields, |field_name| quote!(&self.#field_name)) {
+                Ok(inserts) => inserts,
+                Err(err) => return err.to_compile_error(),
+            };
+
+            quote! {
+                let mut object = ::serde_json::Map::new();
+                #(#inserts)*
+                ::serde_json::Value::Object(object)
+            }
+        }
+        Fields::Unnamed(fields) => {
+            let indices = (0..fields.unnamed.len()).map(Index::from);
+
+            quote! {
+                ::serde_json::Value::Array(vec![
+                    #(::classifier_core::json::ToJsonValue::to_json_value(&self.#indices)),*
+                ])
+            }
+        }
+        Fields::Unit => quote! {
+            ::serde_json::Value::Object(::serde_json::Map::new())
+        },
+    };
+
+    quote! {
+        impl #impl_generics ::classifier_core::json::ToJsonValue for #struct_name #ty_generics #where_clause {
+            fn to_json_value(&self) -> ::serde_json::Value {
+                #value
+            }
+        }
+
+        impl #schema_impl_generics ::classifier_core::json::JsonSchema for #struct_name #ty_generics #schema_where_clause {
+            fn json_schema() -> ::serde_json::Value {
+                #schema
+            }
+        }
+    }
+}
+
+/// Implement `ToJsonValue` and `JsonSchema` for an action enum. The variant name is
+/// stored in camel case under the tag key, followed by the fields of the variant's
+/// object. Variants holding anything other than an object store it under `value`.
+pub fn gen_enum_json_impl(input: &ItemEnum, tag: &str) -> TokenStream {
+    let enum_name = &input.ident;
+    let generics = add_trait_bounds(
+        &input.generics,
+        &parse_quote!(::classifier_core::json::ToJsonValue),
+    );
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let schema_generics = add_trait_bounds(
+        &input.generics,
+        &parse_quote!(::classifier_core::json::JsonSchema),
+    );
+    let (schema_impl_generics, _, schema_where_clause) = schema_generics.split_for_impl();
+    let tag = tag.to_lower_camel_case();
+
+    let mut match_arms = Vec::new();
+    let mut variant_schemas = Vec::new();
+    for variant in input.variants.iter() {
+        let variant_name = &variant.ident;
+        let name = variant_name.to_string().to_lower_camel_case();
+
+        let payload_schema = match &variant.fields {
+            Fields::Unit => quote!(None),
+            Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                let schema = gen_field_schema(fields.unnamed.first().unwrap());
+                quote!(Some(#schema))
+            }
+            Fields::Unnamed(fields) => {
+                let schema = gen_tuple_schema(fields);
+                quote!(Some(#schema))
+            }
+            Fields::Named(fields) => match gen_object_schema(fields) {
+                Ok(schema) => quote!(Some(#schema)),
+                Err(err) => return err.to_compile_error(),
+            },
+        };
+        variant_schemas.push(quote!((#name, #payload_schema)));
+
+        let match_arm = match &variant.fields {
+            Fields::Unit => quote! {
+                Self::#variant_name => (#name, None),
+            },
+            Fields::Unnamed(fields) if fields.unnamed.len() == 1 => quote! {
+                Self::#variant_name(inner) => (
+                    #name,
+                    Some(::classifier_core::json::ToJsonValue::to_json_value(inner)),
+                ),
+            },
+            Fields::Unnamed(fields) => {
+                let bindings = (0..fields.unnamed.len())
+                    .map(|index| format_ident!("field_{}", index))
+                    .collect::<Vec<_>>();
+
+                quote! {
+                    Self::#variant_name(#(#bindings),*) => (
+                        #name,
+                        Some(::serde_json::Value::Array(vec![
+                            #(::classifier_core::json::ToJsonValue::to_json_value(#bindings)),*
+                        ])),
+                    ),
+                }
+            }
+            Fields::Named(fields) => {
+                let field_names = fields
+                    .named
+                    .iter()
+                    .map(|field| field.ident.as_ref().unwrap())
+                    .collect::<Vec<_>>();
+                let inserts = match gen_object_inserts(fields, |field_name| quote!(#field_name)) {
+                    Ok(inserts) => inserts,
+                    Err(err) => return err.to_compile_error(),
+                };
+
+                quote! {
+                    #[allow(unused_variables)]
+                    Self::#variant_name { #(#field_names),* } => {
+                        let mut object = ::serde_json::Map::new();
+                        #(#inserts)*
+
+                        (#name, Some(::serde_json::Value::Object(object)))
+                    }
+                }
+            }
+        };
+
+        match_arms.push(match_arm);
+    }
+
+    let enum_schema = with_description(
+        quote! {
+            ::classifier_core::json::tagged_union_schema(#tag, vec![#(#variant_schemas),*])
+        },
+        &input.attrs,
+    );
+
+    quote! {
+        impl #impl_generics ::classifier_core::json::ToJsonValue for #enum_name #ty_generics #where_clause {
+            fn to_json_value(&self) -> ::serde_json::Value {
+                let (variant, payload) = match self {
+                    #(#match_arms)*
+                };
+
+                let mut object = ::serde_json::Map::new();
+                object.insert(#tag.to_string(), ::serde_json::Value::from(variant));
+
+                match payload {
+                    Some(::serde_json::Value::Object(fields)) => object.extend(fields),
+                    Some(value) => {
+                        object.insert("value".to_string(), value);
+                    }
+                    None => {}
+                }
+
+                ::serde_json::Value::Object(object)
+            }
+        }
+
+        impl #schema_impl_generics ::classifier_core::json::JsonSchema for #enum_name #ty_generics #schema_where_clause {
+            fn json_schema() -> ::serde_json::Value {
+                #enum_schema
+            }
+        }
+    }
+}
@@ -1,2 +1,4 @@
 pub mod action_enum;
 pub mod attribute;
+pub mod document;
+pub mod json;
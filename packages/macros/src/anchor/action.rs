@@ -11,7 +11,11 @@ use syn::{
 };
 
 use crate::{
-    action::attribute::get_action_derivations,
+    action::{
+        attribute::get_action_derivations,
+        document::{gen_enum_document_impl, gen_struct_document_impl, DEFAULT_ENUM_TAG},
+        json::{gen_enum_json_impl, gen_struct_json_impl},
+    },
     anchor::util::{
         convert_idl_type_def_to_ts, convert_idl_type_to_syn_type, find_account_index,
         gen_discriminator,
@@ -148,6 +152,9 @@ pub fn gen_anchor_action_declarations(input: DeclareAnchorActions) -> TokenStrea
     let types_mod = gen_types_mod(&idl);
 
     let actions_enum = gen_actions_enum(&input);
+    let actions_enum_item = syn::parse2(actions_enum.clone()).expect("Invalid actions enum");
+    let actions_enum_document_impl = gen_enum_document_impl(&actions_enum_item, DEFAULT_ENUM_TAG);
+    let actions_enum_json_impl = gen_enum_json_impl(&actions_enum_item, DEFAULT_ENUM_TAG);
 
     let derivations = get_action_derivations();
 
@@ -173,6 +180,9 @@ pub fn gen_anchor_action_declarations(input: DeclareAnchorActions) -> TokenStrea
 
         #derivations
         #actions_enum
+
+        #actions_enum_document_impl
+        #actions_enum_json_impl
     };
 
     expanded
@@ -281,6 +291,10 @@ fn gen_action_struct(name: String, idl_ix: &IdlInstruction, ix: &Instruction) ->
         }
     };
 
+    let ix_struct_item = syn::parse2(ix_struct.clone()).expect("Invalid action struct");
+    let document_impl = gen_struct_document_impl(&ix_struct_item);
+    let json_impl = gen_struct_json_impl(&ix_struct_item);
+
     let derivations = get_action_derivations();
 
     quote! {
@@ -288,6 +302,8 @@ fn gen_action_struct(name: String, idl_ix: &IdlInstruction, ix: &Instruction) ->
         #ix_struct
 
         #from_impl
+        #document_impl
+        #json_impl
     }
 }
 
@@ -10,6 +10,11 @@ use proc_macro2::Literal;
 use quote::{format_ident, quote};
 use std::path::{Path, PathBuf};
 
+use crate::action::{
+    document::{gen_enum_document_impl, gen_struct_document_impl, IDL_ENUM_TAG},
+    json::{gen_enum_json_impl, gen_struct_json_impl},
+};
+
 pub fn workspace_dir() -> PathBuf {
     let output = std::process::Command::new(env!("CARGO"))
         .arg("locate-project")
@@ -294,11 +299,38 @@ pub fn convert_idl_type_def_to_ts(
         }
     };
 
+    // Zero copy types may be packed, so their fields cannot be borrowed
+    let value_impls = match (&ty_def.serialization, &ty_def.ty) {
+        (IdlSerialization::Borsh, IdlTypeDefTy::Struct { .. }) => {
+            let item = syn::parse2(ty.clone()).expect("Invalid type definition");
+            let document_impl = gen_struct_document_impl(&item);
+            let json_impl = gen_struct_json_impl(&item);
+
+            quote! {
+                #document_impl
+                #json_impl
+            }
+        }
+        (IdlSerialization::Borsh, IdlTypeDefTy::Enum { .. }) => {
+            let item = syn::parse2(ty.clone()).expect("Invalid type definition");
+            let document_impl = gen_enum_document_impl(&item, IDL_ENUM_TAG);
+            let json_impl = gen_enum_json_impl(&item, IDL_ENUM_TAG);
+
+            quote! {
+                #document_impl
+                #json_impl
+            }
+        }
+        _ => quote!(),
+    };
+
     // #docs
     quote! {
         #attrs
         #repr
         #ty
+
+        #value_impls
     }
 }
 
@@ -1,6 +1,6 @@
 use action::{
     action_enum::{parse_define_action_enum, ActionEnumInput},
-    attribute::{parse_action_attribute, parse_action_attribute_enum},
+    attribute::{parse_action_attribute, parse_action_attribute_enum, ActionEnumArgs},
 };
 use anchor::{
     gen_anchor_action_declarations, gen_anchor_classifier, AnchorClassifierInput,
@@ -19,9 +19,10 @@ pub fn action(_attr: TokenStream, item: TokenStream) -> TokenStream {
 }
 
 #[proc_macro_attribute]
-pub fn action_enum(_attr: TokenStream, item: TokenStream) -> TokenStream {
+pub fn action_enum(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(attr as ActionEnumArgs);
     let input = parse_macro_input!(item as ItemEnum);
-    parse_action_attribute_enum(input).into()
+    parse_action_attribute_enum(args, input).into()
 }
 
 #[proc_macro]